@@ -0,0 +1,8 @@
+// ! The local indexer for vitalicals
+
+use anyhow::Result;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    indexer::run().await
+}
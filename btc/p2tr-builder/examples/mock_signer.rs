@@ -0,0 +1,70 @@
+//! A mock signer process for test the `ProcessSigner`, sign by the xpriv from the args:
+//!
+//! ```bash
+//! mock_signer <xpriv> [--wrong-key]
+//! ```
+//!
+//! With `--wrong-key` it will sign by other key but response the fingerprint of the xpriv.
+
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use bdk::bitcoin::{
+    bip32::{ChildNumber, DerivationPath, ExtendedPrivKey, Fingerprint},
+    secp256k1::{schnorr, Secp256k1, XOnlyPublicKey},
+    sighash::TapSighash,
+    taproot::{TapLeafHash, TapNodeHash},
+};
+
+use btc_p2tr_builder::signer::{serve, Signer, SoftwareSigner};
+
+struct MockSigner {
+    fingerprint: Fingerprint,
+    inner: SoftwareSigner,
+}
+
+impl Signer for MockSigner {
+    fn fingerprint(&self) -> Fingerprint {
+        self.fingerprint
+    }
+
+    fn sign_key_path(
+        &self,
+        path: &DerivationPath,
+        pubkey: &XOnlyPublicKey,
+        merkle_root: Option<TapNodeHash>,
+        sighash: &TapSighash,
+    ) -> Result<schnorr::Signature> {
+        self.inner.sign_key_path(path, pubkey, merkle_root, sighash)
+    }
+
+    fn sign_script_path(
+        &self,
+        path: &DerivationPath,
+        pubkey: &XOnlyPublicKey,
+        leaf_hash: &TapLeafHash,
+        sighash: &TapSighash,
+    ) -> Result<schnorr::Signature> {
+        self.inner.sign_script_path(path, pubkey, leaf_hash, sighash)
+    }
+}
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+
+    let xpriv = args.next().context("need the xpriv")?;
+    let xpriv = ExtendedPrivKey::from_str(&xpriv).context("parse xpriv")?;
+
+    let secp = Secp256k1::new();
+    let fingerprint = xpriv.fingerprint(&secp);
+
+    let xpriv = if args.next().as_deref() == Some("--wrong-key") {
+        xpriv.derive_priv(&secp, &[ChildNumber::from_normal_idx(1)?])?
+    } else {
+        xpriv
+    };
+
+    let signer = MockSigner { fingerprint, inner: SoftwareSigner::new(xpriv) };
+
+    serve(&signer, std::io::stdin().lock(), std::io::stdout().lock())
+}
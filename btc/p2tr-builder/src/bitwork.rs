@@ -0,0 +1,112 @@
+//! Mine the reveal tx to make its txid match the bitwork.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Result};
+use bdk::bitcoin::{hashes::Hash, Sequence, Transaction};
+
+use vital_script_primitives::types::bitwork::Bitwork;
+
+/// The max sequence for nonce, all the nonce will signal the tx can be replaced by BIP125.
+const NONCE_MAX: u32 = 0xFFFFFFFD;
+
+/// The count of txids each thread to calc before update the progress.
+const BATCH_SIZE: u64 = 4096;
+
+/// Find a nonce as the sequence of the input 0, which make the txid match the bitwork.
+///
+/// The sequence is not used as relative lock time in version 1 tx, and it will not change
+/// the tx weight, so the fee will keep the same.
+pub fn mine_reveal_tx(tx: &Transaction, bitwork: &Bitwork) -> Result<Transaction> {
+    if tx.version != 1 {
+        bail!("only support mine the version 1 tx");
+    }
+
+    if tx.input.is_empty() {
+        bail!("the tx to mine need at least one input");
+    }
+
+    let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1) as u32;
+
+    let found = Mutex::new(None);
+    let stop = AtomicBool::new(false);
+    let tried = AtomicU64::new(0);
+    let start = Instant::now();
+
+    println!("mining the reveal tx for bitwork {} by {} threads", bitwork, threads);
+
+    thread::scope(|s| {
+        let handles = (0..threads)
+            .map(|index| {
+                let (found, stop, tried) = (&found, &stop, &tried);
+
+                s.spawn(move || {
+                    let mut tx = tx.clone();
+                    let mut nonce = Some(index);
+                    let mut count = 0;
+
+                    while let Some(n) = nonce.filter(|n| *n <= NONCE_MAX) {
+                        tx.input[0].sequence = Sequence(n);
+
+                        if bitwork.is_match(&tx.txid().to_byte_array()) {
+                            stop.store(true, Ordering::Relaxed);
+                            *found.lock().expect("lock") = Some(tx);
+                            break;
+                        }
+
+                        count += 1;
+                        if count == BATCH_SIZE {
+                            tried.fetch_add(count, Ordering::Relaxed);
+                            count = 0;
+
+                            if stop.load(Ordering::Relaxed) {
+                                break;
+                            }
+                        }
+
+                        nonce = n.checked_add(threads);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut last_print = Instant::now();
+        while !handles.iter().all(|handle| handle.is_finished()) {
+            thread::sleep(Duration::from_millis(100));
+
+            if last_print.elapsed() >= Duration::from_secs(1) {
+                let tried = tried.load(Ordering::Relaxed);
+                let secs = start.elapsed().as_secs_f64();
+                println!(
+                    "mining: tried {} txids in {:.0}s, {:.0} txid/s",
+                    tried,
+                    secs,
+                    tried as f64 / secs
+                );
+
+                last_print = Instant::now();
+            }
+        }
+    });
+
+    let tx = found
+        .into_inner()
+        .expect("lock")
+        .ok_or_else(|| anyhow!("not found nonce for bitwork {}", bitwork))?;
+
+    println!(
+        "mined the reveal tx {} by nonce {} in {:.1}s",
+        tx.txid(),
+        tx.input[0].sequence.0,
+        start.elapsed().as_secs_f64()
+    );
+
+    Ok(tx)
+}
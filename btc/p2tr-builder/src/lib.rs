@@ -1,6 +1,26 @@
 //! Psbt Builder for send p2tr commit tx and reveal tx
-
+//!
+//! The commit tx can have multiple outputs each for a vital script, all of them will be
+//! spent by the reveal tx from the input 0, then the reveal inputs.
+//!
+//! The commit tx is funded by the fee wallet, its outputs are spent by the key of the fee
+//! wallet, the reveal inputs and the outputs are for the resource wallet, so the reveal tx
+//! will be signed by both.
+//!
+//! The txs can be built without sign, for the watch-only wallet, then sign them in other
+//! place by the `signer`.
+//!
+//! The txs are signed by the `Signer`s, the external signer and the wallets which had the
+//! xpriv, so the watch-only wallets can use the external signer to sign.
+
+mod bitwork;
 mod coin_selector;
+mod psbt_file;
+
+pub mod signer;
+
+pub use psbt_file::CommitRevealPsbts;
+pub use signer::Signer;
 
 use std::{collections::BTreeMap, str::FromStr};
 
@@ -8,82 +28,146 @@ use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
 use bdk::{
     bitcoin::{
         absolute,
-        bip32::{DerivationPath, ExtendedPrivKey},
+        bip32::{DerivationPath, Fingerprint},
         hashes::Hash,
-        key::TapTweak,
-        psbt::{self, Input, PartiallySignedTransaction, Psbt, PsbtSighashType},
-        secp256k1::{All, KeyPair, Secp256k1, SecretKey, XOnlyPublicKey},
-        sighash::{self, TapSighash, TapSighashType},
-        taproot::{self, LeafVersion, TapLeafHash, TaprootBuilder, TaprootSpendInfo},
-        Address, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Weight, Witness,
+        psbt::{Input, PartiallySignedTransaction, Psbt, PsbtSighashType},
+        secp256k1::{All, Secp256k1, XOnlyPublicKey},
+        taproot::{LeafVersion, TaprootBuilder, TaprootSpendInfo},
+        Address, OutPoint, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Weight,
+        Witness,
     },
-    psbt::PsbtUtils,
     wallet::AddressIndex,
-    FeeRate, LocalUtxo, SignOptions,
+    FeeRate, LocalUtxo,
 };
 
 use btc_script_builder::InscriptionScriptBuilder;
 
 use client::context::Context;
+use vital_script_primitives::types::bitwork::Bitwork;
 use wallet::Wallet;
 
 pub struct P2trBuilder<'a> {
     fee_rate: Option<FeeRate>,
     wallet: &'a Wallet,
+    fee_wallet: &'a Wallet,
 
     internal_key: XOnlyPublicKey,
-    reveal_script: ScriptBuf,
+    reveal_scripts: Vec<ScriptBuf>,
     secp: Secp256k1<All>,
-    master_xpriv: ExtendedPrivKey,
+    /// The key origin of the internal key, from the fee wallet.
+    fingerprint: Fingerprint,
     derivation_path: DerivationPath,
 
     no_alive_utxos: Vec<OutPoint>,
 
     reveal_inputs: Vec<LocalUtxo>,
     outputs: Vec<(Address, u64)>,
+
+    bitwork: Option<Bitwork>,
+
+    /// The datas of the vital scripts, for record the txs sent.
+    datas: Vec<Vec<u8>>,
+    /// If signal the commit tx can be replaced.
+    replaceable: bool,
+    /// The commit and reveal txs to replace by RBF, and the fee paid by them.
+    replaced: Option<(Txid, Txid, u64)>,
+    /// If the txs only for dry run, the new addresses in the wallets will not be revealed.
+    dry_run: bool,
+
+    signers: Vec<Box<dyn Signer + 'a>>,
+    /// The command of the external signer process, spawn it when sign.
+    signer_command: Option<String>,
 }
 
+/// The amount for the commit outputs except the first one, which just above the dust for p2tr.
+const COMMIT_OUTPUT_MIN_AMOUNT: u64 = 546;
+
+/// The amount for the cpfp anchor output of the reveal tx, just above the dust for p2tr.
+pub const CPFP_ANCHOR_AMOUNT: u64 = 546;
+
+/// The incremental relay fee rate in sat/vB, the replacement need pay it for its vsize.
+const INCREMENTAL_RELAY_FEE_RATE: f32 = 1.0;
+
 impl<'a> P2trBuilder<'a> {
     pub fn new(context: &'a Context, data: Vec<u8>) -> Result<Self> {
+        Self::new_with_datas(context, vec![data])
+    }
+
+    /// Build the txs for multiple vital scripts, each datas will be a script in the reveal tx,
+    /// the scripts will be in the inputs by the order of the datas.
+    pub fn new_with_datas(context: &'a Context, datas: Vec<Vec<u8>>) -> Result<Self> {
+        if datas.is_empty() {
+            bail!("no datas to inscribe");
+        }
+
         let secp = Secp256k1::new();
 
         let wallet = &context.wallet;
+        let fee_wallet = context.fee_wallet();
         let no_alive_utxos =
             [context.utxo_with_resources.clone(), context.used_utxos.clone()].concat();
 
-        let outputs = context
+        let mut outputs = context
             .outputs
             .iter()
             .map(|(to, amount)| {
                 let to_address = if let Some(to) = to.clone() {
                     to
                 } else {
-                    wallet.wallet.get_address(AddressIndex::New).context("new address")?.address
+                    wallet
+                        .wallet
+                        .get_address(new_address_index(context.dry_run))
+                        .context("new address")?
+                        .address
                 };
 
                 Ok((to_address, *amount))
             })
             .collect::<Result<Vec<_>>>()?;
 
-        let internal_key = wallet.derive_x_only_public_key(&secp)?;
-        let reveal_script = InscriptionScriptBuilder::new(data)
-            .into_script_by_key(&internal_key)
-            .context("build script")?;
+        // the anchor is after all the outputs for the vital scripts, so it had no resources.
+        if context.cpfp_anchor {
+            let anchor = wallet
+                .wallet
+                .get_address(new_address_index(context.dry_run))
+                .context("anchor address")?
+                .address;
+            outputs.push((anchor, CPFP_ANCHOR_AMOUNT));
+        }
+
+        let internal_key = fee_wallet.derive_x_only_public_key(&secp)?;
+        let reveal_scripts = datas
+            .iter()
+            .enumerate()
+            .map(|(index, data)| {
+                InscriptionScriptBuilder::new(data.clone())
+                    .into_script_by_key(&internal_key)
+                    .with_context(|| format!("build script {}", index))
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-        let master_xpriv = *wallet.xpriv();
-        let derivation_path = wallet.full_derivation_path().context("get full derivation")?;
+        let fingerprint = fee_wallet.master_fingerprint().context("get master fingerprint")?;
+        let derivation_path = fee_wallet.full_derivation_path().context("get full derivation")?;
 
         Ok(Self {
-            reveal_script,
+            reveal_scripts,
             fee_rate: context.fee_rate.map(FeeRate::from_sat_per_vb),
             wallet,
+            fee_wallet,
             secp,
             internal_key,
-            master_xpriv,
+            fingerprint,
             derivation_path,
             no_alive_utxos,
             reveal_inputs: context.reveal_inputs.clone(),
             outputs,
+            bitwork: None,
+            datas,
+            replaceable: context.replaceable,
+            replaced: None,
+            dry_run: context.dry_run,
+            signers: Vec::new(),
+            signer_command: context.signer.clone(),
         })
     }
 
@@ -95,12 +179,60 @@ impl<'a> P2trBuilder<'a> {
         self.outputs.iter().map(|(_, amount)| amount).sum()
     }
 
+    fn commit_amounts(&self, fee_for_reveal_tx: u64) -> Result<Vec<u64>> {
+        commit_amounts(self.amount() + fee_for_reveal_tx, self.reveal_scripts.len())
+    }
+
     pub fn with_reveal_input(mut self, utxo: LocalUtxo) -> Self {
         self.reveal_inputs.push(utxo);
 
         self
     }
 
+    /// The reveal tx 's txid need match the bitwork.
+    pub fn with_bitwork(mut self, bitwork: Option<Bitwork>) -> Self {
+        self.bitwork = bitwork;
+
+        self
+    }
+
+    /// Replace the commit tx and its reveal tx by RBF, the new commit tx will spend all the
+    /// inputs of the old one, and its fee should be more than the `replaced_fee` paid by the
+    /// old txs with the incremental relay fee for its vsize (BIP 125).
+    pub fn with_replaced(
+        mut self,
+        commit_txid: Txid,
+        reveal_txid: Txid,
+        replaced_fee: u64,
+    ) -> Self {
+        self.replaced = Some((commit_txid, reveal_txid, replaced_fee));
+
+        self
+    }
+
+    pub fn datas(&self) -> &[Vec<u8>] {
+        &self.datas
+    }
+
+    pub fn reveal_inputs(&self) -> &[LocalUtxo] {
+        &self.reveal_inputs
+    }
+
+    pub fn outputs(&self) -> &[(Address, u64)] {
+        &self.outputs
+    }
+
+    pub fn bitwork(&self) -> Option<&Bitwork> {
+        self.bitwork.as_ref()
+    }
+
+    /// Sign the txs by the signer, can set multiple signers for the fee wallet and the wallet.
+    pub fn with_signer(mut self, signer: Box<dyn Signer + 'a>) -> Self {
+        self.signers.push(signer);
+
+        self
+    }
+
     /// TODO: support merge amount to output mod.
     #[allow(dead_code)]
     fn reveal_input_amount(&self) -> u64 {
@@ -113,153 +245,122 @@ impl<'a> P2trBuilder<'a> {
 
     /// Generate a commit tx psbt
     /// For this psbt, we just need use the bdk wallet to build a simple transf to the
-    /// reveal_script 's p2tr address.
+    /// reveal scripts 's p2tr addresses.
     fn generate_commit_psbt(
         &self,
-        commit_script_pubkey: ScriptBuf,
+        commit_script_pubkeys: &[ScriptBuf],
         fee_for_reveal_tx: Option<u64>,
-    ) -> Result<(Psbt, OutPoint)> {
-        let secp = self.secp();
-        let bdk_wallet = &self.wallet.wallet;
-        let internal_key = self.internal_key;
-        let script_p2tr = self.reveal_script.to_v1_p2tr(secp, internal_key);
+    ) -> Result<(Psbt, Vec<OutPoint>)> {
+        let bdk_wallet = &self.fee_wallet.wallet;
 
-        // the total amount send to output, need the amount and fee for next tx.
+        // the total amount send to outputs, need the amount and fee for next tx.
         // TODO: support merge amount to output mod.
-        let amount_to_trans = self.amount() + fee_for_reveal_tx.unwrap_or_default();
+        let amounts = self.commit_amounts(fee_for_reveal_tx.unwrap_or_default())?;
 
         let mut builder = bdk_wallet
             .build_tx()
             .coin_selection(coin_selector::CoinSelector::new(Vec::new()));
         builder.ordering(bdk::wallet::tx_builder::TxOrdering::Untouched);
 
-        builder.set_recipients(vec![(commit_script_pubkey, amount_to_trans)]);
+        builder.set_recipients(commit_script_pubkeys.iter().cloned().zip(amounts).collect());
+
+        let mut unspendable = self.no_alive_utxos.clone();
+
+        if let Some((commit_txid, reveal_txid, _)) = self.replaced {
+            let replaced_commit = bdk_wallet
+                .get_tx(&commit_txid, true)
+                .context("get replaced commit tx")?
+                .and_then(|details| details.transaction)
+                .ok_or_else(|| anyhow!("not found the commit tx {} in wallet", commit_txid))?;
+
+            // spend all the inputs of the replaced commit tx, so it will be replaced.
+            let replaced_inputs = replaced_commit
+                .input
+                .iter()
+                .map(|input| input.previous_output)
+                .collect::<Vec<_>>();
+            builder.add_utxos(&replaced_inputs).context("add replaced commit inputs")?;
+            unspendable.retain(|outpoint| !replaced_inputs.contains(outpoint));
+
+            // the outputs of the replaced txs will not be exist.
+            for utxo in bdk_wallet.list_unspent().context("list unspent")? {
+                if utxo.outpoint.txid == commit_txid || utxo.outpoint.txid == reveal_txid {
+                    unspendable.push(utxo.outpoint);
+                }
+            }
+        }
+
         // Note we not use this utxos, because it will cost the resource.
-        builder.unspendable(self.no_alive_utxos.clone());
+        builder.unspendable(unspendable);
+
+        // the bdk will reveal a new change address if not set.
+        if self.dry_run {
+            let change = bdk_wallet
+                .get_internal_address(new_address_index(self.dry_run))
+                .context("change address")?;
+            builder.drain_to(change.script_pubkey());
+        }
+
+        if self.replaceable || self.replaced.is_some() {
+            builder.enable_rbf();
+        }
 
         if let Some(fee_rate) = &self.fee_rate {
             builder.fee_rate(*fee_rate);
         }
 
-        let (mut psbt, _details) = builder.finish().context("build tx failed")?;
-
-        let index = {
-            let mut res = 0;
-
-            let outputs = &psbt.unsigned_tx.output;
-            for (index, output) in outputs.iter().enumerate() {
-                if script_p2tr.to_string() == output.script_pubkey.to_string() {
-                    res = index;
-                }
-            }
+        let (psbt, _details) = builder.finish().context("build tx failed")?;
 
-            res as u32
-        };
-
-        // Sign and finalize the PSBT with the signing wallet
-        bdk_wallet.sign(&mut psbt, SignOptions::default())?;
-        bdk_wallet.finalize_psbt(&mut psbt, SignOptions::default())?;
-
-        // Broadcast the transaction
+        // the txid will not be changed after signed, so the reveal tx can spend it.
         let raw_transaction = psbt.clone().extract_tx();
-        let txid = raw_transaction.txid();
+        let commit_outpoints = commit_outpoints(&raw_transaction, commit_script_pubkeys)?;
 
         for i in self.reveal_inputs.iter().enumerate() {
             println!("reveal input {} : for {}", i.0, i.1.outpoint);
         }
         println!("commit tx {}", serde_json::to_string_pretty(&raw_transaction).unwrap());
 
-        Ok((psbt, OutPoint::new(txid, index)))
+        Ok((psbt, commit_outpoints))
     }
 
     fn generate_reveal_psbt(
         &self,
-        commit_outpoint: OutPoint,
-        commit_script_pubkey: ScriptBuf,
-        taproot_spend_info: &TaprootSpendInfo,
-        send_amount: u64,
+        commit_outpoints: &[OutPoint],
+        commit_script_pubkeys: &[ScriptBuf],
+        taproot_spend_infos: &[TaprootSpendInfo],
+        send_amounts: &[u64],
+        bitwork: Option<&Bitwork>,
     ) -> Result<Psbt> {
-        let secp = self.secp();
-
-        let output = self
-            .outputs
-            .iter()
-            .map(|(to, amount)| TxOut { value: *amount, script_pubkey: to.script_pubkey() })
-            .collect::<Vec<_>>();
-
-        let mut input = vec![TxIn {
-            previous_output: commit_outpoint,
-            script_sig: ScriptBuf::new(),
-            sequence: Sequence(0xFFFFFFFD),
-            witness: Witness::default(),
-        }];
-
-        for resource_input in self.reveal_inputs.iter() {
-            input.push(TxIn {
-                previous_output: resource_input.outpoint,
-                script_sig: ScriptBuf::new(),
-                sequence: Sequence(0xFFFFFFFD),
-                witness: Witness::default(),
-            });
-        }
+        let next_tx = reveal_tx(commit_outpoints, &self.reveal_inputs, &self.outputs);
 
-        let next_tx =
-            Transaction { version: 1, lock_time: absolute::LockTime::ZERO, input, output };
+        let next_tx = match bitwork {
+            Some(bitwork) => {
+                bitwork::mine_reveal_tx(&next_tx, bitwork).context("mine reveal tx")?
+            }
+            None => next_tx,
+        };
 
         let mut psbt = PartiallySignedTransaction::from_unsigned_tx(next_tx)?;
 
         // clean the inputs, will push by update func
         psbt.inputs = Vec::with_capacity(psbt.unsigned_tx.input.len());
 
-        self.update_psbt_taproot_input(
-            &mut psbt,
-            secp,
-            taproot_spend_info,
-            commit_script_pubkey,
-            send_amount,
-        )
-        .context("update_psbt_taproot_input")?;
+        for (index, reveal_script) in self.reveal_scripts.iter().enumerate() {
+            self.update_psbt_taproot_input(
+                &mut psbt,
+                reveal_script,
+                &taproot_spend_infos[index],
+                commit_script_pubkeys[index].clone(),
+                send_amounts[index],
+            )
+            .with_context(|| format!("update_psbt_taproot_input {}", index))?;
+        }
 
         self.update_psbt_resource_inputs(&mut psbt)
             .context("update_psbt_resource_inputs")?;
 
         psbt.version = 1;
 
-        // println!("psbt input {}", serde_json::to_string_pretty(&psbt.unsigned_tx.input).unwrap());
-        // println!("psbt input update {}", serde_json::to_string_pretty(&psbt.inputs).unwrap());
-
-        self.sign_psbt_inputs(&mut psbt, secp).context("sign_psbt_inputs")?;
-
-        println!(
-            "psbt input sign_psbt_inputs {}",
-            serde_json::to_string_pretty(&psbt.inputs).unwrap()
-        );
-
-        psbt.inputs.iter_mut().for_each(|input| {
-            let mut script_witness: Witness = Witness::new();
-            if let Some(tap_key_sig) = input.tap_key_sig {
-                script_witness.push(tap_key_sig.to_vec());
-            }
-            for (_, signature) in input.tap_script_sigs.iter() {
-                script_witness.push(signature.to_vec());
-            }
-            for (control_block, (script, _)) in input.tap_scripts.iter() {
-                script_witness.push(script.to_bytes());
-                script_witness.push(control_block.serialize());
-            }
-            input.final_script_witness = Some(script_witness);
-
-            // Clear all the data fields as per the spec.
-            input.partial_sigs = BTreeMap::new();
-            input.sighash_type = None;
-            input.redeem_script = None;
-            input.witness_script = None;
-            input.bip32_derivation = BTreeMap::new();
-            input.tap_script_sigs = BTreeMap::new();
-            input.tap_scripts = BTreeMap::new();
-            input.tap_key_sig = None;
-        });
-
         Ok(psbt)
     }
 
@@ -273,28 +374,61 @@ impl<'a> P2trBuilder<'a> {
         Ok(taproot_spend_info)
     }
 
-    pub fn build(self) -> Result<(Psbt, Psbt)> {
-        let taproot_spend_info = self
-            .taproot_spend_info(self.reveal_script.clone())
-            .context("taproot_spend_info for vital script input")?;
+    /// Build the commit and reveal txs then sign them by the signers.
+    pub fn build(mut self) -> Result<(Psbt, Psbt)> {
+        let (mut commit_psbt, mut reveal_psbt) = self.build_unsigned()?;
 
-        let commit_script_pubkey = ScriptBuf::new_v1_p2tr(
-            self.secp(),
-            taproot_spend_info.internal_key(),
-            taproot_spend_info.merkle_root(),
-        );
+        self.append_default_signers()?;
+
+        for signer in self.signers.iter() {
+            signer::sign_psbt(&mut commit_psbt, signer.as_ref(), self.secp())
+                .context("sign commit")?;
+            signer::sign_psbt(&mut reveal_psbt, signer.as_ref(), self.secp())
+                .context("sign reveal")?;
+        }
+
+        signer::finalize_psbt(&mut commit_psbt).context("finalize commit")?;
+        signer::finalize_psbt(&mut reveal_psbt).context("finalize reveal")?;
+
+        Ok((commit_psbt, reveal_psbt))
+    }
+
+    /// Append the external signer if had, and the xprivs of the fee wallet and the wallet if
+    /// they are not watch-only.
+    fn append_default_signers(&mut self) -> Result<()> {
+        let mut signers = signer::signers_for_wallets(
+            self.signer_command.take().as_deref(),
+            &[self.fee_wallet, self.wallet],
+        )?;
+        self.signers.append(&mut signers);
 
-        // In first time, we need fee to be calculated.
+        Ok(())
+    }
+
+    /// Build the commit and reveal txs without sign, the reveal tx spend the commit tx by
+    /// its unsigned txid, the txid will not be changed after signed.
+    pub fn build_unsigned(&self) -> Result<(Psbt, Psbt)> {
+        let (taproot_spend_infos, commit_script_pubkeys) = self.commit_scripts()?;
+
+        // In first time, we need fee to be calculated, the bitwork and the amounts will not
+        // change the fee.
+        let placeholder_outpoints = (0..self.reveal_scripts.len())
+            .map(|index| OutPoint::new(Txid::all_zeros(), index as u32))
+            .collect::<Vec<_>>();
         let reveal_psbt = self
             .generate_reveal_psbt(
-                OutPoint::new(Txid::all_zeros(), 0),
-                commit_script_pubkey.clone(),
-                &taproot_spend_info.clone(),
-                self.amount(),
+                &placeholder_outpoints,
+                &commit_script_pubkeys,
+                &taproot_spend_infos,
+                &vec![self.amount(); self.reveal_scripts.len()],
+                None,
             )
             .context("generate_reveal_psbt")?;
 
-        // fee for reveal
+        // fee for reveal, the placeholder signatures have the same size to the real ones.
+        let mut reveal_psbt = reveal_psbt;
+        signer::sign_psbt_with_placeholder(&mut reveal_psbt).context("placeholder sign")?;
+        signer::finalize_psbt(&mut reveal_psbt).context("placeholder finalize")?;
         let reveal_tx = reveal_psbt.extract_tx();
         let fee_rate = self.fee_rate.unwrap_or_default();
 
@@ -310,49 +444,132 @@ impl<'a> P2trBuilder<'a> {
         let fee_for_reveal =
             fee_rate.fee_wu(reveal_tx.weight()) + fee_rate.fee_wu(Weight::from_wu(2));
 
-        let (commit_psbt, commit_outpoint) = self
-            .generate_commit_psbt(commit_script_pubkey.clone(), Some(fee_for_reveal))
+        let (commit_psbt, commit_outpoints) = self
+            .generate_commit_psbt(&commit_script_pubkeys, Some(fee_for_reveal))
             .context("generate_commit_psbt")?;
 
-        let amount = self.amount() + fee_for_reveal;
+        if let Some((commit_txid, _, replaced_fee)) = self.replaced {
+            check_replacement_fee(&commit_psbt, replaced_fee)
+                .with_context(|| format!("replace the commit tx {}", commit_txid))?;
+        }
+
+        let amounts = self.commit_amounts(fee_for_reveal)?;
 
         let reveal_psbt = self
             .generate_reveal_psbt(
-                commit_outpoint,
-                commit_script_pubkey,
-                &taproot_spend_info,
-                amount,
+                &commit_outpoints,
+                &commit_script_pubkeys,
+                &taproot_spend_infos,
+                &amounts,
+                self.bitwork.as_ref(),
             )
             .context("generate_reveal_psbt")?;
 
         Ok((commit_psbt, reveal_psbt))
     }
 
-    /// this will push taproot input into index 0 for vital script
+    /// The taproot spend infos and the script pubkeys of the commit outputs, by the order of
+    /// the vital scripts.
+    fn commit_scripts(&self) -> Result<(Vec<TaprootSpendInfo>, Vec<ScriptBuf>)> {
+        let taproot_spend_infos = self
+            .reveal_scripts
+            .iter()
+            .map(|script| self.taproot_spend_info(script.clone()))
+            .collect::<Result<Vec<_>>>()
+            .context("taproot_spend_info for vital script input")?;
+
+        let commit_script_pubkeys = taproot_spend_infos
+            .iter()
+            .map(|info| {
+                ScriptBuf::new_v1_p2tr(self.secp(), info.internal_key(), info.merkle_root())
+            })
+            .collect::<Vec<_>>();
+
+        Ok((taproot_spend_infos, commit_script_pubkeys))
+    }
+
+    /// Build the recover tx which spend the commit outputs back to the fee wallet by the script
+    /// path, for the reveal tx can not be sent, then sign it by the signers.
+    pub fn build_recover(mut self, commit_tx: &Transaction) -> Result<Psbt> {
+        let mut psbt = self.build_recover_unsigned(commit_tx)?;
+
+        self.append_default_signers()?;
+
+        for signer in self.signers.iter() {
+            signer::sign_psbt(&mut psbt, signer.as_ref(), self.secp()).context("sign recover")?;
+        }
+
+        signer::finalize_psbt(&mut psbt).context("finalize recover")?;
+
+        Ok(psbt)
+    }
+
+    /// Build the recover tx without sign, the commit outputs are found by the scripts of the
+    /// datas, so the datas should be same as the commit tx built.
+    pub fn build_recover_unsigned(&self, commit_tx: &Transaction) -> Result<Psbt> {
+        let (taproot_spend_infos, commit_script_pubkeys) = self.commit_scripts()?;
+
+        let to = self
+            .fee_wallet
+            .wallet
+            .get_address(new_address_index(self.dry_run))
+            .context("new address")?
+            .address;
+
+        let (tx, amounts) = recover_tx(commit_tx, &commit_script_pubkeys, &to)?;
+        let total = tx.output[0].value;
+        let mut psbt = PartiallySignedTransaction::from_unsigned_tx(tx)?;
+
+        // clean the inputs, will push by update func
+        psbt.inputs = Vec::with_capacity(psbt.unsigned_tx.input.len());
+
+        for (index, reveal_script) in self.reveal_scripts.iter().enumerate() {
+            self.update_psbt_taproot_input(
+                &mut psbt,
+                reveal_script,
+                &taproot_spend_infos[index],
+                commit_script_pubkeys[index].clone(),
+                amounts[index],
+            )
+            .with_context(|| format!("update_psbt_taproot_input {}", index))?;
+        }
+
+        // the placeholder signatures have the same size to the real ones.
+        let mut placeholder = psbt.clone();
+        signer::sign_psbt_with_placeholder(&mut placeholder).context("placeholder sign")?;
+        signer::finalize_psbt(&mut placeholder).context("placeholder finalize")?;
+        let fee = self.fee_rate.unwrap_or_default().fee_wu(placeholder.extract_tx().weight());
+
+        psbt.unsigned_tx.output[0].value = recover_amount(total, fee)?;
+
+        Ok(psbt)
+    }
+
+    /// this will push taproot input for the vital script, from index 0
     fn update_psbt_taproot_input(
         &self,
         psbt: &mut PartiallySignedTransaction,
-        secp: &Secp256k1<All>,
+        reveal_script: &ScriptBuf,
         taproot_spend_info: &TaprootSpendInfo,
         commit_script_pubkey: ScriptBuf,
         send_amount: u64,
     ) -> Result<()> {
         let internal_key = self.internal_key;
 
-        let leaf_hash = self.reveal_script.tapscript_leaf_hash();
+        let leaf_hash = reveal_script.tapscript_leaf_hash();
         let mut origins = BTreeMap::new();
         origins.insert(
             internal_key,
-            (vec![leaf_hash], (self.master_xpriv.fingerprint(secp), self.derivation_path.clone())),
+            (vec![leaf_hash], (self.fingerprint, self.derivation_path.clone())),
         );
 
         let ty = PsbtSighashType::from_str("SIGHASH_ALL")?;
         let mut tap_scripts = BTreeMap::new();
         tap_scripts.insert(
             taproot_spend_info
-                .control_block(&(self.reveal_script.clone(), LeafVersion::TapScript))
+                .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
                 .unwrap(),
-            (self.reveal_script.clone(), LeafVersion::TapScript),
+            (reveal_script.clone(), LeafVersion::TapScript),
         );
 
         let input = Input {
@@ -372,11 +589,13 @@ impl<'a> P2trBuilder<'a> {
         Ok(())
     }
 
-    /// this will push taproot input from index 1 for vital script
+    /// this will push the resource inputs after the vital script inputs
     fn update_psbt_resource_inputs(&self, psbt: &mut PartiallySignedTransaction) -> Result<()> {
-        for input_index in 1..psbt.unsigned_tx.input.len() {
+        let scripts_count = self.reveal_scripts.len();
+
+        for input_index in scripts_count..psbt.unsigned_tx.input.len() {
             let input = &psbt.unsigned_tx.input[input_index];
-            let reveal_input = &self.reveal_inputs[input_index - 1];
+            let reveal_input = &self.reveal_inputs[input_index - scripts_count];
 
             assert_eq!(input.previous_output, reveal_input.outpoint);
 
@@ -392,127 +611,371 @@ impl<'a> P2trBuilder<'a> {
 
         Ok(())
     }
+}
 
-    fn sighash(
-        psbt: &PartiallySignedTransaction,
-        input_index: usize,
-        extra: Option<taproot::TapLeafHash>,
-    ) -> Result<(TapSighash, TapSighashType)> {
-        if input_index >= psbt.inputs.len() || input_index >= psbt.unsigned_tx.input.len() {
-            bail!("InputIndexOutOfRange");
-        }
+/// The index to get the new address, for dry run use the last unused one, so the addresses
+/// will not be revealed by each run.
+fn new_address_index(dry_run: bool) -> AddressIndex {
+    if dry_run {
+        AddressIndex::LastUnused
+    } else {
+        AddressIndex::New
+    }
+}
 
-        let psbt_input = &psbt.inputs[input_index];
-
-        let sighash_type = psbt_input
-            .sighash_type
-            .unwrap_or_else(|| TapSighashType::Default.into())
-            .taproot_hash_ty()
-            .map_err(|_| anyhow!("InvalidSighash"))?;
-        let witness_utxos =
-            (0..psbt.inputs.len()).map(|i| psbt.get_utxo_for(i)).collect::<Vec<_>>();
-        let mut all_witness_utxos = vec![];
-
-        let mut cache = sighash::SighashCache::new(&psbt.unsigned_tx);
-        let is_anyone_can_pay = psbt::PsbtSighashType::from(sighash_type).to_u32() & 0x80 != 0;
-        let prevouts = if is_anyone_can_pay {
-            sighash::Prevouts::One(
-                input_index,
-                witness_utxos[input_index].as_ref().ok_or(anyhow!("MissingWitnessUtxo"))?,
-            )
-        } else if witness_utxos.iter().all(Option::is_some) {
-            all_witness_utxos.extend(witness_utxos.iter().filter_map(|x| x.as_ref()));
-            sighash::Prevouts::All(&all_witness_utxos)
-        } else {
-            return Err(anyhow!("MissingWitnessUtxo"));
-        };
+/// The replacement should pay more fee than the replaced txs, and the incremental relay fee for
+/// its vsize, or it will be rejected (BIP 125 rule 3 and 4).
+fn check_replacement_fee(psbt: &Psbt, replaced_fee: u64) -> Result<()> {
+    let fee = psbt_fee(psbt)?;
+
+    // the placeholder signatures have the same size to the real ones.
+    let mut placeholder = psbt.clone();
+    signer::sign_psbt_with_placeholder(&mut placeholder).context("placeholder sign")?;
+    signer::finalize_psbt(&mut placeholder).context("placeholder finalize")?;
+    let vsize = placeholder.extract_tx().vsize();
+
+    let min_fee = replaced_fee + (INCREMENTAL_RELAY_FEE_RATE * vsize as f32).ceil() as u64;
+    if fee < min_fee {
+        bail!(
+            "the fee {} sats should be at least {} sats, the replaced txs paid {} sats, need a higher fee rate",
+            fee,
+            min_fee,
+            replaced_fee
+        );
+    }
 
-        // Assume no OP_CODESEPARATOR
-        let extra = extra.map(|leaf_hash| (leaf_hash, 0xFFFFFFFF));
+    Ok(())
+}
 
-        Ok((
-            cache.taproot_signature_hash(input_index, &prevouts, None, extra, sighash_type)?,
-            sighash_type,
-        ))
+/// The fee of the psbt by the utxos of its inputs.
+fn psbt_fee(psbt: &Psbt) -> Result<u64> {
+    let mut input_amount = 0;
+    for (txin, input) in psbt.unsigned_tx.input.iter().zip(psbt.inputs.iter()) {
+        let outpoint = txin.previous_output;
+        input_amount += match (&input.witness_utxo, &input.non_witness_utxo) {
+            (Some(utxo), _) => utxo.value,
+            (None, Some(tx)) => {
+                tx.output
+                    .get(outpoint.vout as usize)
+                    .ok_or_else(|| anyhow!("not found the output {}", outpoint))?
+                    .value
+            }
+            (None, None) => bail!("not found the utxo of the input {}", outpoint),
+        };
     }
 
-    fn sign_psbt_inputs(
-        &self,
-        psbt: &mut PartiallySignedTransaction,
-        secp: &Secp256k1<All>,
-    ) -> Result<()> {
-        // Sign and finalize the PSBT with the signing wallet
-        let internal_key = self.internal_key;
+    let output_amount = psbt.unsigned_tx.output.iter().map(|output| output.value).sum::<u64>();
 
-        // SIGNER
-        for (leaf_hashes, (_, path)) in psbt.inputs[0].tap_key_origins.clone().values() {
-            let secret_key = self.master_xpriv.derive_priv(secp, path)?.to_priv().inner;
-
-            for lh in leaf_hashes {
-                let (hash, hash_ty) = Self::sighash(psbt, 0, Some(*lh)).context("sighash")?;
-
-                sign_psbt_taproot(
-                    &secret_key,
-                    internal_key,
-                    Some(*lh),
-                    &mut psbt.inputs[0],
-                    hash,
-                    hash_ty,
-                    secp,
-                );
-            }
+    input_amount
+        .checked_sub(output_amount)
+        .ok_or_else(|| anyhow!("the outputs of psbt more than the inputs"))
+}
+
+/// The unsigned recover tx, spend the commit outputs found by the script pubkeys, all the
+/// amount send to `to`, return it with the amounts of the commit outputs.
+fn recover_tx(
+    commit_tx: &Transaction,
+    commit_script_pubkeys: &[ScriptBuf],
+    to: &Address,
+) -> Result<(Transaction, Vec<u64>)> {
+    let commit_outpoints = commit_outpoints(commit_tx, commit_script_pubkeys)
+        .with_context(|| format!("the commit tx {}", commit_tx.txid()))?;
+
+    let amounts = commit_outpoints
+        .iter()
+        .map(|outpoint| commit_tx.output[outpoint.vout as usize].value)
+        .collect::<Vec<_>>();
+    let total = amounts.iter().sum::<u64>();
+
+    let input = commit_outpoints
+        .iter()
+        .map(|commit_outpoint| TxIn {
+            previous_output: *commit_outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence(0xFFFFFFFD),
+            witness: Witness::default(),
+        })
+        .collect::<Vec<_>>();
+    let output = vec![TxOut { value: total, script_pubkey: to.script_pubkey() }];
+
+    let tx = Transaction { version: 1, lock_time: absolute::LockTime::ZERO, input, output };
+
+    Ok((tx, amounts))
+}
+
+/// The amount of the recover tx after paid the fee, it should not be dust.
+fn recover_amount(total: u64, fee: u64) -> Result<u64> {
+    total
+        .checked_sub(fee)
+        .filter(|amount| *amount >= COMMIT_OUTPUT_MIN_AMOUNT)
+        .ok_or_else(|| {
+            anyhow!("the commit outputs amount {} is not enough for the fee {}", total, fee)
+        })
+}
+
+/// The unsigned reveal tx, spend the commit outputs then the resource inputs.
+fn reveal_tx(
+    commit_outpoints: &[OutPoint],
+    reveal_inputs: &[LocalUtxo],
+    outputs: &[(Address, u64)],
+) -> Transaction {
+    let output = outputs
+        .iter()
+        .map(|(to, amount)| TxOut { value: *amount, script_pubkey: to.script_pubkey() })
+        .collect::<Vec<_>>();
+
+    let input = commit_outpoints
+        .iter()
+        .chain(reveal_inputs.iter().map(|utxo| &utxo.outpoint))
+        .map(|outpoint| TxIn {
+            previous_output: *outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence(0xFFFFFFFD),
+            witness: Witness::default(),
+        })
+        .collect::<Vec<_>>();
+
+    Transaction { version: 1, lock_time: absolute::LockTime::ZERO, input, output }
+}
+
+/// The index of the reveal tx 's output which can be spent by the cpfp child tx, it should be
+/// in the wallet and had no resources, such as the cpfp anchor.
+pub fn cpfp_output_index(
+    reveal_tx: &Transaction,
+    resource_outpoints: &[OutPoint],
+    is_mine: impl Fn(&Script) -> Result<bool>,
+) -> Result<Option<u32>> {
+    let txid = reveal_tx.txid();
+
+    for (index, output) in reveal_tx.output.iter().enumerate() {
+        if resource_outpoints.contains(&OutPoint::new(txid, index as u32)) {
+            continue;
         }
 
-        for input_index in 1..psbt.inputs.len() {
-            for (key, (_, (_, path))) in &psbt.inputs[input_index].tap_key_origins.clone() {
-                let (hash, hash_ty) = Self::sighash(psbt, input_index, None).context("sighash")?;
-
-                let secret_key = self.master_xpriv.derive_priv(secp, path)?.to_priv().inner;
-
-                sign_psbt_taproot(
-                    &secret_key,
-                    *key,
-                    None,
-                    &mut psbt.inputs[input_index],
-                    hash,
-                    hash_ty,
-                    secp,
-                );
-            }
+        if is_mine(&output.script_pubkey)? {
+            return Ok(Some(index as u32));
         }
+    }
 
-        Ok(())
+    Ok(None)
+}
+
+/// The amounts for each commit output, the first one carry all the amount except the min
+/// amounts for the others.
+fn commit_amounts(total: u64, count: usize) -> Result<Vec<u64>> {
+    let others = COMMIT_OUTPUT_MIN_AMOUNT * (count as u64 - 1);
+
+    if total < others + COMMIT_OUTPUT_MIN_AMOUNT {
+        bail!("the amount {} is not enough for {} commit outputs", total, count);
     }
+
+    let mut res = vec![total - others];
+    res.resize(count, COMMIT_OUTPUT_MIN_AMOUNT);
+
+    Ok(res)
 }
 
-// Calling this with `leaf_hash` = `None` will sign for key-spend
-fn sign_psbt_taproot(
-    secret_key: &SecretKey,
-    pubkey: XOnlyPublicKey,
-    leaf_hash: Option<TapLeafHash>,
-    psbt_input: &mut Input,
-    hash: TapSighash,
-    hash_ty: TapSighashType,
-    secp: &Secp256k1<All>,
-) {
-    use bdk::bitcoin::secp256k1::Message;
-
-    let keypair = KeyPair::from_seckey_slice(secp, secret_key.as_ref()).unwrap();
-    let keypair = match leaf_hash {
-        None => keypair.tap_tweak(secp, psbt_input.tap_merkle_root).to_inner(),
-        Some(_) => keypair, // no tweak for script spend
-    };
-
-    let msg = &Message::from(hash);
-    let sig = secp.sign_schnorr(msg, &keypair);
-    secp.verify_schnorr(&sig, msg, &XOnlyPublicKey::from_keypair(&keypair).0)
-        .expect("invalid or corrupted schnorr signature");
-
-    let final_signature = taproot::Signature { sig, hash_ty };
-
-    if let Some(lh) = leaf_hash {
-        psbt_input.tap_script_sigs.insert((pubkey, lh), final_signature);
-    } else {
-        psbt_input.tap_key_sig = Some(final_signature);
+/// The outpoints of the commit outputs by the order of the script pubkeys, the outputs may be
+/// not in order for the change.
+fn commit_outpoints(
+    commit_tx: &Transaction,
+    script_pubkeys: &[ScriptBuf],
+) -> Result<Vec<OutPoint>> {
+    let txid = commit_tx.txid();
+
+    script_pubkeys
+        .iter()
+        .map(|script_pubkey| {
+            commit_tx
+                .output
+                .iter()
+                .position(|output| &output.script_pubkey == script_pubkey)
+                .map(|index| OutPoint::new(txid, index as u32))
+                .ok_or_else(|| anyhow!("not found the commit output for {}", script_pubkey))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk::bitcoin::Network;
+
+    use super::*;
+
+    /// The script pubkeys for the commit outputs of the vital scripts.
+    fn commit_script_pubkeys(count: u8) -> Vec<ScriptBuf> {
+        let secp = Secp256k1::new();
+        let xpriv = bdk::bitcoin::bip32::ExtendedPrivKey::new_master(Network::Regtest, &[7_u8; 32])
+            .expect("xpriv");
+        let internal_key = xpriv.to_keypair(&secp).x_only_public_key().0;
+
+        (0..count)
+            .map(|index| {
+                let script = InscriptionScriptBuilder::new(vec![index])
+                    .into_script_by_key(&internal_key)
+                    .expect("script");
+                let info = TaprootBuilder::new()
+                    .add_leaf(0, script)
+                    .expect("add leaf")
+                    .finalize(&secp, internal_key)
+                    .expect("finalize");
+
+                ScriptBuf::new_v1_p2tr(&secp, info.internal_key(), info.merkle_root())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_commit_amounts_for_multiple_outputs() {
+        assert_eq!(commit_amounts(10000, 1).expect("amounts"), vec![10000]);
+        assert_eq!(
+            commit_amounts(10000, 3).expect("amounts"),
+            vec![
+                10000 - 2 * COMMIT_OUTPUT_MIN_AMOUNT,
+                COMMIT_OUTPUT_MIN_AMOUNT,
+                COMMIT_OUTPUT_MIN_AMOUNT
+            ]
+        );
+
+        // the first output also need the min amount.
+        assert!(commit_amounts(COMMIT_OUTPUT_MIN_AMOUNT * 3, 3).is_ok());
+        assert!(commit_amounts(COMMIT_OUTPUT_MIN_AMOUNT * 3 - 1, 3).is_err());
+    }
+
+    #[test]
+    fn test_commit_outpoints_for_multiple_outputs() {
+        let count = 5;
+        let script_pubkeys = commit_script_pubkeys(count);
+        let amounts = commit_amounts(100000, count as usize).expect("amounts");
+
+        // the change output is in the middle, and the commit outputs are not in order.
+        let change = TxOut { value: 5000, script_pubkey: ScriptBuf::new() };
+        let mut output = script_pubkeys
+            .iter()
+            .zip(amounts)
+            .map(|(script_pubkey, value)| TxOut { value, script_pubkey: script_pubkey.clone() })
+            .collect::<Vec<_>>();
+        output.insert(2, change);
+        output.swap(0, 4);
+
+        let commit_tx =
+            Transaction { version: 1, lock_time: absolute::LockTime::ZERO, input: vec![], output };
+        let txid = commit_tx.txid();
+
+        let outpoints = commit_outpoints(&commit_tx, &script_pubkeys).expect("outpoints");
+        assert_eq!(outpoints.len(), count as usize);
+        for (outpoint, script_pubkey) in outpoints.iter().zip(script_pubkeys.iter()) {
+            assert_eq!(outpoint.txid, txid);
+            assert_eq!(&commit_tx.output[outpoint.vout as usize].script_pubkey, script_pubkey);
+        }
+        assert_eq!(outpoints.iter().map(|o| o.vout).collect::<Vec<_>>(), vec![4, 1, 3, 0, 5]);
+
+        // all the commit outputs should be in the tx.
+        let missing = commit_script_pubkeys(count + 1);
+        assert!(commit_outpoints(&commit_tx, &missing).is_err());
+    }
+
+    #[test]
+    fn test_recover_tx_should_spend_the_commit_outputs() {
+        let count = 3;
+        let script_pubkeys = commit_script_pubkeys(count);
+        let to = Address::from_script(&script_pubkeys[0], Network::Regtest).expect("address");
+
+        // the change output is the first, and the commit outputs are not in order.
+        let mut output = script_pubkeys
+            .iter()
+            .zip([10000, 600, 700])
+            .map(|(script_pubkey, value)| TxOut { value, script_pubkey: script_pubkey.clone() })
+            .collect::<Vec<_>>();
+        output.swap(0, 2);
+        output.insert(0, TxOut { value: 5000, script_pubkey: ScriptBuf::new() });
+
+        let commit_tx =
+            Transaction { version: 1, lock_time: absolute::LockTime::ZERO, input: vec![], output };
+        let txid = commit_tx.txid();
+
+        let (tx, amounts) = recover_tx(&commit_tx, &script_pubkeys, &to).expect("recover tx");
+        assert_eq!(amounts, vec![10000, 600, 700]);
+        assert_eq!(
+            tx.input.iter().map(|input| input.previous_output).collect::<Vec<_>>(),
+            vec![OutPoint::new(txid, 3), OutPoint::new(txid, 2), OutPoint::new(txid, 1)]
+        );
+        assert_eq!(tx.output, vec![TxOut { value: 11300, script_pubkey: to.script_pubkey() }]);
+
+        // the commit tx not for the scripts.
+        let others = commit_script_pubkeys(count + 1);
+        assert!(recover_tx(&commit_tx, &others, &to).is_err());
+    }
+
+    #[test]
+    fn test_recover_amount_should_not_be_dust() {
+        assert_eq!(recover_amount(10000, 300).expect("amount"), 9700);
+        assert_eq!(
+            recover_amount(300 + COMMIT_OUTPUT_MIN_AMOUNT, 300).expect("amount"),
+            COMMIT_OUTPUT_MIN_AMOUNT
+        );
+
+        assert!(recover_amount(300 + COMMIT_OUTPUT_MIN_AMOUNT - 1, 300).is_err(), "dust");
+        assert!(recover_amount(200, 300).is_err(), "not enough for the fee");
+    }
+
+    #[test]
+    fn test_psbt_fee() {
+        let utxo = |value| TxOut { value, script_pubkey: ScriptBuf::new() };
+        let prev_tx = Transaction {
+            version: 1,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![],
+            output: vec![utxo(1000), utxo(2000)],
+        };
+
+        let tx = reveal_tx(
+            &[OutPoint::new(Txid::all_zeros(), 0), OutPoint::new(prev_tx.txid(), 1)],
+            &[],
+            &[],
+        );
+        let mut psbt =
+            Psbt::from_unsigned_tx(Transaction { output: vec![utxo(2500)], ..tx }).expect("psbt");
+        psbt.inputs[0].witness_utxo = Some(utxo(1500));
+        psbt.inputs[1].non_witness_utxo = Some(prev_tx);
+        assert_eq!(psbt_fee(&psbt).expect("fee"), 1000);
+
+        psbt.unsigned_tx.output[0].value = 4000;
+        assert!(psbt_fee(&psbt).is_err(), "the outputs more than the inputs");
+
+        psbt.inputs[0].witness_utxo = None;
+        assert!(psbt_fee(&psbt).is_err(), "no utxo for the input");
+    }
+
+    #[test]
+    fn test_cpfp_output_index_by_anchor() {
+        let addresses = commit_script_pubkeys(3)
+            .iter()
+            .map(|script_pubkey| {
+                Address::from_script(script_pubkey, Network::Regtest).expect("address")
+            })
+            .collect::<Vec<_>>();
+        let commit_outpoints = vec![OutPoint::new(Txid::all_zeros(), 0)];
+        let is_mine = |_: &Script| Ok(true);
+
+        // the outputs for the vital scripts all had resources.
+        let outputs = vec![(addresses[0].clone(), 600), (addresses[1].clone(), 600)];
+        let tx = reveal_tx(&commit_outpoints, &[], &outputs);
+        let resource_outpoints =
+            (0..2).map(|index| OutPoint::new(tx.txid(), index)).collect::<Vec<_>>();
+        assert_eq!(cpfp_output_index(&tx, &resource_outpoints, is_mine).expect("index"), None);
+
+        // the anchor is the last output.
+        let outputs = [outputs, vec![(addresses[2].clone(), CPFP_ANCHOR_AMOUNT)]].concat();
+        let tx = reveal_tx(&commit_outpoints, &[], &outputs);
+        let resource_outpoints =
+            (0..2).map(|index| OutPoint::new(tx.txid(), index)).collect::<Vec<_>>();
+        assert_eq!(cpfp_output_index(&tx, &resource_outpoints, is_mine).expect("index"), Some(2));
+        assert_eq!(tx.output[2].value, CPFP_ANCHOR_AMOUNT);
+
+        // the anchor not in the wallet.
+        let anchor = addresses[2].script_pubkey();
+        let not_anchor = |script: &Script| Ok(script != anchor.as_script());
+        assert_eq!(cpfp_output_index(&tx, &resource_outpoints, not_anchor).expect("index"), None);
     }
 }
@@ -0,0 +1,166 @@
+//! The commit and reveal psbts exported to file, so they can be signed in other place.
+//!
+//! The reveal tx spend the commit tx by its txid, all the commit tx 's inputs are segwit, so
+//! the txid will not be changed after signed, the two psbts can be signed and broadcast later.
+
+use std::{path::Path, str::FromStr};
+
+use anyhow::{bail, Context as AnyhowContext, Result};
+use bdk::bitcoin::psbt::Psbt;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitRevealPsbts {
+    /// The commit psbt in base64.
+    pub commit: String,
+    /// The reveal psbt in base64.
+    pub reveal: String,
+    /// The reveal tx need be broadcast after the commit tx confirmed, for dmint.
+    #[serde(default)]
+    pub wait_commit_confirmed: bool,
+}
+
+impl CommitRevealPsbts {
+    pub fn new(commit: &Psbt, reveal: &Psbt, wait_commit_confirmed: bool) -> Result<Self> {
+        check_reveal_spend_commit(commit, reveal)?;
+
+        Ok(Self { commit: commit.to_string(), reveal: reveal.to_string(), wait_commit_confirmed })
+    }
+
+    pub fn psbts(&self) -> Result<(Psbt, Psbt)> {
+        let commit = Psbt::from_str(&self.commit).context("parse commit psbt")?;
+        let reveal = Psbt::from_str(&self.reveal).context("parse reveal psbt")?;
+
+        check_reveal_spend_commit(&commit, &reveal)?;
+
+        Ok((commit, reveal))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let datas = std::fs::read_to_string(path).context("read psbts file")?;
+
+        serde_json::from_str(&datas).context("psbts from json")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let datas = serde_json::to_string_pretty(self)?;
+
+        std::fs::write(path, datas).context("write psbts file")
+    }
+}
+
+/// The reveal tx should spend the commit tx, and the commit tx 's txid should not be changed
+/// by sign, so all its inputs should be segwit.
+fn check_reveal_spend_commit(commit: &Psbt, reveal: &Psbt) -> Result<()> {
+    for (index, input) in commit.inputs.iter().enumerate() {
+        if input.witness_utxo.is_none() {
+            bail!("the commit tx 's input {} is not segwit, its txid will be changed", index);
+        }
+    }
+
+    let commit_txid = commit.unsigned_tx.txid();
+    if !reveal
+        .unsigned_tx
+        .input
+        .iter()
+        .any(|input| input.previous_output.txid == commit_txid)
+    {
+        bail!("the reveal tx not spend the commit tx {}", commit_txid);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk::bitcoin::{
+        absolute, hashes::Hash, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid,
+        Witness,
+    };
+
+    use super::*;
+
+    fn psbt(previous_output: OutPoint, value: u64) -> Psbt {
+        let tx = Transaction {
+            version: 1,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut { value, script_pubkey: ScriptBuf::new() }],
+        };
+
+        Psbt::from_unsigned_tx(tx).expect("psbt")
+    }
+
+    /// The commit psbt with a segwit input, and the reveal psbt spend it.
+    fn psbts() -> (Psbt, Psbt) {
+        let mut commit = psbt(OutPoint::new(Txid::all_zeros(), 1), 10000);
+        commit.inputs[0].witness_utxo =
+            Some(TxOut { value: 20000, script_pubkey: ScriptBuf::new() });
+
+        let reveal = psbt(OutPoint::new(commit.unsigned_tx.txid(), 0), 600);
+
+        (commit, reveal)
+    }
+
+    #[test]
+    fn test_save_and_load_should_work() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("psbts.json");
+        let (commit, reveal) = psbts();
+
+        CommitRevealPsbts::new(&commit, &reveal, true)?.save(&path)?;
+
+        let loaded = CommitRevealPsbts::load(&path)?;
+        assert!(loaded.wait_commit_confirmed);
+
+        let (loaded_commit, loaded_reveal) = loaded.psbts()?;
+        assert_eq!(loaded_commit, commit);
+        assert_eq!(loaded_reveal, reveal);
+        assert_eq!(
+            loaded_reveal.unsigned_tx.input[0].previous_output.txid,
+            loaded_commit.unsigned_tx.txid()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reveal_not_spend_commit_should_failed() -> Result<()> {
+        let (commit, _) = psbts();
+        let reveal = psbt(OutPoint::new(Txid::all_zeros(), 0), 600);
+
+        let err = CommitRevealPsbts::new(&commit, &reveal, false).expect_err("should failed");
+        assert!(err.to_string().starts_with("the reveal tx not spend the commit tx"), "{}", err);
+
+        // the file changed by others.
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("psbts.json");
+        let psbts = CommitRevealPsbts {
+            commit: commit.to_string(),
+            reveal: reveal.to_string(),
+            wait_commit_confirmed: false,
+        };
+        psbts.save(&path)?;
+        assert!(CommitRevealPsbts::load(&path)?.psbts().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_input_not_segwit_should_failed() {
+        let (mut commit, _) = psbts();
+        commit.inputs[0].witness_utxo = None;
+        let reveal = psbt(OutPoint::new(commit.unsigned_tx.txid(), 0), 600);
+
+        let err = CommitRevealPsbts::new(&commit, &reveal, false).expect_err("should failed");
+        assert_eq!(
+            err.to_string(),
+            "the commit tx 's input 0 is not segwit, its txid will be changed"
+        );
+    }
+}
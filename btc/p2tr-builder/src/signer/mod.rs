@@ -0,0 +1,372 @@
+//! Sign and finalize the psbts of the commit tx and the reveal tx.
+//!
+//! The inputs are signed by their tap key origins, so the psbts can be signed in other place
+//! which just had the master key. The script path inputs for the vital scripts can not be
+//! finalized by bdk, so all the inputs are finalized by self.
+//!
+//! The keys are behind the `Signer`, the `SoftwareSigner` sign by the xpriv in memory, the
+//! `ProcessSigner` ask an external process by json lines, so the hardware signers or other
+//! custody can be used without the xpriv.
+
+mod process;
+mod software;
+
+pub use process::{serve, ProcessSigner, Request, Response};
+pub use software::SoftwareSigner;
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use bdk::{
+    bitcoin::{
+        bip32::{DerivationPath, Fingerprint},
+        key::TapTweak,
+        psbt::{self, PartiallySignedTransaction},
+        secp256k1::{schnorr, All, Message, Secp256k1, XOnlyPublicKey},
+        sighash::{self, TapSighash, TapSighashType},
+        taproot::{self, TapLeafHash, TapNodeHash},
+        Witness,
+    },
+    psbt::PsbtUtils,
+};
+use wallet::Wallet;
+
+/// The signer for the taproot inputs, the keys are from a master key by the derivation paths.
+pub trait Signer {
+    /// The fingerprint of the master key, only the inputs which key origins from it will be
+    /// signed by this signer.
+    fn fingerprint(&self) -> Fingerprint;
+
+    /// Sign for the key path spend, the key derived by the path is the internal key `pubkey`,
+    /// it should be tweaked by the `merkle_root` before sign.
+    fn sign_key_path(
+        &self,
+        path: &DerivationPath,
+        pubkey: &XOnlyPublicKey,
+        merkle_root: Option<TapNodeHash>,
+        sighash: &TapSighash,
+    ) -> Result<schnorr::Signature>;
+
+    /// Sign for the script path spend of the `leaf_hash`, the key not need tweak.
+    fn sign_script_path(
+        &self,
+        path: &DerivationPath,
+        pubkey: &XOnlyPublicKey,
+        leaf_hash: &TapLeafHash,
+        sighash: &TapSighash,
+    ) -> Result<schnorr::Signature>;
+}
+
+/// The signers for the wallets, the external signer by the command if set, and the xprivs of
+/// the wallets which are not watch-only.
+pub fn signers_for_wallets<'a>(
+    signer_command: Option<&str>,
+    wallets: &[&Wallet],
+) -> Result<Vec<Box<dyn Signer + 'a>>> {
+    let mut signers: Vec<Box<dyn Signer + 'a>> = Vec::with_capacity(wallets.len() + 1);
+
+    if let Some(command) = signer_command {
+        let signer = ProcessSigner::spawn(command).context("spawn the external signer")?;
+        signers.push(Box::new(signer));
+    }
+
+    let mut xprivs = Vec::with_capacity(wallets.len());
+    for wallet in wallets.iter() {
+        if let Ok(xpriv) = wallet.xpriv() {
+            if !xprivs.contains(xpriv) {
+                xprivs.push(*xpriv);
+            }
+        }
+    }
+
+    for xpriv in xprivs {
+        signers.push(Box::new(SoftwareSigner::new(xpriv)));
+    }
+
+    Ok(signers)
+}
+
+/// Sign all the inputs which key origins are from the signer, the inputs from other keys
+/// will be skipped, so the psbt can be signed by multiple signers.
+///
+/// The signatures from the signer will be verified before put into the psbt.
+///
+/// Return the count of the inputs signed.
+pub fn sign_psbt(
+    psbt: &mut PartiallySignedTransaction,
+    signer: &dyn Signer,
+    secp: &Secp256k1<All>,
+) -> Result<usize> {
+    let fingerprint = signer.fingerprint();
+    let mut signed = 0;
+
+    for input_index in 0..psbt.inputs.len() {
+        let mut is_signed = false;
+
+        for (key, (leaf_hashes, (key_fingerprint, path))) in
+            psbt.inputs[input_index].tap_key_origins.clone()
+        {
+            if key_fingerprint != fingerprint {
+                continue;
+            }
+
+            if leaf_hashes.is_empty() {
+                // the key spend, for the resource inputs and the commit tx 's inputs.
+                let (hash, hash_ty) = sighash(psbt, input_index, None).context("sighash")?;
+                let merkle_root = psbt.inputs[input_index].tap_merkle_root;
+
+                let sig = signer
+                    .sign_key_path(&path, &key, merkle_root, &hash)
+                    .with_context(|| format!("sign input {}", input_index))?;
+
+                let tweaked_key = key.tap_tweak(secp, merkle_root).0.to_inner();
+                verify(secp, &sig, &hash, &tweaked_key, input_index)?;
+
+                psbt.inputs[input_index].tap_key_sig = Some(taproot::Signature { sig, hash_ty });
+            }
+
+            // the script spend, for the vital scripts inputs.
+            for lh in leaf_hashes {
+                let (hash, hash_ty) = sighash(psbt, input_index, Some(lh)).context("sighash")?;
+
+                let sig = signer
+                    .sign_script_path(&path, &key, &lh, &hash)
+                    .with_context(|| format!("sign input {}", input_index))?;
+
+                verify(secp, &sig, &hash, &key, input_index)?;
+
+                psbt.inputs[input_index]
+                    .tap_script_sigs
+                    .insert((key, lh), taproot::Signature { sig, hash_ty });
+            }
+
+            is_signed = true;
+        }
+
+        if is_signed {
+            signed += 1;
+        }
+    }
+
+    Ok(signed)
+}
+
+/// Fill the inputs by the placeholder signatures, just for calculate the weight of the tx.
+pub fn sign_psbt_with_placeholder(psbt: &mut PartiallySignedTransaction) -> Result<()> {
+    let placeholder = schnorr::Signature::from_slice(&[1_u8; 64]).context("placeholder")?;
+
+    for input in psbt.inputs.iter_mut() {
+        let hash_ty = input
+            .sighash_type
+            .unwrap_or_else(|| TapSighashType::Default.into())
+            .taproot_hash_ty()
+            .map_err(|_| anyhow!("InvalidSighash"))?;
+        let signature = taproot::Signature { sig: placeholder, hash_ty };
+
+        for (key, (leaf_hashes, _)) in input.tap_key_origins.clone() {
+            if leaf_hashes.is_empty() {
+                input.tap_key_sig = Some(signature);
+            }
+
+            for lh in leaf_hashes {
+                input.tap_script_sigs.insert((key, lh), signature);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Finalize all the inputs by the signatures, all the inputs should had been signed.
+pub fn finalize_psbt(psbt: &mut PartiallySignedTransaction) -> Result<()> {
+    for (index, input) in psbt.inputs.iter_mut().enumerate() {
+        if input.final_script_witness.is_some() {
+            continue;
+        }
+
+        if input.tap_key_sig.is_none() && input.tap_script_sigs.is_empty() {
+            bail!("the input {} had not signed", index);
+        }
+
+        let mut script_witness: Witness = Witness::new();
+        if let Some(tap_key_sig) = input.tap_key_sig {
+            script_witness.push(tap_key_sig.to_vec());
+        }
+        for (_, signature) in input.tap_script_sigs.iter() {
+            script_witness.push(signature.to_vec());
+        }
+        for (control_block, (script, _)) in input.tap_scripts.iter() {
+            script_witness.push(script.to_bytes());
+            script_witness.push(control_block.serialize());
+        }
+        input.final_script_witness = Some(script_witness);
+
+        // Clear all the data fields as per the spec.
+        input.partial_sigs = BTreeMap::new();
+        input.sighash_type = None;
+        input.redeem_script = None;
+        input.witness_script = None;
+        input.bip32_derivation = BTreeMap::new();
+        input.tap_script_sigs = BTreeMap::new();
+        input.tap_scripts = BTreeMap::new();
+        input.tap_key_sig = None;
+    }
+
+    Ok(())
+}
+
+fn sighash(
+    psbt: &PartiallySignedTransaction,
+    input_index: usize,
+    extra: Option<taproot::TapLeafHash>,
+) -> Result<(TapSighash, TapSighashType)> {
+    if input_index >= psbt.inputs.len() || input_index >= psbt.unsigned_tx.input.len() {
+        bail!("InputIndexOutOfRange");
+    }
+
+    let psbt_input = &psbt.inputs[input_index];
+
+    let sighash_type = psbt_input
+        .sighash_type
+        .unwrap_or_else(|| TapSighashType::Default.into())
+        .taproot_hash_ty()
+        .map_err(|_| anyhow!("InvalidSighash"))?;
+    let witness_utxos = (0..psbt.inputs.len()).map(|i| psbt.get_utxo_for(i)).collect::<Vec<_>>();
+    let mut all_witness_utxos = vec![];
+
+    let mut cache = sighash::SighashCache::new(&psbt.unsigned_tx);
+    let is_anyone_can_pay = psbt::PsbtSighashType::from(sighash_type).to_u32() & 0x80 != 0;
+    let prevouts = if is_anyone_can_pay {
+        sighash::Prevouts::One(
+            input_index,
+            witness_utxos[input_index].as_ref().ok_or(anyhow!("MissingWitnessUtxo"))?,
+        )
+    } else if witness_utxos.iter().all(Option::is_some) {
+        all_witness_utxos.extend(witness_utxos.iter().filter_map(|x| x.as_ref()));
+        sighash::Prevouts::All(&all_witness_utxos)
+    } else {
+        return Err(anyhow!("MissingWitnessUtxo"));
+    };
+
+    // Assume no OP_CODESEPARATOR
+    let extra = extra.map(|leaf_hash| (leaf_hash, 0xFFFFFFFF));
+
+    Ok((
+        cache.taproot_signature_hash(input_index, &prevouts, None, extra, sighash_type)?,
+        sighash_type,
+    ))
+}
+
+fn verify(
+    secp: &Secp256k1<All>,
+    sig: &schnorr::Signature,
+    hash: &TapSighash,
+    pubkey: &XOnlyPublicKey,
+    input_index: usize,
+) -> Result<()> {
+    secp.verify_schnorr(sig, &Message::from(*hash), pubkey)
+        .map_err(|_| anyhow!("the signature of input {} is invalid", input_index))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::str::FromStr;
+
+    use bdk::bitcoin::{
+        absolute,
+        bip32::ExtendedPrivKey,
+        opcodes::all::OP_CHECKSIG,
+        psbt::Input,
+        script::Builder,
+        taproot::{LeafVersion, TaprootBuilder},
+        Network, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid,
+    };
+
+    use super::*;
+
+    /// A psbt with a key path input and a script path input, both from the xpriv.
+    pub(crate) fn mock_psbt() -> (PartiallySignedTransaction, ExtendedPrivKey) {
+        let secp = Secp256k1::new();
+        let xpriv = ExtendedPrivKey::new_master(Network::Regtest, &[7_u8; 32]).expect("xpriv");
+        let path = DerivationPath::from_str("m/86'/1'/0'/0/0").expect("path");
+        let key = xpriv
+            .derive_priv(&secp, &path)
+            .expect("derive")
+            .to_keypair(&secp)
+            .x_only_public_key()
+            .0;
+        let fingerprint = xpriv.fingerprint(&secp);
+
+        let script = Builder::new().push_x_only_key(&key).push_opcode(OP_CHECKSIG).into_script();
+        let leaf_hash = TapLeafHash::from_script(&script, LeafVersion::TapScript);
+        let spend_info = TaprootBuilder::new()
+            .add_leaf(0, script.clone())
+            .expect("add leaf")
+            .finalize(&secp, key)
+            .expect("finalize");
+
+        let input = |vout| TxIn {
+            previous_output: OutPoint::new(Txid::from_str(&"11".repeat(32)).expect("txid"), vout),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence(0xFFFFFFFD),
+            witness: Witness::default(),
+        };
+        let tx = Transaction {
+            version: 1,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![input(0), input(1)],
+            output: vec![TxOut {
+                value: 1000,
+                script_pubkey: ScriptBuf::new_v1_p2tr(&secp, key, None),
+            }],
+        };
+
+        let mut psbt = PartiallySignedTransaction::from_unsigned_tx(tx).expect("psbt");
+
+        psbt.inputs[0] = Input {
+            witness_utxo: Some(TxOut {
+                value: 2000,
+                script_pubkey: ScriptBuf::new_v1_p2tr(&secp, key, None),
+            }),
+            tap_key_origins: [(key, (vec![], (fingerprint, path.clone())))].into(),
+            tap_internal_key: Some(key),
+            ..Default::default()
+        };
+        psbt.inputs[1] = Input {
+            witness_utxo: Some(TxOut {
+                value: 2000,
+                script_pubkey: ScriptBuf::new_v1_p2tr(&secp, key, spend_info.merkle_root()),
+            }),
+            tap_key_origins: [(key, (vec![leaf_hash], (fingerprint, path)))].into(),
+            tap_internal_key: Some(key),
+            tap_merkle_root: spend_info.merkle_root(),
+            tap_scripts: [(
+                spend_info
+                    .control_block(&(script.clone(), LeafVersion::TapScript))
+                    .expect("control block"),
+                (script, LeafVersion::TapScript),
+            )]
+            .into(),
+            ..Default::default()
+        };
+
+        (psbt, xpriv)
+    }
+
+    #[test]
+    fn test_sign_by_software_signer() {
+        let secp = Secp256k1::new();
+        let (mut psbt, xpriv) = mock_psbt();
+
+        // the other key will not sign any input.
+        let other = ExtendedPrivKey::new_master(Network::Regtest, &[8_u8; 32]).expect("xpriv");
+        assert_eq!(sign_psbt(&mut psbt, &SoftwareSigner::new(other), &secp).expect("sign"), 0);
+        assert!(finalize_psbt(&mut psbt.clone()).is_err());
+
+        assert_eq!(sign_psbt(&mut psbt, &SoftwareSigner::new(xpriv), &secp).expect("sign"), 2);
+        assert!(psbt.inputs[0].tap_key_sig.is_some());
+        assert_eq!(psbt.inputs[1].tap_script_sigs.len(), 1);
+
+        finalize_psbt(&mut psbt).expect("finalize");
+    }
+}
@@ -0,0 +1,288 @@
+//! The signer by an external process, which talk by json lines in its stdin and stdout.
+//!
+//! Each request is a line of json, the process should response a line for each request:
+//!
+//! ```text
+//! > {"method":"fingerprint"}
+//! < {"fingerprint":"d34db33f"}
+//! > {"method":"sign_key_path","path":"m/86'/1'/0'/0/0","pubkey":"..","merkle_root":"..","sighash":".."}
+//! < {"signature":".."}
+//! > {"method":"sign_script_path","path":"m/86'/1'/0'/0/0","pubkey":"..","leaf_hash":"..","sighash":".."}
+//! < {"error":"rejected by user"}
+//! ```
+//!
+//! All the hashes, keys and signatures are in hex. The stderr of the process is not used, so it
+//! can print the logs to it.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    sync::Mutex,
+};
+
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use bdk::bitcoin::{
+    bip32::{DerivationPath, Fingerprint},
+    secp256k1::{schnorr, XOnlyPublicKey},
+    sighash::TapSighash,
+    taproot::{TapLeafHash, TapNodeHash},
+};
+use serde::{Deserialize, Serialize};
+
+use super::Signer;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Request {
+    Fingerprint,
+    SignKeyPath {
+        path: DerivationPath,
+        pubkey: XOnlyPublicKey,
+        merkle_root: Option<TapNodeHash>,
+        sighash: TapSighash,
+    },
+    SignScriptPath {
+        path: DerivationPath,
+        pubkey: XOnlyPublicKey,
+        leaf_hash: TapLeafHash,
+        sighash: TapSighash,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Response {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<Fingerprint>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<schnorr::Signature>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Response {
+    fn into_signature(self) -> Result<schnorr::Signature> {
+        if let Some(error) = self.error {
+            bail!("the signer failed by {}", error);
+        }
+
+        self.signature.ok_or_else(|| anyhow!("no signature in response"))
+    }
+}
+
+pub struct ProcessSigner {
+    child: Child,
+    io: Mutex<(ChildStdin, BufReader<ChildStdout>)>,
+    fingerprint: Fingerprint,
+}
+
+impl ProcessSigner {
+    /// Spawn the signer by the command line, the args split by the whitespaces.
+    pub fn spawn(command: &str) -> Result<Self> {
+        let mut args = command.split_whitespace();
+        let program = args.next().ok_or_else(|| anyhow!("the signer command is empty"))?;
+
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("spawn signer {}", program))?;
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+
+        let mut res =
+            Self { child, io: Mutex::new((stdin, stdout)), fingerprint: Fingerprint::default() };
+
+        let response = res.request(&Request::Fingerprint).context("get fingerprint")?;
+        if let Some(error) = response.error {
+            bail!("the signer failed by {}", error);
+        }
+        res.fingerprint =
+            response.fingerprint.ok_or_else(|| anyhow!("no fingerprint in response"))?;
+
+        Ok(res)
+    }
+
+    fn request(&self, request: &Request) -> Result<Response> {
+        let mut io = self.io.lock().map_err(|_| anyhow!("the signer io poisoned"))?;
+        let (stdin, stdout) = &mut *io;
+
+        writeln!(stdin, "{}", serde_json::to_string(request)?).context("write request")?;
+        stdin.flush().context("flush request")?;
+
+        let mut line = String::new();
+        if stdout.read_line(&mut line).context("read response")? == 0 {
+            bail!("the signer exited");
+        }
+
+        serde_json::from_str(&line).context("parse response")
+    }
+}
+
+impl Drop for ProcessSigner {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Signer for ProcessSigner {
+    fn fingerprint(&self) -> Fingerprint {
+        self.fingerprint
+    }
+
+    fn sign_key_path(
+        &self,
+        path: &DerivationPath,
+        pubkey: &XOnlyPublicKey,
+        merkle_root: Option<TapNodeHash>,
+        sighash: &TapSighash,
+    ) -> Result<schnorr::Signature> {
+        self.request(&Request::SignKeyPath {
+            path: path.clone(),
+            pubkey: *pubkey,
+            merkle_root,
+            sighash: *sighash,
+        })?
+        .into_signature()
+    }
+
+    fn sign_script_path(
+        &self,
+        path: &DerivationPath,
+        pubkey: &XOnlyPublicKey,
+        leaf_hash: &TapLeafHash,
+        sighash: &TapSighash,
+    ) -> Result<schnorr::Signature> {
+        self.request(&Request::SignScriptPath {
+            path: path.clone(),
+            pubkey: *pubkey,
+            leaf_hash: *leaf_hash,
+            sighash: *sighash,
+        })?
+        .into_signature()
+    }
+}
+
+/// Serve the requests from the reader by the signer, for implement the signer process, it
+/// will return when the reader closed.
+pub fn serve(signer: &dyn Signer, reader: impl BufRead, mut writer: impl Write) -> Result<()> {
+    for line in reader.lines() {
+        let line = line.context("read request")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(Request::Fingerprint) => {
+                Response { fingerprint: Some(signer.fingerprint()), ..Default::default() }
+            }
+            Ok(Request::SignKeyPath { path, pubkey, merkle_root, sighash }) => {
+                signer.sign_key_path(&path, &pubkey, merkle_root, &sighash).into()
+            }
+            Ok(Request::SignScriptPath { path, pubkey, leaf_hash, sighash }) => {
+                signer.sign_script_path(&path, &pubkey, &leaf_hash, &sighash).into()
+            }
+            Err(err) => {
+                Response { error: Some(format!("invalid request: {}", err)), ..Default::default() }
+            }
+        };
+
+        writeln!(writer, "{}", serde_json::to_string(&response)?).context("write response")?;
+        writer.flush().context("flush response")?;
+    }
+
+    Ok(())
+}
+
+impl From<Result<schnorr::Signature>> for Response {
+    fn from(res: Result<schnorr::Signature>) -> Self {
+        match res {
+            Ok(signature) => Self { signature: Some(signature), ..Default::default() },
+            Err(err) => Self { error: Some(format!("{:#}", err)), ..Default::default() },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::OnceLock};
+
+    use super::*;
+    use crate::signer::{finalize_psbt, sign_psbt, tests::mock_psbt};
+
+    /// The mock signer is `examples/mock_signer.rs`, the examples are not built by
+    /// `cargo test --lib` or a filtered test run, so build it by cargo once here.
+    fn mock_signer_command(xpriv: &str) -> String {
+        static MOCK_SIGNER: OnceLock<PathBuf> = OnceLock::new();
+
+        let path = MOCK_SIGNER.get_or_init(|| {
+            let output = Command::new(env!("CARGO"))
+                .args(["build", "--example", "mock_signer", "--message-format=json"])
+                .args(["--manifest-path", concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")])
+                .stderr(Stdio::inherit())
+                .output()
+                .expect("run cargo build");
+            assert!(output.status.success(), "build mock signer failed");
+
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+                .filter(|msg| msg["target"]["name"] == "mock_signer")
+                .find_map(|msg| msg["executable"].as_str().map(PathBuf::from))
+                .expect("no mock signer executable")
+        });
+
+        format!("{} {}", path.display(), xpriv)
+    }
+
+    #[test]
+    fn test_sign_by_process_signer() {
+        let (mut psbt, xpriv) = mock_psbt();
+
+        let signer = ProcessSigner::spawn(&mock_signer_command(&xpriv.to_string()))
+            .expect("spawn mock signer");
+        assert_eq!(
+            signer.fingerprint(),
+            xpriv.fingerprint(&bdk::bitcoin::secp256k1::Secp256k1::new())
+        );
+
+        let secp = bdk::bitcoin::secp256k1::Secp256k1::new();
+        assert_eq!(sign_psbt(&mut psbt, &signer, &secp).expect("sign"), 2);
+        finalize_psbt(&mut psbt).expect("finalize");
+    }
+
+    #[test]
+    fn test_process_signer_error() {
+        let (mut psbt, xpriv) = mock_psbt();
+
+        // the mock signer got a different key with the same fingerprint, so its signatures
+        // will not match the keys in psbt.
+        let signer = ProcessSigner::spawn(&format!(
+            "{} --wrong-key",
+            mock_signer_command(&xpriv.to_string())
+        ))
+        .expect("spawn mock signer");
+
+        let secp = bdk::bitcoin::secp256k1::Secp256k1::new();
+        let err = sign_psbt(&mut psbt, &signer, &secp).expect_err("should failed");
+        assert!(format!("{:#}", err).contains("not match"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_request_json() {
+        assert_eq!(
+            serde_json::to_string(&Request::Fingerprint).expect("to json"),
+            r#"{"method":"fingerprint"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Response>(r#"{"error":"rejected"}"#)
+                .expect("from json")
+                .into_signature()
+                .expect_err("should failed")
+                .to_string(),
+            "the signer failed by rejected"
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! The signer by the xpriv in memory.
+
+use anyhow::{bail, Result};
+use bdk::bitcoin::{
+    bip32::{DerivationPath, ExtendedPrivKey, Fingerprint},
+    key::TapTweak,
+    secp256k1::{schnorr, All, KeyPair, Message, Secp256k1, XOnlyPublicKey},
+    sighash::TapSighash,
+    taproot::{TapLeafHash, TapNodeHash},
+};
+
+use super::Signer;
+
+pub struct SoftwareSigner {
+    master_xpriv: ExtendedPrivKey,
+    secp: Secp256k1<All>,
+}
+
+impl SoftwareSigner {
+    pub fn new(master_xpriv: ExtendedPrivKey) -> Self {
+        Self { master_xpriv, secp: Secp256k1::new() }
+    }
+
+    fn keypair(&self, path: &DerivationPath, pubkey: &XOnlyPublicKey) -> Result<KeyPair> {
+        let keypair = self.master_xpriv.derive_priv(&self.secp, path)?.to_keypair(&self.secp);
+
+        if keypair.x_only_public_key().0 != *pubkey {
+            bail!("the key derived by {} not match {}", path, pubkey);
+        }
+
+        Ok(keypair)
+    }
+}
+
+impl Signer for SoftwareSigner {
+    fn fingerprint(&self) -> Fingerprint {
+        self.master_xpriv.fingerprint(&self.secp)
+    }
+
+    fn sign_key_path(
+        &self,
+        path: &DerivationPath,
+        pubkey: &XOnlyPublicKey,
+        merkle_root: Option<TapNodeHash>,
+        sighash: &TapSighash,
+    ) -> Result<schnorr::Signature> {
+        let keypair = self.keypair(path, pubkey)?.tap_tweak(&self.secp, merkle_root).to_inner();
+
+        Ok(self.secp.sign_schnorr(&Message::from(*sighash), &keypair))
+    }
+
+    fn sign_script_path(
+        &self,
+        path: &DerivationPath,
+        pubkey: &XOnlyPublicKey,
+        _leaf_hash: &TapLeafHash,
+        sighash: &TapSighash,
+    ) -> Result<schnorr::Signature> {
+        // no tweak for script spend
+        let keypair = self.keypair(path, pubkey)?;
+
+        Ok(self.secp.sign_schnorr(&Message::from(*sighash), &keypair))
+    }
+}
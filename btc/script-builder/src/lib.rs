@@ -6,6 +6,7 @@
 //! OP_IF
 //! OP_PUSHBYTES_5 766974616c
 //! OP_PUSHBYTES_XX datas1 or OP_PUSHDATA2 len datasXXX
+//! ... more pushes if the datas is larger than 520 bytes
 //! OP_ENDIF
 
 use anyhow::{bail, Context, Result};
@@ -21,8 +22,7 @@ use bdk::bitcoin::{
 };
 
 use vital_primitives::consts::INSCRIBE_TAG;
-
-const MAX_INSCRIPTION_DATAS_LEN: usize = 4096;
+use vital_script_primitives::consts::{MAX_PUSH_DATAS_LEN, MAX_SCRIPT_DATAS_LEN};
 
 pub struct InscriptionScriptBuilder {
     datas: Vec<u8>,
@@ -34,26 +34,29 @@ impl InscriptionScriptBuilder {
     }
 
     pub fn into_script_by_key(self, key: &XOnlyPublicKey) -> Result<ScriptBuf> {
-        if self.datas.len() >= MAX_INSCRIPTION_DATAS_LEN {
+        if self.datas.len() > MAX_SCRIPT_DATAS_LEN {
             bail!(
                 "the inscription datas is too large expect {} got {}!",
-                MAX_INSCRIPTION_DATAS_LEN,
+                MAX_SCRIPT_DATAS_LEN,
                 self.datas.len()
             )
         } else {
-            let builder = ScriptBuf::builder()
+            let mut builder = ScriptBuf::builder()
                 .push_x_only_key(key)
                 .push_opcode(OP_CHECKSIG)
                 .push_opcode(OP_0)
                 .push_opcode(OP_IF)
                 .push_slice(INSCRIBE_TAG);
 
-            // Note the datas len must < MAX_INSCRIPTION_DATAS_LEN
-            let mut buf = PushBytesBuf::with_capacity(self.datas.len());
-            buf.extend_from_slice(&self.datas)?;
+            // Note the datas len must <= MAX_SCRIPT_DATAS_LEN
+            for chunk in self.datas.chunks(MAX_PUSH_DATAS_LEN) {
+                let mut buf = PushBytesBuf::with_capacity(chunk.len());
+                buf.extend_from_slice(chunk)?;
+
+                builder = builder.push_slice(buf);
+            }
 
-            let builder = builder.push_slice(buf).push_opcode(OP_ENDIF);
-            let script = builder.into_script();
+            let script = builder.push_opcode(OP_ENDIF).into_script();
 
             Ok(script)
         }
@@ -109,5 +112,29 @@ mod tests {
 
         test_datas_with_len(4000);
         test_datas_with_len(4095);
+        test_datas_with_len(4096);
+    }
+
+    #[test]
+    fn test_scripts_chunked_by_push_len() {
+        let datas = gen_datas(1200);
+        let script = InscriptionScriptBuilder::new(datas.clone())
+            .into_script(&hex_literal::hex!(
+                "d2e7612f73d26067ae83e2a9d8bfa496193374677490dff0792242bbacba6922"
+            ))
+            .expect("datas");
+
+        let pushes = script
+            .instructions()
+            .filter_map(|ins| ins.expect("instruction").push_bytes().map(|bytes| bytes.len()))
+            .collect::<Vec<_>>();
+
+        // key, OP_0, tag and the datas chunks
+        assert_eq!(pushes, vec![32, 0, 5, 520, 520, 160]);
+
+        let res = InscriptionScriptBuilder::new(gen_datas(MAX_SCRIPT_DATAS_LEN + 1)).into_script(
+            &hex_literal::hex!("d2e7612f73d26067ae83e2a9d8bfa496193374677490dff0792242bbacba6922"),
+        );
+        assert!(res.is_err());
     }
 }
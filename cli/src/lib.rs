@@ -2,6 +2,7 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 
 use bdk::bitcoin::Network;
+use client::fee_rate::FeeRateArg;
 
 mod sub;
 use sub::*;
@@ -50,9 +51,9 @@ struct Cli {
     #[arg(long, default_value = "600")]
     sats: u64,
 
-    /// Specify a fee rate in sat/vB.
+    /// Specify a fee rate in sat/vB, or `fast`, `normal`, `slow` to estimate it by the electrum.
     #[arg(short, long)]
-    fee_rate: Option<f32>,
+    fee_rate: Option<FeeRateArg>,
 
     /// Signal that this transaction can be replaced by a transaction (BIP 125).
     #[arg(long)]
@@ -69,6 +70,25 @@ struct Cli {
     /// The name of wallet for fee
     #[arg(long)]
     fee_wallet: Option<String>,
+
+    /// Not sign and send the txs, but save the unsigned commit and reveal psbts to the file,
+    /// which can be signed by `utils sign-psbt` then sent by `utils broadcast-psbt`.
+    #[arg(long)]
+    psbt_out: Option<std::path::PathBuf>,
+
+    /// The command of the external signer, it will be spawned to sign the txs by json lines,
+    /// see `btc_p2tr_builder::signer::ProcessSigner`.
+    #[arg(long)]
+    signer: Option<String>,
+
+    /// Build and simulate the txs then show the cost, but not broadcast them.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Add an output without resources to the wallet in the reveal tx, so the reveal tx can be
+    /// accelerated by `tx cpfp`.
+    #[arg(long)]
+    cpfp_anchor: bool,
 }
 
 impl Cli {
@@ -102,6 +122,14 @@ enum SubCommands {
     #[command(subcommand)]
     Move(move_resource::MoveSubCommands),
 
+    /// Burn tokens
+    #[command(subcommand)]
+    Burn(burn::BurnSubCommands),
+
+    /// Manage the txs sent
+    #[command(subcommand)]
+    Tx(tx::TxSubCommands),
+
     /// Wallet cmds
     #[command(subcommand)]
     Wallet(wallet::WalletSubCommands),
@@ -139,6 +167,8 @@ pub async fn run() -> Result<()> {
         SubCommands::Mint(cmd) => cmd.run(&cli).await,
         SubCommands::Deploy(cmd) => cmd.run(&cli).await,
         SubCommands::Move(cmd) => cmd.run(&cli).await,
+        SubCommands::Burn(cmd) => cmd.run(&cli).await,
+        SubCommands::Tx(cmd) => cmd.run(&cli).await,
         SubCommands::Wallet(cmd) => cmd.run(&cli).await,
         SubCommands::Utils(cmd) => cmd.run(&cli).await,
         SubCommands::Version { json } => print_version(*json),
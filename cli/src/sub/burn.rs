@@ -0,0 +1,119 @@
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use clap::Subcommand;
+use vital_script_primitives::{
+    resources::{Resource, Tag},
+    U256,
+};
+
+use crate::Cli;
+
+use super::context::{build_context, Context};
+
+#[derive(Debug, Subcommand)]
+pub enum BurnSubCommands {
+    /// Burn name resource.
+    Name {
+        /// The name to burn.
+        name: String,
+    },
+    /// Burn the amount of vrc20, the charge will be moved to the output.
+    VRC20 {
+        /// The name of vrc20
+        name: String,
+        /// The amount to burn
+        amount: u128,
+    },
+}
+
+impl BurnSubCommands {
+    pub(crate) async fn run(&self, cli: &Cli) -> Result<()> {
+        let mut context = build_context(cli).await.context("build context")?;
+
+        match self {
+            Self::Name { name } => {
+                burn_name(&mut context, name).await?;
+            }
+            Self::VRC20 { name, amount } => {
+                burn_vrc20(&mut context, name, U256::from(*amount)).await?;
+            }
+        }
+
+        context.flush_wallets()?;
+
+        Ok(())
+    }
+}
+
+async fn burn_name(context: &mut Context, name: &str) -> Result<()> {
+    use vital_script_builder::templates;
+
+    let name =
+        Tag::try_from(name).with_context(|| format!("the '{}' name format is invalid", name))?;
+
+    let input_name_utxo = context
+        .get_owned_resource(&Resource::name(name))
+        .ok_or_else(|| anyhow!("burn name need required a name resource by {}", name))?;
+
+    context.append_reveal_input(&[input_name_utxo]);
+
+    let scripts_bytes = templates::burn_names(&[name]).context("build scripts failed")?;
+
+    // build tx then send
+    crate::send_p2tr(context, scripts_bytes).await.context("send_p2tr failed")?;
+
+    Ok(())
+}
+
+async fn burn_vrc20(context: &mut Context, name: &str, amount: U256) -> Result<()> {
+    use vital_script_builder::templates;
+
+    let vrc20_name =
+        Tag::try_from(name).with_context(|| format!("name {} format invalid", name))?;
+
+    let (sum, mut owned_vrc20s) = context
+        .fetch_all_vrc20_by_name(vrc20_name)
+        .await
+        .context("fetch_all_vrc20_by_name")?;
+
+    if sum < amount {
+        bail!("the vrc20 {} owned just {}, less then {:?}", name, sum, amount);
+    }
+
+    let mut inputs = Vec::with_capacity(owned_vrc20s.len());
+    let mut utxos = Vec::with_capacity(owned_vrc20s.len());
+
+    let mut pushed_amount = U256::zero();
+
+    // TODO: we need a way to select inputs
+    owned_vrc20s.sort_by_key(|local| local.resource.as_vrc20().expect("should be vrc20").amount);
+
+    for (index, local) in owned_vrc20s.into_iter().enumerate() {
+        if pushed_amount >= amount {
+            break;
+        }
+
+        // the index is from1
+        let index = index + 1;
+
+        if index >= u8::MAX as usize {
+            bail!("the index index not supported >= {}", u8::MAX);
+        }
+
+        let add_amount = local.resource.as_vrc20().expect("should be vrc20").amount;
+
+        utxos.push(local.utxo);
+        inputs.push((index as u8, add_amount));
+        pushed_amount += add_amount;
+    }
+
+    // build script, the charge will be moved to the output 0 if had.
+    let (_, scripts_bytes) = templates::burn_vrc20s_with_charge(vrc20_name, inputs, amount)
+        .context("build scripts failed")?;
+
+    context.append_reveal_input(&utxos);
+
+    // build tx then send
+    crate::send_p2tr(context, scripts_bytes).await.context("send_p2tr failed")?;
+
+    Ok(())
+}
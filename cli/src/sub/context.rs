@@ -7,23 +7,47 @@ use crate::Cli;
 
 pub async fn build_context(cli: &Cli) -> Result<Context> {
     let network = cli.network();
+    let wallet_name = cli.wallet.clone().unwrap_or(DEFAULT_WALLET_NAME.to_string());
 
     let wallet = wallet::Wallet::load(
         network,
         cli.endpoint.clone(),
         &cli.datadir,
-        cli.wallet.as_ref().unwrap_or(&DEFAULT_WALLET_NAME.to_string()),
+        &wallet_name,
         !cli.no_sync,
     )
     .context("load wallet failed")?;
 
+    // if the fee wallet is the same to the wallet, just use the wallet.
+    let fee_wallet = cli
+        .fee_wallet
+        .as_ref()
+        .filter(|fee_wallet_name| **fee_wallet_name != wallet_name)
+        .map(|fee_wallet_name| {
+            wallet::Wallet::load(
+                network,
+                cli.endpoint.clone(),
+                &cli.datadir,
+                fee_wallet_name,
+                !cli.no_sync,
+            )
+        })
+        .transpose()
+        .context("load fee wallet failed")?;
+
     let context = Context::new(cli.datadir.clone(), &cli.indexer, wallet)
         .await?
-        .with_fee_rate(&cli.fee_rate)
+        .with_fee_wallet(fee_wallet)
+        .await?
+        .with_fee_rate(&cli.fee_rate)?
         .with_replaceable(&cli.replaceable)
         .with_to_address(&cli.to)
         .context("with address")?
-        .with_sats_amount(cli.sats);
+        .with_sats_amount(cli.sats)
+        .with_psbt_out(&cli.psbt_out)
+        .with_signer(&cli.signer)
+        .with_dry_run(&cli.dry_run)
+        .with_cpfp_anchor(&cli.cpfp_anchor);
 
     Ok(context)
 }
@@ -1,10 +1,13 @@
-use anyhow::{anyhow, Context as AnyhowContext, Result};
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
 use clap::Subcommand;
 
 use vital_script_primitives::{
-    resources::{Name, Resource},
+    consts::PROTOCOL_VERSION_V2,
+    resources::{Resource, Tag},
     types::{
+        bitwork::Bitwork,
         vrc20::{VRC20MetaData, VRC20MintMeta},
+        vrc721::{VRC721MetaData, ROYALTY_BASE},
         MetaData,
     },
 };
@@ -26,9 +29,9 @@ pub enum DeploySubCommands {
         #[arg(long, default_value = "0")]
         nonce: u64,
 
-        /// The bworkc for mint.
-        #[arg(long, default_value = "0")]
-        bworkc: u64,
+        /// The bitwork for mint, the hex prefix each mint 's reveal txid need match, e.g. `0000`.
+        #[arg(long)]
+        bworkc: Option<String>,
 
         /// The amount for each mint
         mint_amount: u128,
@@ -44,6 +47,22 @@ pub enum DeploySubCommands {
         #[arg(long)]
         meta_data: Option<String>,
     },
+    /// Deploy VRC721 collection by a name
+    VRC721 {
+        /// The name for the collection to deploy
+        name: String,
+
+        /// The max count of the vrc721s can be minted in the collection
+        max_supply: u64,
+
+        /// The royalty for the creator, in parts per ten thousand, e.g. `500` is 5%.
+        #[arg(long, default_value = "0")]
+        royalty: u16,
+
+        /// The ext datas for the collection
+        #[arg(long)]
+        meta_data: Option<String>,
+    },
 }
 
 impl DeploySubCommands {
@@ -64,10 +83,25 @@ impl DeploySubCommands {
                 let meta_data =
                     meta_data.as_ref().map(|data| MetaData { raw: data.as_bytes().to_vec() });
 
+                let protocol_version = context.protocol_version()?;
+                let bworkc = match bworkc {
+                    // the bworkc is not checked by the indexer before the protocol version 2,
+                    // so the mints will not need the bitwork.
+                    Some(_) if protocol_version < PROTOCOL_VERSION_V2 => bail!(
+                        "the bworkc need the protocol version {} at least, current is {}",
+                        PROTOCOL_VERSION_V2,
+                        protocol_version
+                    ),
+                    Some(prefix) => Bitwork::from_hex_prefix(prefix)
+                        .with_context(|| format!("the bworkc {} is invalid", prefix))?
+                        .to_bworkc(),
+                    None => 0,
+                };
+
                 let meta = VRC20MetaData {
                     decimals: *decimals,
                     nonce: *nonce,
-                    bworkc: *bworkc,
+                    bworkc,
                     mint: VRC20MintMeta {
                         mint_amount: *mint_amount,
                         mint_height: *mint_height,
@@ -78,9 +112,22 @@ impl DeploySubCommands {
 
                 deploy_vrc20(&mut context, name.clone(), meta).await?;
             }
+            Self::VRC721 { name, max_supply, royalty, meta_data } => {
+                if *royalty > ROYALTY_BASE {
+                    bail!("the royalty must be not more than {}", ROYALTY_BASE);
+                }
+
+                let meta = VRC721MetaData {
+                    max_supply: *max_supply,
+                    royalty: *royalty,
+                    meta: meta_data.as_ref().map(|data| MetaData { raw: data.as_bytes().to_vec() }),
+                };
+
+                deploy_vrc721(&mut context, name.clone(), meta).await?;
+            }
         }
 
-        context.wallet.flush()?;
+        context.flush_wallets()?;
 
         Ok(())
     }
@@ -89,7 +136,7 @@ impl DeploySubCommands {
 async fn deploy_vrc20(context: &mut Context, name: String, meta: VRC20MetaData) -> Result<()> {
     use vital_script_builder::templates;
 
-    let name = Name::try_from(name.as_str())
+    let name = Tag::try_from(name.as_str())
         .with_context(|| format!("the '{}' name format is invalid", name))?;
     let name_resource = Resource::name(name);
 
@@ -111,3 +158,31 @@ async fn deploy_vrc20(context: &mut Context, name: String, meta: VRC20MetaData)
 
     Ok(())
 }
+
+async fn deploy_vrc721(context: &mut Context, name: String, meta: VRC721MetaData) -> Result<()> {
+    use vital_script_builder::templates;
+
+    let name = Tag::try_from(name.as_str())
+        .with_context(|| format!("the '{}' name format is invalid", name))?;
+    let name_resource = Resource::name(name);
+
+    // Got the name resource
+    let input_name_utxo = context
+        .get_owned_resource(&name_resource)
+        .ok_or_else(|| anyhow!("deploy vrc721 need required a name resource by {}", name))?;
+
+    context.append_reveal_input(&[input_name_utxo]);
+
+    // build script.
+    // all begin with 1, 0 is for script.
+    // the name will be moved to the output 0 as the owner of the collection.
+    let input_index = 1_u32;
+    let output_index = 0_u32;
+    let scripts_bytes = templates::deploy_vrc721(input_index, output_index, name, meta)
+        .context("build scripts failed")?;
+
+    // build tx then send
+    crate::send_p2tr(context, scripts_bytes).await.context("send_p2tr failed")?;
+
+    Ok(())
+}
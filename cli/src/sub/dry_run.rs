@@ -0,0 +1,96 @@
+//! Show the cost and the resources of the txs without send them.
+
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use bdk::{
+    bitcoin::{psbt::PartiallySignedTransaction, Address, Network},
+    blockchain::GetHeight,
+    psbt::PsbtUtils,
+};
+use btc_p2tr_builder::{signer, P2trBuilder};
+
+use super::Context;
+
+/// Build the commit and reveal txs and simulate the reveal tx by the local runner, then show
+/// the vsize, fees, inputs and the resource outputs.
+///
+/// The txs are signed by the placeholder signatures which had the same size, so the keys not
+/// needed, the watch-only wallet and the external signer also can dry run.
+pub(crate) async fn dry_run(context: &Context, builder: P2trBuilder<'_>) -> Result<()> {
+    let (mut commit_psbt, mut reveal_psbt) = builder.build_unsigned().context("build tx error")?;
+
+    for psbt in [&mut commit_psbt, &mut reveal_psbt] {
+        signer::sign_psbt_with_placeholder(psbt).context("placeholder sign")?;
+        signer::finalize_psbt(psbt).context("placeholder finalize")?;
+    }
+
+    let network = context.network();
+
+    println!("dry run, the txs will not be sent:");
+    let commit_fee = show_tx("commit", &commit_psbt, network)?;
+    let reveal_fee = show_tx("reveal", &reveal_psbt, network)?;
+    println!("total fee: {} sats", commit_fee + reveal_fee);
+
+    // the reveal tx will be in the next block at least.
+    let block_height = context.wallet.blockchain.get_height().context("get block height")? + 1;
+    let reveal_tx = reveal_psbt.extract_tx();
+    let reveal_txid = reveal_tx.txid();
+    let resources = context
+        .run_tx_in_local(block_height, reveal_tx.clone())
+        .await
+        .context("simulate the reveal tx failed")?
+        .ok_or_else(|| anyhow!("the reveal tx had no vital scripts"))?;
+
+    println!("reveal tx {} outputs:", reveal_txid);
+    for (index, output) in reveal_tx.output.iter().enumerate() {
+        let to = Address::from_script(&output.script_pubkey, network)
+            .map(|address| address.to_string())
+            .unwrap_or_else(|_| output.script_pubkey.to_hex_string());
+
+        println!("    output {} {}: {} sats", index, to, output.value);
+
+        for (_, resource) in resources.iter().filter(|(outpoint, _)| outpoint.vout == index as u32)
+        {
+            println!("        {}", resource);
+        }
+    }
+
+    Ok(())
+}
+
+/// Show the vsize, the fee and the inputs of the tx, return the fee.
+fn show_tx(name: &str, psbt: &PartiallySignedTransaction, network: Network) -> Result<u64> {
+    let tx = psbt.clone().extract_tx();
+
+    let mut input_amount = 0;
+    for index in 0..psbt.inputs.len() {
+        let utxo = psbt
+            .get_utxo_for(index)
+            .ok_or_else(|| anyhow!("not found the utxo for {} input {}", name, index))?;
+        input_amount += utxo.value;
+    }
+    let output_amount = tx.output.iter().map(|output| output.value).sum::<u64>();
+    let fee = input_amount
+        .checked_sub(output_amount)
+        .ok_or_else(|| anyhow!("the {} tx 's outputs more than inputs", name))?;
+
+    let vsize = tx.vsize();
+    println!(
+        "{} tx {}: {} vB, fee {} sats, {:.2} sat/vB",
+        name,
+        tx.txid(),
+        vsize,
+        fee,
+        fee as f64 / vsize as f64
+    );
+
+    for (index, input) in tx.input.iter().enumerate() {
+        let utxo = psbt.get_utxo_for(index).expect("checked above");
+        let from = Address::from_script(&utxo.script_pubkey, network)
+            .map(|address| address.to_string())
+            .unwrap_or_else(|_| utxo.script_pubkey.to_hex_string());
+
+        println!("    input {} {}: {} sats", input.previous_output, from, utxo.value);
+    }
+
+    Ok(fee)
+}
@@ -1,6 +1,13 @@
+use std::path::{Path, PathBuf};
+
 use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
 use clap::Subcommand;
-use vital_script_primitives::{resources::Name, traits::EnvContext};
+use vital_script_primitives::{
+    consts::PROTOCOL_VERSION_V2,
+    resources::{Resource, Tag, VRC721},
+    traits::EnvContext,
+    types::bitwork::Bitwork,
+};
 
 use crate::Cli;
 
@@ -8,54 +15,116 @@ use super::context::{build_context, Context};
 
 #[derive(Debug, Subcommand)]
 pub enum MintSubCommands {
-    /// Mint Name resource.
+    /// Mint Name resources, multiple names will be minted in one reveal tx, each by a script.
     Name {
-        /// The name to mint
-        name: String,
+        /// The names to mint
+        #[arg(required = true)]
+        names: Vec<String>,
+        /// Use distributed mint, the reveal tx will be sent after the commit tx confirmed.
+        #[arg(long)]
+        dmint: bool,
     },
     /// Mint VRC20 resource by it 's name.
     VRC20 {
         /// The vrc20 's name to mint.
         vrc20_name: String,
+        /// Use distributed mint, the reveal tx will be sent after the commit tx confirmed.
+        #[arg(long)]
+        dmint: bool,
+    },
+    /// Mint VRC721 resource by the content of a file, the resource is the sha256 of the content.
+    VRC721 {
+        /// The file of the content to mint.
+        file: PathBuf,
+        /// Mint the vrc721 in the collection by its name.
+        #[arg(long)]
+        collection: Option<String>,
+        /// Use distributed mint, the reveal tx will be sent after the commit tx confirmed.
+        #[arg(long, conflicts_with = "collection")]
+        dmint: bool,
     },
 }
 
 impl MintSubCommands {
     pub(crate) async fn run(&self, cli: &Cli) -> Result<()> {
-        let context = build_context(cli).await?;
+        let mut context = build_context(cli).await?;
 
         match self {
-            Self::Name { name } => {
-                mint_name(&context, name.clone()).await?;
+            Self::Name { names, dmint } => {
+                if let [name] = names.as_slice() {
+                    mint_name(&context, name.clone(), *dmint).await?;
+                } else {
+                    mint_names(&mut context, names, *dmint).await?;
+                }
+            }
+            Self::VRC20 { vrc20_name, dmint } => {
+                mint_vrc20(&context, vrc20_name.clone(), *dmint).await?;
             }
-            Self::VRC20 { vrc20_name } => {
-                mint_vrc20(&context, vrc20_name.clone()).await?;
+            Self::VRC721 { file, collection, dmint } => {
+                mint_vrc721(&mut context, file, collection.as_deref(), *dmint).await?;
             }
         }
 
-        context.wallet.flush()?;
+        context.flush_wallets()?;
 
         Ok(())
     }
 }
 
-async fn mint_name(context: &Context, name: String) -> Result<()> {
+async fn mint_name(context: &Context, name: String, dmint: bool) -> Result<()> {
     use vital_script_builder::templates;
 
-    // build script.
+    // build script, then build tx and send
     let output_index = 0_u32;
-    let scripts_bytes = templates::mint_name(output_index, name).context("build scripts failed")?;
+    if dmint {
+        let scripts_bytes =
+            templates::dmint_name(output_index, name).context("build scripts failed")?;
+        crate::send_p2tr_for_dmint(context, scripts_bytes, None)
+            .await
+            .context("send_p2tr failed")?;
+    } else {
+        let scripts_bytes =
+            templates::mint_name(output_index, name).context("build scripts failed")?;
+        crate::send_p2tr(context, scripts_bytes).await.context("send_p2tr failed")?;
+    }
+
+    Ok(())
+}
+
+/// Mint the names in one reveal tx, the name in the script i will be minted to the output i.
+async fn mint_names(context: &mut Context, names: &[String], dmint: bool) -> Result<()> {
+    use vital_script_builder::templates;
 
-    // build tx then send
-    crate::send_p2tr(context, scripts_bytes).await.context("send_p2tr failed")?;
+    let scripts_bytes = names
+        .iter()
+        .enumerate()
+        .map(|(output_index, name)| {
+            let output_index = output_index as u32;
+            let scripts_bytes = if dmint {
+                templates::dmint_name(output_index, name.clone())
+            } else {
+                templates::mint_name(output_index, name.clone())
+            };
+
+            scripts_bytes.with_context(|| format!("build scripts for {} failed", name))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    context
+        .set_outputs_from(0, names.len(), context.sats_amount)
+        .context("set outputs")?;
+
+    crate::send_p2tr_batch(context, scripts_bytes, dmint)
+        .await
+        .context("send_p2tr failed")?;
 
     Ok(())
 }
 
-async fn mint_vrc20(context: &Context, vrc20_name: String) -> Result<()> {
+async fn mint_vrc20(context: &Context, vrc20_name: String, dmint: bool) -> Result<()> {
     use vital_script_builder::templates;
 
-    let name = Name::try_from(vrc20_name.as_str())
+    let name = Tag::try_from(vrc20_name.as_str())
         .with_context(|| format!("the vrc20 name {} format invalid", vrc20_name))?;
 
     let vrc20_metadata = context
@@ -74,6 +143,7 @@ async fn mint_vrc20(context: &Context, vrc20_name: String) -> Result<()> {
     if vrc20_metadata.meta.mint.mint_height > 0 {
         let current_height = context.get_btc_block_height()?;
 
+        // the tx will be in the next block at least, the runner need its height >= mint_height.
         if current_height + 1 < vrc20_metadata.meta.mint.mint_height {
             bail!(
                 "the vrc20 mint height is {}, and the current height is {}, so the mint will failed",
@@ -82,13 +152,111 @@ async fn mint_vrc20(context: &Context, vrc20_name: String) -> Result<()> {
         }
     }
 
-    // build script.
+    // the bitwork is checked by the indexer since the protocol version 2, the vrc20 deployed
+    // before with an invalid bworkc not need it.
+    let bitwork = if context.protocol_version()? >= PROTOCOL_VERSION_V2 {
+        Bitwork::from_bworkc(vrc20_metadata.meta.bworkc).ok().flatten()
+    } else {
+        None
+    };
+    if let Some(bitwork) = bitwork {
+        println!("the vrc20 {} mint need the reveal txid match bitwork {}", name, bitwork);
+    }
+
+    // build script, then build tx and send
     let output_index = 0_u32;
-    let scripts_bytes =
-        templates::mint_vrc20(output_index, name).context("build scripts failed")?;
+    if dmint {
+        let scripts_bytes =
+            templates::dmint_vrc20(output_index, name).context("build scripts failed")?;
+        crate::send_p2tr_for_dmint(context, scripts_bytes, bitwork)
+            .await
+            .context("send_p2tr failed")?;
+    } else {
+        let scripts_bytes =
+            templates::mint_vrc20(output_index, name).context("build scripts failed")?;
+        crate::send_p2tr_with_bitwork(context, scripts_bytes, bitwork)
+            .await
+            .context("send_p2tr failed")?;
+    }
+
+    Ok(())
+}
 
-    // build tx then send
-    crate::send_p2tr(context, scripts_bytes).await.context("send_p2tr failed")?;
+async fn mint_vrc721(
+    context: &mut Context,
+    file: &Path,
+    collection: Option<&str>,
+    dmint: bool,
+) -> Result<()> {
+    use vital_script_builder::templates;
+
+    let content =
+        std::fs::read(file).with_context(|| format!("read content from {}", file.display()))?;
+    let vrc721 = VRC721::from_content(&content);
+
+    if context
+        .query_env_context
+        .vrc721_had_mint(vrc721.hash)
+        .context("get vrc721 had mint")?
+    {
+        bail!("the vrc721 {:?} had been minted, so the mint will failed", vrc721.hash);
+    }
+
+    println!("mint vrc721 {:?} by the content of {}", vrc721.hash, file.display());
+
+    // build script, then build tx and send
+    let output_index = 0_u32;
+    if let Some(collection) = collection {
+        let collection = Tag::try_from(collection)
+            .with_context(|| format!("the collection name {} format invalid", collection))?;
+
+        let status_data = context
+            .query_env_context
+            .get_vrc721_metadata(collection)
+            .context("get vrc721 metadata")?
+            .ok_or_else(|| anyhow!("not found vrc721 collection by {}", collection))?;
+
+        if status_data.mint_count >= status_data.meta.max_supply {
+            bail!(
+                "the collection mint count is {}, and it had reached it 's max supply {}, so the mint will failed",
+                status_data.mint_count, status_data.meta.max_supply
+            );
+        }
+
+        // only the owner of the collection can mint, so the name should be in the inputs.
+        let input_name_utxo =
+            context.get_owned_resource(&Resource::Name(collection)).ok_or_else(|| {
+                anyhow!("mint in collection need required the name by {}", collection)
+            })?;
+
+        context.append_reveal_input(&[input_name_utxo]);
+        context
+            .set_outputs_from(output_index, 2, context.sats_amount)
+            .context("set outputs")?;
+
+        // the input begin with 1, 0 is for script, the name will be moved back to output 1.
+        let input_index = 1_u32;
+        let owner_output_index = output_index + 1;
+        let scripts_bytes = templates::mint_vrc721_in_collection(
+            input_index,
+            output_index,
+            owner_output_index,
+            collection,
+            vrc721.hash,
+        )
+        .context("build scripts failed")?;
+        crate::send_p2tr(context, scripts_bytes).await.context("send_p2tr failed")?;
+    } else if dmint {
+        let scripts_bytes =
+            templates::dmint_vrc721(output_index, vrc721.hash).context("build scripts failed")?;
+        crate::send_p2tr_for_dmint(context, scripts_bytes, None)
+            .await
+            .context("send_p2tr failed")?;
+    } else {
+        let scripts_bytes =
+            templates::mint_vrc721(output_index, vrc721.hash).context("build scripts failed")?;
+        crate::send_p2tr(context, scripts_bytes).await.context("send_p2tr failed")?;
+    }
 
     Ok(())
 }
@@ -1,65 +1,277 @@
 mod context;
+mod dry_run;
 
+pub mod burn;
 pub mod deploy;
 pub mod mint;
 pub mod move_resource;
 pub mod query;
+pub mod tx;
 pub mod utils;
 pub mod wallet;
 
-const MAX_SCRIPT_LEN: usize = 480;
+const MAX_SCRIPT_LEN: usize = vital_script_primitives::consts::MAX_SCRIPT_DATAS_LEN;
 
+/// The interval to check if the commit tx confirmed for dmint.
+const WAIT_CONFIRMED_INTERVAL: Duration = Duration::from_secs(30);
+
+use std::time::Duration;
+
+use ::wallet::Wallet;
 use anyhow::{bail, Context as AnyhowContext, Result};
-use bdk::blockchain::Blockchain;
-use btc_p2tr_builder::P2trBuilder;
+use bdk::{
+    bitcoin::{Transaction, Txid},
+    blockchain::{AnyBlockchain, Blockchain, GetTx},
+    SyncOptions,
+};
+use btc_p2tr_builder::{CommitRevealPsbts, P2trBuilder};
+use client::sent_p2tr::{SentP2tr, SentStatus};
+use vital_script_primitives::types::bitwork::Bitwork;
 
 pub(crate) use context::{build_context, Context};
+pub(crate) use dry_run::dry_run;
 
 pub(crate) async fn send_p2tr(context: &Context, scripts_bytes: Vec<u8>) -> Result<()> {
-    let wallet = &context.wallet;
-    let bdk_blockchain = &wallet.blockchain;
-
-    if scripts_bytes.len() >= MAX_SCRIPT_LEN {
-        bail!(
-            "the script must be less then {}, currently is {}",
-            MAX_SCRIPT_LEN,
-            scripts_bytes.len()
-        );
+    send_p2tr_with_bitwork(context, scripts_bytes, None).await
+}
+
+/// Send the p2tr txs, the reveal tx will be mined to match the bitwork if need.
+pub(crate) async fn send_p2tr_with_bitwork(
+    context: &Context,
+    scripts_bytes: Vec<u8>,
+    bitwork: Option<Bitwork>,
+) -> Result<()> {
+    send_p2tr_impl(context, vec![scripts_bytes], bitwork, false).await
+}
+
+/// Send the p2tr txs for dmint, the reveal tx will be sent after the commit tx confirmed.
+pub(crate) async fn send_p2tr_for_dmint(
+    context: &Context,
+    scripts_bytes: Vec<u8>,
+    bitwork: Option<Bitwork>,
+) -> Result<()> {
+    send_p2tr_impl(context, vec![scripts_bytes], bitwork, true).await
+}
+
+/// Send the p2tr txs with multiple vital scripts, the commit tx has an output for each script,
+/// and the reveal tx spend them by the order of the scripts.
+pub(crate) async fn send_p2tr_batch(
+    context: &Context,
+    scripts_bytes: Vec<Vec<u8>>,
+    wait_commit_confirmed: bool,
+) -> Result<()> {
+    send_p2tr_impl(context, scripts_bytes, None, wait_commit_confirmed).await
+}
+
+async fn send_p2tr_impl(
+    context: &Context,
+    scripts_bytes: Vec<Vec<u8>>,
+    bitwork: Option<Bitwork>,
+    wait_commit_confirmed: bool,
+) -> Result<()> {
+    for (index, bytes) in scripts_bytes.iter().enumerate() {
+        if bytes.len() > MAX_SCRIPT_LEN {
+            bail!(
+                "the script {} must be not more than {}, currently is {}",
+                index,
+                MAX_SCRIPT_LEN,
+                bytes.len()
+            );
+        }
+
+        println!("scripts_bytes {}", hex::encode(bytes));
     }
 
-    println!("scripts_bytes {}", hex::encode(&scripts_bytes));
+    let builder = P2trBuilder::new_with_datas(context, scripts_bytes)
+        .context("builder build")?
+        .with_bitwork(bitwork);
 
-    let builder = P2trBuilder::new(context, scripts_bytes).context("builder build")?;
+    if context.dry_run {
+        return dry_run(context, builder).await;
+    }
 
-    let (commit_psbt, reveal_psbt) = builder.build().context("build tx error")?;
+    if let Some(psbt_out) = &context.psbt_out {
+        return save_unsigned_psbts(context, builder, psbt_out, wait_commit_confirmed);
+    }
 
-    let outpoints_used = commit_psbt
-        .unsigned_tx
-        .input
-        .iter()
-        .map(|input| input.previous_output)
-        .collect::<Vec<_>>();
+    send_p2tr_by_builder(context, builder, wait_commit_confirmed, None).await
+}
 
-    let commit_raw_transaction = commit_psbt.extract_tx();
-    let commit_txid = commit_raw_transaction.txid();
+/// Build, sign and send the txs, the txs will be saved to the journal before sent, so can be
+/// resumed or bumped, if `replaced` the txs will replace the old ones.
+pub(crate) async fn send_p2tr_by_builder(
+    context: &Context,
+    builder: P2trBuilder<'_>,
+    wait_commit_confirmed: bool,
+    replaced: Option<&SentP2tr>,
+) -> Result<()> {
+    let datas = builder.datas().to_vec();
+    let reveal_inputs = builder.reveal_inputs().to_vec();
+    let outputs = builder.outputs().to_vec();
+    let bitwork = builder.bitwork().copied();
+
+    let (commit_psbt, reveal_psbt) = builder.build().context("build tx error")?;
 
     println!("tx: {}", serde_json::to_string_pretty(&reveal_psbt.unsigned_tx).expect("to"));
 
+    let commit_raw_transaction = commit_psbt.extract_tx();
     let reveal_raw_transaction = reveal_psbt.extract_tx();
 
     println!("tx: {}", serde_json::to_string_pretty(&reveal_raw_transaction).expect("to"));
 
+    let mut sent = SentP2tr::new(
+        &commit_raw_transaction,
+        &reveal_raw_transaction,
+        &datas,
+        &reveal_inputs,
+        &outputs,
+        bitwork.as_ref(),
+        wait_commit_confirmed,
+    );
+    sent.save(&context.root_path).context("save the txs to journal")?;
+
+    broadcast_sent_p2tr(context, &mut sent, replaced).await
+}
+
+/// Send the txs in the journal from its status, the status will be updated after each sent.
+pub(crate) async fn broadcast_sent_p2tr(
+    context: &Context,
+    sent: &mut SentP2tr,
+    replaced: Option<&SentP2tr>,
+) -> Result<()> {
+    let root = &context.root_path;
+    let bdk_blockchain = &context.wallet.blockchain;
+
+    let commit_raw_transaction = sent.commit_tx()?;
+    let commit_txid = commit_raw_transaction.txid();
+    let reveal_raw_transaction = sent.reveal_tx()?;
     let reveal_txid = reveal_raw_transaction.txid();
 
-    bdk_blockchain.broadcast(&commit_raw_transaction)?;
-    println!("Commit Transaction broadcast! TXID: {txid}.\nExplorer URL: https://mempool.space/testnet/tx/{txid}", txid = commit_txid);
+    if sent.status == SentStatus::Built {
+        broadcast_if_unknown(bdk_blockchain, &commit_raw_transaction)?;
+        println!("Commit Transaction broadcast! TXID: {txid}.\nExplorer URL: https://mempool.space/testnet/tx/{txid}", txid = commit_txid);
 
-    bdk_blockchain.broadcast(&reveal_raw_transaction)?;
-    println!("Reveal Transaction broadcast! TXID: {txid}.\nExplorer URL: https://mempool.space/testnet/tx/{txid}", txid = reveal_txid);
+        sent.update_status(root, SentStatus::CommitSent).context("update journal")?;
 
+        let outpoints_used = commit_raw_transaction
+            .input
+            .iter()
+            .map(|input| input.previous_output)
+            .collect::<Vec<_>>();
+        let saved = match replaced {
+            Some(replaced) => {
+                let replaced_used = replaced
+                    .commit_tx()
+                    .context("the replaced commit tx")?
+                    .input
+                    .iter()
+                    .map(|input| input.previous_output)
+                    .collect::<Vec<_>>();
+
+                SentP2tr::remove(root, &replaced.commit_txid).context("remove the replaced txs")?;
+                context.replace_used_utxos(&replaced_used, &outpoints_used)
+            }
+            None => context.append_used_utxos(&outpoints_used),
+        };
+        if let Err(err) = saved {
+            println!("save used utxos failed by {}, should use --sync to ensure synced", err);
+        };
+
+        // the change of the commit tx is in the fee wallet.
+        context.fee_wallet().flush().context("flush fee wallet")?;
+    }
+
+    if sent.status == SentStatus::CommitSent {
+        if sent.wait_commit_confirmed {
+            println!(
+                "the reveal tx will be broadcast after the commit tx confirmed, if exit, can send it by `tx resume {}`",
+                commit_txid
+            );
+            wait_tx_confirmed(context.fee_wallet(), &commit_txid)
+                .await
+                .context("wait commit tx confirmed")?;
+        }
+
+        broadcast_if_unknown(bdk_blockchain, &reveal_raw_transaction).with_context(|| {
+            format!(
+                "send reveal tx failed, can retry by `tx resume {}` or get back the commit outputs by `tx recover {}`",
+                commit_txid, commit_txid
+            )
+        })?;
+        println!("Reveal Transaction broadcast! TXID: {txid}.\nExplorer URL: https://mempool.space/testnet/tx/{txid}", txid = reveal_txid);
+
+        sent.update_status(root, SentStatus::RevealSent).context("update journal")?;
+    }
+
+    Ok(())
+}
+
+/// Broadcast the tx if the electrum not known it, the tx may be sent before the journal updated.
+pub(crate) fn broadcast_if_unknown(blockchain: &AnyBlockchain, tx: &Transaction) -> Result<()> {
+    if let Ok(Some(_)) = blockchain.get_tx(&tx.txid()) {
+        println!("the tx {} had been sent", tx.txid());
+        return Ok(());
+    }
+
+    blockchain.broadcast(tx).context("broadcast")
+}
+
+/// Save the unsigned commit and reveal psbts to file, they can be signed by `utils sign-psbt`
+/// then broadcast by `utils broadcast-psbt`.
+fn save_unsigned_psbts(
+    context: &Context,
+    builder: P2trBuilder<'_>,
+    psbt_out: &std::path::Path,
+    wait_commit_confirmed: bool,
+) -> Result<()> {
+    let (commit_psbt, reveal_psbt) = builder.build_unsigned().context("build tx error")?;
+
+    CommitRevealPsbts::new(&commit_psbt, &reveal_psbt, wait_commit_confirmed)?
+        .save(psbt_out)
+        .context("save psbts")?;
+
+    let outpoints_used = commit_psbt
+        .unsigned_tx
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect::<Vec<_>>();
     if let Err(err) = context.append_used_utxos(&outpoints_used) {
         println!("save used utxos failed by {}, should use --sync to ensure synced", err);
     };
 
+    context.fee_wallet().flush().context("flush fee wallet")?;
+
+    println!(
+        "the unsigned psbts for commit tx {} and reveal tx {} saved to {}",
+        commit_psbt.unsigned_tx.txid(),
+        reveal_psbt.unsigned_tx.txid(),
+        psbt_out.display()
+    );
+
     Ok(())
 }
+
+/// Wait the tx confirmed, the tx should be related to the wallet, need the multi-thread runtime.
+pub(crate) async fn wait_tx_confirmed(wallet: &Wallet, txid: &Txid) -> Result<()> {
+    loop {
+        // the sync is blocking, and the wallet can not be sent to other threads, so run it in
+        // place and let the runtime move the other tasks away.
+        tokio::task::block_in_place(|| {
+            wallet.wallet.sync(&wallet.blockchain, SyncOptions::default()).context("sync")
+        })?;
+
+        let confirmation_time = wallet
+            .wallet
+            .get_tx(txid, false)
+            .context("get tx")?
+            .and_then(|details| details.confirmation_time);
+        if let Some(confirmation_time) = confirmation_time {
+            println!("the tx {} confirmed in block {}", txid, confirmation_time.height);
+            return Ok(());
+        }
+
+        println!("waiting the tx {} confirmed", txid);
+        tokio::time::sleep(WAIT_CONFIRMED_INTERVAL).await;
+    }
+}
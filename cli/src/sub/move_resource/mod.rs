@@ -6,9 +6,11 @@ use crate::{build_context, Cli};
 
 mod name;
 mod vrc20;
+mod vrc721;
 
 use name::*;
 use vrc20::*;
+use vrc721::*;
 
 #[derive(Debug, Subcommand)]
 pub enum MoveSubCommands {
@@ -29,6 +31,11 @@ pub enum MoveSubCommands {
         /// The amount
         amount: u128,
     },
+    /// Move vrc721s to outpoint.
+    VRC721 {
+        /// The hashes of the vrc721s to move, in hex.
+        hashes: Vec<String>,
+    },
 }
 
 impl MoveSubCommands {
@@ -45,9 +52,12 @@ impl MoveSubCommands {
             MoveSubCommands::VRC20 { name, amount } => {
                 move_vrc20(&mut context, name, U256::from(*amount)).await?;
             }
+            MoveSubCommands::VRC721 { hashes } => {
+                move_vrc721s(&mut context, hashes).await?;
+            }
         }
 
-        context.wallet.flush()?;
+        context.flush_wallets()?;
 
         Ok(())
     }
@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Context as AnyhowContext, Result};
 
-use vital_script_primitives::resources::{Name, Resource};
+use vital_script_primitives::resources::{Resource, Tag};
 
 use crate::Context;
 
@@ -11,7 +11,7 @@ pub async fn move_names(context: &mut Context, names: &[String]) -> Result<()> {
     let mut move_names = Vec::with_capacity(names.len());
 
     for name in names.iter() {
-        let name = Name::try_from(name.as_str())
+        let name = Tag::try_from(name.as_str())
             .with_context(|| format!("the '{}' name format is invalid", name))?;
 
         let name_resource = Resource::name(name);
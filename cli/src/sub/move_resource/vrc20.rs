@@ -1,6 +1,6 @@
 use anyhow::{bail, Context as AnyhowContext, Result};
 
-use vital_script_primitives::{resources::Name, U256};
+use vital_script_primitives::{resources::Tag, U256};
 
 use crate::Context;
 
@@ -8,7 +8,7 @@ pub async fn move_vrc20(context: &mut Context, name: &String, amount: U256) -> R
     use vital_script_builder::templates;
 
     let vrc20_name =
-        Name::try_from(name.clone()).with_context(|| format!("name {} format invalid", name))?;
+        Tag::try_from(name.clone()).with_context(|| format!("name {} format invalid", name))?;
 
     let (sum, mut owned_vrc20s) = context
         .fetch_all_vrc20_by_name(vrc20_name)
@@ -0,0 +1,60 @@
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+
+use vital_script_primitives::{resources::Resource, H256};
+
+use crate::Context;
+
+pub async fn move_vrc721s(context: &mut Context, hashes: &[String]) -> Result<()> {
+    use vital_script_builder::templates;
+
+    if hashes.is_empty() {
+        bail!("need at least one vrc721 to move");
+    }
+
+    let hashes = hashes
+        .iter()
+        .map(|hash| {
+            parse_vrc721_hash(hash).with_context(|| format!("the hash {} is invalid", hash))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut utxos = Vec::with_capacity(hashes.len());
+
+    for hash in hashes.iter() {
+        let vrc721_resource = Resource::vrc721(*hash);
+
+        // Got the vrc721 resource
+        let input_vrc721_utxo = context
+            .get_owned_resource(&vrc721_resource)
+            .ok_or_else(|| anyhow!("move vrc721 need required a vrc721 resource by {:?}", hash))?;
+
+        utxos.push(input_vrc721_utxo);
+    }
+
+    context.append_reveal_input(&utxos);
+
+    let startup_output_index = 0;
+    context
+        .set_outputs_from(startup_output_index, hashes.len(), context.sats_amount)
+        .context("set outputs")?;
+
+    // build script.
+    // all begin with 0.
+    let scripts_bytes = templates::move_vrc721s(&hashes, Some(startup_output_index))
+        .context("build scripts failed")?;
+
+    // build tx then send
+    crate::send_p2tr(context, scripts_bytes).await.context("send_p2tr failed")?;
+
+    Ok(())
+}
+
+/// Parse the vrc721 hash from hex, the `0x` prefix is optional.
+fn parse_vrc721_hash(hash: &str) -> Result<H256> {
+    let bytes = hex::decode(hash.trim_start_matches("0x")).context("decode hex")?;
+    if bytes.len() != 32 {
+        bail!("the hash len should be 32, got {}", bytes.len());
+    }
+
+    Ok(H256::from_slice(&bytes))
+}
@@ -2,11 +2,16 @@ use anyhow::Result;
 use clap::Subcommand;
 
 mod resources;
+mod space;
 mod vrc20;
+mod vrc721;
 
 use crate::Cli;
 
-use self::{resources::QueryResources, vrc20::QueryVrc20Metadata};
+use self::{
+    resources::QueryResources, space::QuerySpace, vrc20::QueryVrc20Metadata,
+    vrc721::QueryVrc721Metadata,
+};
 
 #[derive(Debug, Subcommand)]
 pub enum QuerySubCommands {
@@ -14,6 +19,10 @@ pub enum QuerySubCommands {
     Resources(QueryResources),
     /// Query vrc20 metadata
     Vrc20Metadata(QueryVrc20Metadata),
+    /// Query vrc721 collection metadata
+    Vrc721Metadata(QueryVrc721Metadata),
+    /// Query the resources in the space of a tx, such as the resources parked by it.
+    Space(QuerySpace),
 }
 
 impl QuerySubCommands {
@@ -21,6 +30,8 @@ impl QuerySubCommands {
         match self {
             Self::Resources(q) => q.run(cli).await?,
             Self::Vrc20Metadata(q) => q.run(cli).await?,
+            Self::Vrc721Metadata(q) => q.run(cli).await?,
+            Self::Space(q) => q.run(cli).await?,
         }
 
         Ok(())
@@ -0,0 +1,35 @@
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use bdk::bitcoin::Txid;
+use clap::Parser;
+
+use crate::{sub::context::build_context, Cli};
+
+#[derive(Debug, Parser)]
+#[command(name = "query space", about = "Query the resources in the space of a tx")]
+pub struct QuerySpace {
+    /// The txid of the tx, the resources can be claimed by its first output.
+    txid: String,
+}
+
+impl QuerySpace {
+    pub async fn run(&self, cli: &Cli) -> Result<()> {
+        let context = build_context(cli).await.context("build context")?;
+
+        let txid = Txid::from_str(&self.txid).context("parse txid")?;
+        let resources = context.get_space_resources(&txid).await?;
+
+        if resources.is_empty() {
+            println!("no resources in the space of {}", txid);
+            return Ok(());
+        }
+
+        println!("find {} resources in the space of {}", resources.len(), txid);
+        for (index, resource) in resources.iter().enumerate() {
+            println!("{}. {}", index, resource);
+        }
+
+        Ok(())
+    }
+}
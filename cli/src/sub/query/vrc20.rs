@@ -1,7 +1,7 @@
 use anyhow::{anyhow, Context, Result};
 use clap::Parser;
 
-use vital_script_primitives::{resources::Name, traits::EnvContext};
+use vital_script_primitives::{resources::Tag, traits::EnvContext};
 
 use crate::{sub::context::build_context, Cli};
 
@@ -15,7 +15,7 @@ impl QueryVrc20Metadata {
     pub async fn run(&self, cli: &Cli) -> Result<()> {
         let context = build_context(cli).await.context("build context")?;
 
-        let name = Name::try_from(self.name.as_str())
+        let name = Tag::try_from(self.name.as_str())
             .with_context(|| format!("the vrc20 name {} format invalid", self.name))?;
 
         let vrc20_metadata = context
@@ -24,7 +24,13 @@ impl QueryVrc20Metadata {
             .context("get vrc20 metadata")?
             .ok_or_else(|| anyhow!("not found vrc20 metadata by {}", name))?;
 
+        let burned =
+            context.query_env_context.get_vrc20_burned(name).context("get vrc20 burned")?;
+
         println!("metadata: {}", serde_json::to_string_pretty(&vrc20_metadata).expect("json"));
+        println!("minted: {}", vrc20_metadata.minted());
+        println!("burned: {}", burned);
+        println!("circulating: {}", vrc20_metadata.circulating(burned));
 
         Ok(())
     }
@@ -0,0 +1,31 @@
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
+
+use vital_script_primitives::{resources::Tag, traits::EnvContext};
+
+use crate::{sub::context::build_context, Cli};
+
+#[derive(Debug, Parser)]
+#[command(name = "query vrc721 metadata", about = "Query vrc721 collection metadata by name")]
+pub struct QueryVrc721Metadata {
+    name: String,
+}
+
+impl QueryVrc721Metadata {
+    pub async fn run(&self, cli: &Cli) -> Result<()> {
+        let context = build_context(cli).await.context("build context")?;
+
+        let name = Tag::try_from(self.name.as_str())
+            .with_context(|| format!("the collection name {} format invalid", self.name))?;
+
+        let vrc721_metadata = context
+            .query_env_context
+            .get_vrc721_metadata(name)
+            .context("get vrc721 metadata")?
+            .ok_or_else(|| anyhow!("not found vrc721 metadata by {}", name))?;
+
+        println!("metadata: {}", serde_json::to_string_pretty(&vrc721_metadata).expect("json"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,571 @@
+use std::{collections::BTreeSet, str::FromStr};
+
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use bdk::{
+    bitcoin::{
+        psbt::PartiallySignedTransaction, secp256k1::Secp256k1, OutPoint, Transaction, Txid,
+    },
+    blockchain::{Blockchain, GetHeight, GetTx},
+    wallet::AddressIndex,
+    LocalUtxo,
+};
+use clap::Subcommand;
+
+use btc_p2tr_builder::{cpfp_output_index, signer, P2trBuilder};
+use client::{
+    fee_rate::FeeRateArg,
+    sent_p2tr::{SentP2tr, SentStatus},
+};
+use wallet::Wallet;
+
+use crate::{build_context, Cli, Context};
+
+/// The weight of the witness for the taproot key spend: the count, the len and the signature.
+const TAPROOT_KEY_SPEND_SATISFACTION_WEIGHT: usize = 66;
+
+/// The max times to build the child tx, until the fee is enough for its vsize.
+const CPFP_BUILD_ROUNDS: usize = 3;
+
+/// The min relay fee rate in sat/vB, the child tx self should pay at least it.
+const MIN_RELAY_FEE_RATE: f32 = 1.0;
+
+#[derive(Debug, Subcommand)]
+pub enum TxSubCommands {
+    /// Bump the fee of the commit tx and its reveal tx by RBF, the commit tx should be sent with
+    /// `--replaceable`.
+    Bump {
+        /// The txid of the commit tx to bump.
+        commit_txid: String,
+
+        /// The new fee rate in sat/vB, or `fast`, `normal`, `slow`, should be higher than the
+        /// old one.
+        #[arg(short, long)]
+        fee_rate: FeeRateArg,
+    },
+
+    /// Accelerate the pending reveal tx by CPFP, the child tx spend an output without resources
+    /// of the reveal tx, and the utxos from the fee wallet if need, the reveal tx should be sent
+    /// with `--cpfp-anchor`.
+    Cpfp {
+        /// The txid of the reveal tx to accelerate.
+        reveal_txid: String,
+
+        /// The fee rate for the package of the child tx and its pending parents, in sat/vB or
+        /// `fast`, `normal`, `slow`.
+        #[arg(short, long)]
+        fee_rate: FeeRateArg,
+    },
+
+    /// List the commit and reveal txs which the reveal tx not sent.
+    Pending,
+
+    /// Send the commit and reveal txs in the journal which not sent, such as the reveal tx
+    /// failed to send.
+    Resume {
+        /// The txid of the commit tx to resume.
+        commit_txid: String,
+    },
+
+    /// Spend the commit outputs back to the fee wallet by the script path, for the reveal tx
+    /// can not be sent.
+    Recover {
+        /// The txid of the commit tx to recover.
+        commit_txid: String,
+
+        /// The fee rate in sat/vB, or `fast`, `normal`, `slow`, default is 1 sat/vB.
+        #[arg(short, long)]
+        fee_rate: Option<FeeRateArg>,
+    },
+
+    /// Claim the resources in the space of the tx, such as the resources parked by it when it
+    /// spent them by mistake, the first output of the tx should be in the wallet.
+    Claim {
+        /// The txid of the tx which the resources in its space.
+        txid: String,
+    },
+}
+
+impl TxSubCommands {
+    pub(crate) async fn run(&self, cli: &Cli) -> Result<()> {
+        match self {
+            Self::Bump { commit_txid, fee_rate } => {
+                let commit_txid = Txid::from_str(commit_txid).context("parse commit txid")?;
+
+                bump(cli, &commit_txid, fee_rate).await?;
+            }
+            Self::Cpfp { reveal_txid, fee_rate } => {
+                let reveal_txid = Txid::from_str(reveal_txid).context("parse reveal txid")?;
+
+                cpfp(cli, &reveal_txid, fee_rate).await?;
+            }
+            Self::Pending => {
+                pending(cli)?;
+            }
+            Self::Resume { commit_txid } => {
+                let commit_txid = Txid::from_str(commit_txid).context("parse commit txid")?;
+
+                resume(cli, &commit_txid).await?;
+            }
+            Self::Recover { commit_txid, fee_rate } => {
+                let commit_txid = Txid::from_str(commit_txid).context("parse commit txid")?;
+
+                recover(cli, &commit_txid, fee_rate).await?;
+            }
+            Self::Claim { txid } => {
+                let txid = Txid::from_str(txid).context("parse txid")?;
+
+                claim(cli, &txid).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Rebuild the commit and reveal txs with the same vital scripts, the resource inputs and the
+/// outputs, then sign and send them to replace the old ones.
+async fn bump(cli: &Cli, commit_txid: &Txid, fee_rate: &FeeRateArg) -> Result<()> {
+    let sent = SentP2tr::load(&cli.datadir, commit_txid)?;
+    if sent.status == SentStatus::Recovered {
+        bail!("the commit tx {} had been recovered", commit_txid);
+    }
+
+    let mut context = build_context(cli).await?.with_fee_rate(&Some(*fee_rate))?;
+
+    // the replacement should be accepted by BIP 125, so check it before rebuild.
+    let commit_tx = sent.commit_tx()?;
+    if !commit_tx.input.iter().any(|input| input.sequence.is_rbf()) {
+        bail!(
+            "the commit tx {} not signal RBF, it should be sent with `--replaceable`, can use `tx cpfp` instead",
+            commit_txid
+        );
+    }
+
+    let new_fee_rate = context.fee_rate.expect("the fee rate had set");
+    let commit_fee = tx_fee(&context, &commit_tx)?;
+    let original_fee_rate = commit_fee as f32 / commit_tx.vsize() as f32;
+    if new_fee_rate <= original_fee_rate {
+        bail!(
+            "the new fee rate {} sat/vB should be higher than the original {:.2} sat/vB",
+            new_fee_rate,
+            original_fee_rate
+        );
+    }
+
+    // the outputs in the journal had the cpfp anchor if had.
+    context.outputs = sent
+        .outputs(context.network())?
+        .into_iter()
+        .map(|(to, amount)| (Some(to), amount))
+        .collect();
+    context.cpfp_anchor = false;
+    context.reveal_inputs = sent.reveal_inputs.clone();
+
+    // the reveal tx in mempool will be evicted too, the new commit tx should pay for it.
+    let replaced_fee = if sent.status == SentStatus::RevealSent {
+        commit_fee + tx_fee(&context, &sent.reveal_tx()?)?
+    } else {
+        commit_fee
+    };
+
+    let builder = P2trBuilder::new_with_datas(&context, sent.datas()?)
+        .context("builder build")?
+        .with_bitwork(sent.bitwork()?)
+        .with_replaced(sent.commit_txid, sent.reveal_txid, replaced_fee);
+
+    if context.dry_run {
+        return crate::dry_run(&context, builder).await;
+    }
+
+    crate::send_p2tr_by_builder(&context, builder, sent.wait_commit_confirmed, Some(&sent))
+        .await
+        .context("send the replacement txs failed")?;
+
+    context.flush_wallets()?;
+
+    println!("the commit tx {} and the reveal tx {} replaced", sent.commit_txid, sent.reveal_txid);
+
+    Ok(())
+}
+
+fn pending(cli: &Cli) -> Result<()> {
+    let pendings = SentP2tr::list(&cli.datadir)?
+        .into_iter()
+        .filter(|sent| sent.status.is_pending())
+        .collect::<Vec<_>>();
+
+    if pendings.is_empty() {
+        println!("no pending txs");
+        return Ok(());
+    }
+
+    for sent in pendings.iter() {
+        println!(
+            "commit tx: {}, reveal tx: {}, status: {}",
+            sent.commit_txid, sent.reveal_txid, sent.status
+        );
+    }
+
+    Ok(())
+}
+
+/// Send the txs from the status in the journal, the txs already sent will be skipped.
+async fn resume(cli: &Cli, commit_txid: &Txid) -> Result<()> {
+    let mut sent = SentP2tr::load(&cli.datadir, commit_txid)?;
+    if !sent.status.is_pending() {
+        bail!("the commit tx {} is {}, no need to resume", commit_txid, sent.status);
+    }
+
+    let context = build_context(cli).await?;
+    if context.dry_run {
+        println!(
+            "dry run, the commit tx {} and the reveal tx {} will not be sent, status: {}",
+            sent.commit_txid, sent.reveal_txid, sent.status
+        );
+        return Ok(());
+    }
+
+    crate::broadcast_sent_p2tr(&context, &mut sent, None)
+        .await
+        .context("resume the txs failed")?;
+
+    context.flush_wallets()?;
+
+    Ok(())
+}
+
+/// Spend the first output of the tx to claim the resources in its space, each resource will be
+/// moved to an output.
+async fn claim(cli: &Cli, txid: &Txid) -> Result<()> {
+    use vital_script_builder::templates;
+
+    let mut context = build_context(cli).await?;
+
+    let resources = context.get_space_resources(txid).await?;
+    if resources.is_empty() {
+        bail!("no resources in the space of {}", txid);
+    }
+
+    let first_output = OutPoint::new(*txid, 0);
+    let utxo =
+        context
+            .wallet
+            .wallet
+            .get_utxo(first_output)
+            .context("get utxo")?
+            .ok_or_else(|| {
+                anyhow!(
+                    "the first output {} not in the wallet, can not claim the space",
+                    first_output
+                )
+            })?;
+
+    println!("claim {} resources in the space of {}", resources.len(), txid);
+
+    context.append_reveal_input(&[utxo]);
+    context
+        .set_outputs_from(0, resources.len(), context.sats_amount)
+        .context("set outputs")?;
+
+    // the input begin with 1, 0 is for script.
+    let scripts_bytes = templates::claim_space(1, resources).context("build scripts failed")?;
+    crate::send_p2tr(&context, scripts_bytes).await.context("send_p2tr failed")?;
+
+    context.flush_wallets()?;
+
+    Ok(())
+}
+
+/// Rebuild the scripts by the datas in the journal, then spend the commit outputs back to the
+/// fee wallet by the script path.
+async fn recover(cli: &Cli, commit_txid: &Txid, fee_rate: &Option<FeeRateArg>) -> Result<()> {
+    let mut sent = SentP2tr::load(&cli.datadir, commit_txid)?;
+    match sent.status {
+        SentStatus::CommitSent => {}
+        SentStatus::Built => {
+            bail!("the commit tx {} not sent, no need to recover", commit_txid)
+        }
+        status => bail!("the commit tx {} is {}, can not recover", commit_txid, status),
+    }
+
+    let mut context = build_context(cli).await?.with_fee_rate(fee_rate)?;
+    // the recover tx only send to the fee wallet, no need the outputs.
+    context.outputs = Vec::new();
+
+    // the reveal tx may be sent by others, so the commit outputs had been spent.
+    if let Ok(Some(_)) = context.wallet.blockchain.get_tx(&sent.reveal_txid) {
+        sent.update_status(&cli.datadir, SentStatus::RevealSent)?;
+        bail!("the reveal tx {} had been sent, can not recover", sent.reveal_txid);
+    }
+
+    let commit_tx = sent.commit_tx()?;
+    let builder = P2trBuilder::new_with_datas(&context, sent.datas()?).context("builder build")?;
+
+    if context.dry_run {
+        let psbt = builder.build_recover_unsigned(&commit_tx)?;
+        println!(
+            "dry run, the recover tx {} will not be sent: {} sats back to the fee wallet",
+            psbt.unsigned_tx.txid(),
+            psbt.unsigned_tx.output[0].value
+        );
+        return Ok(());
+    }
+
+    let recover_tx = builder.build_recover(&commit_tx).context("build recover tx")?.extract_tx();
+    let recover_txid = recover_tx.txid();
+
+    context.wallet.blockchain.broadcast(&recover_tx)?;
+    println!("Recover Transaction broadcast! TXID: {txid}.\nExplorer URL: https://mempool.space/testnet/tx/{txid}", txid = recover_txid);
+
+    sent.update_status(&cli.datadir, SentStatus::Recovered)
+        .context("update journal")?;
+
+    context.flush_wallets()?;
+
+    Ok(())
+}
+
+/// Spend an output of the reveal tx by a child tx which pay the fee for the package, the
+/// outputs and the utxos with resources will never be spent.
+async fn cpfp(cli: &Cli, reveal_txid: &Txid, fee_rate: &FeeRateArg) -> Result<()> {
+    let context = build_context(cli).await?.with_fee_rate(&Some(*fee_rate))?;
+    let package_fee_rate = context.fee_rate.expect("the fee rate had set");
+    let wallet = &context.wallet;
+
+    match is_confirmed(wallet, reveal_txid)? {
+        Some(true) => bail!("the reveal tx {} had confirmed", reveal_txid),
+        Some(false) => {}
+        None => bail!("not found the reveal tx {} in wallet", reveal_txid),
+    }
+
+    let reveal_tx = wallet
+        .blockchain
+        .get_tx(reveal_txid)
+        .context("get reveal tx")?
+        .ok_or_else(|| anyhow!("not found the reveal tx {}", reveal_txid))?;
+
+    let (parents_fee, parents_vsize) = pending_package(&context, &reveal_tx)?;
+    if parents_fee as f32 >= package_fee_rate * parents_vsize as f32 {
+        bail!(
+            "the fee rate of the pending txs is {:.2} sat/vB, not less than {} sat/vB",
+            parents_fee as f32 / parents_vsize as f32,
+            package_fee_rate
+        );
+    }
+
+    let outpoint = OutPoint::new(*reveal_txid, cpfp_output(&context, &reveal_tx).await?);
+    let utxo = wallet
+        .wallet
+        .get_utxo(outpoint)
+        .context("get utxo")?
+        .ok_or_else(|| anyhow!("not found the output {} in wallet", outpoint))?;
+
+    // the fee need the vsize of the child tx, so rebuild it until the fee is enough.
+    let mut child_vsize = 0;
+    let mut child = None;
+    for _ in 0..CPFP_BUILD_ROUNDS {
+        let package_fee = (package_fee_rate * (parents_vsize + child_vsize) as f32).ceil() as u64;
+        let min_fee = (MIN_RELAY_FEE_RATE * child_vsize as f32).ceil() as u64;
+        let fee = package_fee.saturating_sub(parents_fee).max(min_fee);
+
+        let psbt = build_child(&context, &utxo, fee)?;
+
+        let mut placeholder = psbt.clone();
+        signer::sign_psbt_with_placeholder(&mut placeholder).context("placeholder sign")?;
+        signer::finalize_psbt(&mut placeholder).context("placeholder finalize")?;
+        let vsize = placeholder.extract_tx().vsize();
+
+        if vsize <= child_vsize {
+            child = Some((psbt, fee));
+            break;
+        }
+        child_vsize = vsize;
+    }
+    let (mut psbt, fee) = child.ok_or_else(|| anyhow!("the fee for the child tx not be stable"))?;
+
+    if context.dry_run {
+        println!(
+            "dry run, the child tx {} will not be sent: {} vB, fee {} sats",
+            psbt.unsigned_tx.txid(),
+            child_vsize,
+            fee
+        );
+        return Ok(());
+    }
+
+    let secp = Secp256k1::new();
+    let signers =
+        signer::signers_for_wallets(context.signer.as_deref(), &[context.fee_wallet(), wallet])?;
+    for signer in signers.iter() {
+        signer::sign_psbt(&mut psbt, signer.as_ref(), &secp).context("sign child tx")?;
+    }
+    signer::finalize_psbt(&mut psbt).context("finalize child tx")?;
+
+    let outpoints_used = psbt
+        .unsigned_tx
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect::<Vec<_>>();
+
+    let child_tx = psbt.extract_tx();
+    let child_txid = child_tx.txid();
+
+    wallet.blockchain.broadcast(&child_tx)?;
+    println!("Child Transaction broadcast! TXID: {txid}.\nExplorer URL: https://mempool.space/testnet/tx/{txid}", txid = child_txid);
+
+    println!(
+        "the package fee rate is {:.2} sat/vB",
+        (parents_fee + fee) as f32 / (parents_vsize + child_tx.vsize()) as f32
+    );
+
+    if let Err(err) = context.append_used_utxos(&outpoints_used) {
+        println!("save used utxos failed by {}, should use --sync to ensure synced", err);
+    };
+
+    context.flush_wallets()?;
+
+    Ok(())
+}
+
+/// If the tx confirmed, None if the tx not in the wallet.
+fn is_confirmed(wallet: &Wallet, txid: &Txid) -> Result<Option<bool>> {
+    Ok(wallet
+        .wallet
+        .get_tx(txid, false)
+        .context("get tx")?
+        .map(|details| details.confirmation_time.is_some()))
+}
+
+/// The fee and the vsize of the reveal tx and its pending parents, such as the commit tx.
+fn pending_package(context: &Context, reveal_tx: &Transaction) -> Result<(u64, usize)> {
+    let mut fee = tx_fee(context, reveal_tx)?;
+    let mut vsize = reveal_tx.vsize();
+
+    let mut parents = BTreeSet::new();
+    for input in reveal_tx.input.iter() {
+        let txid = input.previous_output.txid;
+        if !parents.insert(txid) {
+            continue;
+        }
+
+        // the txs not in wallets are as confirmed.
+        let pending = is_confirmed(context.fee_wallet(), &txid)? == Some(false)
+            || is_confirmed(&context.wallet, &txid)? == Some(false);
+        if !pending {
+            continue;
+        }
+
+        let parent = context
+            .wallet
+            .blockchain
+            .get_tx(&txid)
+            .context("get parent tx")?
+            .ok_or_else(|| anyhow!("not found the parent tx {}", txid))?;
+
+        fee += tx_fee(context, &parent)?;
+        vsize += parent.vsize();
+    }
+
+    Ok((fee, vsize))
+}
+
+fn tx_fee(context: &Context, tx: &Transaction) -> Result<u64> {
+    let mut input_amount = 0;
+    for input in tx.input.iter() {
+        let outpoint = input.previous_output;
+        let prev_tx = context
+            .wallet
+            .blockchain
+            .get_tx(&outpoint.txid)
+            .context("get prev tx")?
+            .ok_or_else(|| anyhow!("not found the tx {}", outpoint.txid))?;
+
+        input_amount += prev_tx
+            .output
+            .get(outpoint.vout as usize)
+            .ok_or_else(|| anyhow!("not found the output {}", outpoint))?
+            .value;
+    }
+
+    let output_amount = tx.output.iter().map(|output| output.value).sum::<u64>();
+
+    input_amount
+        .checked_sub(output_amount)
+        .ok_or_else(|| anyhow!("the outputs of tx {} more than the inputs", tx.txid()))
+}
+
+/// The index of the output in wallet which had no resources, by the indexer and the local
+/// simulation of the reveal tx.
+async fn cpfp_output(context: &Context, reveal_tx: &Transaction) -> Result<u32> {
+    let txid = reveal_tx.txid();
+
+    let block_height = context.wallet.blockchain.get_height().context("get block height")? + 1;
+    let resources = context
+        .run_tx_in_local(block_height, reveal_tx.clone())
+        .await
+        .context("simulate the reveal tx failed")?
+        .unwrap_or_default();
+
+    let resource_outpoints = context
+        .utxo_with_resources
+        .iter()
+        .copied()
+        .chain(resources.into_iter().map(|(outpoint, _)| outpoint))
+        .collect::<Vec<_>>();
+
+    cpfp_output_index(reveal_tx, &resource_outpoints, |script| {
+        context.wallet.wallet.is_mine(script).context("is mine")
+    })?
+    .ok_or_else(|| {
+        anyhow!(
+            "the reveal tx {} had no output without resources in wallet for cpfp, it should be sent with `--cpfp-anchor`",
+            txid
+        )
+    })
+}
+
+/// Build the child tx by the fee wallet, spend the reveal output and the utxos of the fee wallet
+/// if need, all the amount except the fee will be sent to the change.
+fn build_child(
+    context: &Context,
+    utxo: &LocalUtxo,
+    fee: u64,
+) -> Result<PartiallySignedTransaction> {
+    let fee_wallet = &context.fee_wallet().wallet;
+
+    let mut builder = fee_wallet.build_tx();
+
+    if context.fee_wallet.is_some() {
+        let psbt_input = context
+            .wallet
+            .wallet
+            .get_psbt_input(utxo.clone(), None, false)
+            .context("get psbt input")?;
+        builder
+            .add_foreign_utxo(utxo.outpoint, psbt_input, TAPROOT_KEY_SPEND_SATISFACTION_WEIGHT)
+            .context("add the reveal output")?;
+    } else {
+        builder.add_utxo(utxo.outpoint).context("add the reveal output")?;
+    }
+
+    let change = fee_wallet.get_internal_address(AddressIndex::LastUnused).context("change")?;
+    builder.drain_to(change.script_pubkey());
+
+    // Note we not use this utxos, because it will cost the resource.
+    builder.unspendable([context.utxo_with_resources.clone(), context.used_utxos.clone()].concat());
+    builder.fee_absolute(fee);
+
+    let (psbt, _details) = builder.finish().context("build child tx failed")?;
+
+    if let Some(input) = psbt
+        .unsigned_tx
+        .input
+        .iter()
+        .find(|input| context.utxo_with_resources.contains(&input.previous_output))
+    {
+        bail!("the child tx should not spend the utxo {} with resources", input.previous_output);
+    }
+
+    Ok(psbt)
+}
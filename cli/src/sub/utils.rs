@@ -1,10 +1,15 @@
-use std::{collections::BTreeMap, str::FromStr};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use anyhow::{anyhow, Context, Result};
 use bdk::{
     bitcoin::{
         absolute,
         address::{NetworkUnchecked, Payload},
+        bip32::ExtendedPrivKey,
         key::TapTweak,
         psbt::{Input, PartiallySignedTransaction, PsbtSighashType},
         secp256k1::{All, KeyPair, Secp256k1, SecretKey, XOnlyPublicKey},
@@ -18,9 +23,13 @@ use bdk::{
 };
 use clap::Subcommand;
 
-use btc_p2tr_builder::P2trBuilder;
+use btc_p2tr_builder::{
+    signer::{self, ProcessSigner, SoftwareSigner},
+    CommitRevealPsbts, P2trBuilder, Signer,
+};
 use btc_script_builder::InscriptionScriptBuilder;
-use wallet::consts::DEFAULT_WALLET_NAME;
+use client::{fee_rate::FeeRateArg, sent_p2tr::SentP2tr};
+use wallet::{consts::DEFAULT_WALLET_NAME, WalletFile};
 
 use crate::Cli;
 
@@ -34,9 +43,9 @@ pub enum UtilsSubCommands {
         /// The sat amount in BTC to send.
         amount: u64,
 
-        /// Specify a fee rate in sat/vB.
+        /// Specify a fee rate in sat/vB, or `fast`, `normal`, `slow` to estimate it.
         #[arg(short, long)]
-        fee_rate: Option<f32>,
+        fee_rate: Option<FeeRateArg>,
 
         /// Signal that this transaction can be replaced by a transaction (BIP 125).
         #[arg(long)]
@@ -51,6 +60,19 @@ pub enum UtilsSubCommands {
         /// The datas to inscribe
         datas: String,
     },
+
+    /// Sign the psbts saved by `--psbt-out` with the `--wallet` or the `--signer`, it not need
+    /// connect to the network, so can sign in the offline machine.
+    SignPsbt {
+        /// The file of the psbts.
+        file: PathBuf,
+    },
+
+    /// Broadcast the signed psbts, the commit tx first then the reveal tx.
+    BroadcastPsbt {
+        /// The file of the psbts.
+        file: PathBuf,
+    },
 }
 
 impl UtilsSubCommands {
@@ -71,6 +93,8 @@ impl UtilsSubCommands {
 
                 inscribe_to_address(&context, datas.as_str()).await
             }
+            Self::SignPsbt { file } => sign_psbt(cli, file),
+            Self::BroadcastPsbt { file } => broadcast_psbt(cli, file).await,
         }
     }
 }
@@ -80,7 +104,7 @@ fn send_to_address(
     cli: &Cli,
     address: Address,
     amount: u64,
-    fee_rate: &Option<f32>,
+    fee_rate: &Option<FeeRateArg>,
     replaceable: bool,
 ) -> Result<()> {
     let wallet = wallet::Wallet::load(
@@ -102,7 +126,8 @@ fn send_to_address(
     }
 
     if let Some(fee_rate) = fee_rate {
-        builder.fee_rate(FeeRate::from_sat_per_vb(*fee_rate));
+        let fee_rate = fee_rate.resolve(bdk_blockchain).context("resolve fee rate")?;
+        builder.fee_rate(FeeRate::from_sat_per_vb(fee_rate));
     }
 
     let (mut psbt, details) = builder.finish().context("build tx failed")?;
@@ -122,42 +147,99 @@ fn send_to_address(
     let raw_transaction = psbt.extract_tx();
     let txid = raw_transaction.txid();
 
+    if cli.dry_run {
+        println!(
+            "dry run, the tx {} will not be sent: {} vB, fee {:?} sats",
+            txid,
+            raw_transaction.vsize(),
+            details.fee
+        );
+        return Ok(());
+    }
+
     bdk_blockchain.broadcast(&raw_transaction)?;
     println!("Transaction broadcast! TXID: {txid}.\nExplorer URL: https://mempool.space/testnet/tx/{txid}", txid = txid);
 
     Ok(())
 }
 
-async fn inscribe_to_address(context: &crate::Context, datas: &str) -> Result<()> {
-    let wallet = &context.wallet;
-    let bdk_blockchain = &wallet.blockchain;
+fn sign_psbt(cli: &Cli, file: &Path) -> Result<()> {
+    let network = cli.network();
+    let wallet_name = cli.wallet.clone().unwrap_or(DEFAULT_WALLET_NAME.to_string());
 
-    let builder = P2trBuilder::new(context, hex::decode(datas).context("decode datas")?)
-        .context("builder build")?;
+    let (signer, signer_name): (Box<dyn Signer>, _) = match &cli.signer {
+        Some(command) => {
+            (Box::new(ProcessSigner::spawn(command).context("spawn signer")?), command.clone())
+        }
+        None => {
+            // just need the key, so not load the wallet which need connect to electrum.
+            let xpriv = WalletFile::load(&cli.datadir, &wallet_name, network)
+                .context("load wallet file failed")?
+                .unlock(&wallet_name)?;
+            let xpriv = ExtendedPrivKey::from_str(&xpriv).context("parse xpriv")?;
+
+            (Box::new(SoftwareSigner::new(xpriv)), format!("wallet {}", wallet_name))
+        }
+    };
 
-    let (commit_psbt, reveal_psbt) = builder.build().context("build tx error")?;
+    let secp = Secp256k1::new();
 
-    let commit_raw_transaction = commit_psbt.extract_tx();
-    let commit_txid = commit_raw_transaction.txid();
+    let psbts = CommitRevealPsbts::load(file)?;
+    let (mut commit_psbt, mut reveal_psbt) = psbts.psbts()?;
 
-    println!("reveal_psbt: {}", serde_json::to_string_pretty(&reveal_psbt.unsigned_tx.input)?);
-    println!("reveal_psbt: {}", serde_json::to_string_pretty(&reveal_psbt.inputs)?);
+    let commit_signed =
+        signer::sign_psbt(&mut commit_psbt, signer.as_ref(), &secp).context("sign commit psbt")?;
+    let reveal_signed =
+        signer::sign_psbt(&mut reveal_psbt, signer.as_ref(), &secp).context("sign reveal psbt")?;
+
+    CommitRevealPsbts::new(&commit_psbt, &reveal_psbt, psbts.wait_commit_confirmed)?
+        .save(file)
+        .context("save psbts")?;
 
-    let reveal_raw_transaction = reveal_psbt.extract_tx();
     println!(
-        "reveal_psbt raw_transaction: {}",
-        serde_json::to_string_pretty(&reveal_raw_transaction)?
+        "signed {} inputs of commit tx and {} inputs of reveal tx by {}",
+        commit_signed, reveal_signed, signer_name
     );
 
-    let reveal_txid = reveal_raw_transaction.txid();
+    Ok(())
+}
 
-    bdk_blockchain.broadcast(&commit_raw_transaction)?;
-    println!("Commit Transaction broadcast! TXID: {txid}.\nExplorer URL: https://mempool.space/testnet/tx/{txid}", txid = commit_txid);
+async fn broadcast_psbt(cli: &Cli, file: &Path) -> Result<()> {
+    let psbts = CommitRevealPsbts::load(file)?;
+    let (mut commit_psbt, mut reveal_psbt) = psbts.psbts()?;
 
-    bdk_blockchain.broadcast(&reveal_raw_transaction)?;
-    println!("Reveal Transaction broadcast! TXID: {txid}.\nExplorer URL: https://mempool.space/testnet/tx/{txid}", txid = reveal_txid);
+    signer::finalize_psbt(&mut commit_psbt).context("finalize commit psbt")?;
+    signer::finalize_psbt(&mut reveal_psbt).context("finalize reveal psbt")?;
 
-    Ok(())
+    // the commit tx is funded by the fee wallet, the reveal inputs are from the wallet.
+    let context = crate::build_context(cli).await?;
+
+    let commit_raw_transaction = commit_psbt.extract_tx();
+    let reveal_raw_transaction = reveal_psbt.extract_tx();
+
+    // record the txs to the journal, so the reveal tx can be resumed if failed to send.
+    let mut sent = SentP2tr::from_signed_txs(
+        &commit_raw_transaction,
+        &reveal_raw_transaction,
+        &context.wallet,
+        context.network(),
+        psbts.wait_commit_confirmed,
+    )
+    .context("record the txs")?;
+    sent.save(&context.root_path).context("save the txs to journal")?;
+
+    super::broadcast_sent_p2tr(&context, &mut sent, None).await
+}
+
+async fn inscribe_to_address(context: &crate::Context, datas: &str) -> Result<()> {
+    let builder = P2trBuilder::new(context, hex::decode(datas).context("decode datas")?)
+        .context("builder build")?;
+
+    if context.dry_run {
+        return crate::sub::dry_run(context, builder).await;
+    }
+
+    super::send_p2tr_by_builder(context, builder, false, None).await
 }
 
 #[allow(dead_code)]
@@ -183,7 +265,7 @@ fn inscribe_to_address_impl(
 
     let secp = Secp256k1::new();
 
-    let master_xpriv = wallet.xpriv();
+    let master_xpriv = wallet.xpriv()?;
     let derivation_path = wallet.full_derivation_path().context("get full derivation")?;
     let internal_key = wallet.derive_x_only_public_key(&secp)?;
 
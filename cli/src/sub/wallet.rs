@@ -24,6 +24,20 @@ pub enum WalletSubCommands {
         wallet: String,
     },
 
+    /// Import a watch-only wallet by the public `tr(...)` descriptors, it can not sign the txs,
+    /// use `--psbt-out` to export the psbts then sign them by `utils sign-psbt` in other place.
+    ImportWatchOnly {
+        /// The public descriptor for receive, like `tr([fingerprint/86'/1'/0']tpub.../0/*)`.
+        descriptor: String,
+
+        /// The public descriptor for change, like `tr([fingerprint/86'/1'/0']tpub.../1/*)`.
+        #[arg(long)]
+        change_descriptor: Option<String>,
+
+        #[arg(long, default_value = "default")]
+        wallet: String,
+    },
+
     /// Get Balance for wallet.
     Balance { wallet: Option<String> },
 
@@ -37,6 +51,12 @@ pub enum WalletSubCommands {
 
     /// List all alive wallets.
     List,
+
+    /// Change the password of wallet, the wallet not encrypted will be encrypted.
+    ChangePassword {
+        #[arg(long, default_value = "default")]
+        wallet: String,
+    },
 }
 
 impl WalletSubCommands {
@@ -48,6 +68,17 @@ impl WalletSubCommands {
             Self::Import { mnemonic, wallet: wallet_name } => {
                 import_mnemonic(cli, wallet_name, mnemonic.clone())?;
             }
+            Self::ImportWatchOnly { descriptor, change_descriptor, wallet: wallet_name } => {
+                Wallet::import_watch_only(
+                    cli.network(),
+                    cli.endpoint.clone(),
+                    &cli.datadir,
+                    wallet_name,
+                    descriptor,
+                    change_descriptor.as_deref(),
+                    true,
+                )?;
+            }
             Self::Balance { wallet: wallet_name } => {
                 balance(cli, wallet_name)?;
             }
@@ -57,6 +88,9 @@ impl WalletSubCommands {
             Self::List => {
                 list(cli)?;
             }
+            Self::ChangePassword { wallet: wallet_name } => {
+                WalletFile::change_password(&cli.datadir, wallet_name, cli.network())?;
+            }
         }
 
         Ok(())
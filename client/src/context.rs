@@ -21,33 +21,47 @@ use vital_interfaces_indexer::{
     traits::IndexerClientT, vital_env_for_query, IndexerClient, QueryEnvContext,
 };
 use vital_script_primitives::{
-    resources::{Name, Resource, ResourceType},
+    consts::protocol_version_at,
+    resources::{Resource, ResourceType, Tag},
     U256,
 };
 use vital_script_runner::*;
 use wallet::Wallet;
 
 use crate::{
-    parser::tx_from_bdk,
+    fee_rate::FeeRateArg,
+    parser::{network_from_bdk, tx_from_bdk},
     resource::LocalResource,
-    used_utxo::{append_used_utxos, load_used_utxos, save_used_utxos},
+    used_utxo::{append_used_utxos, load_used_utxos, replace_used_utxos, save_used_utxos},
     vital_script_runner::LocalRunner,
 };
 
 pub struct Context {
     pub root_path: std::path::PathBuf,
     pub wallet: Wallet,
+    /// The wallet to pay the fee for the commit tx, if None, will use the `wallet`.
+    pub fee_wallet: Option<Wallet>,
     pub indexer: IndexerClient,
     pub query_env_context: QueryEnvContext,
     pub fee_rate: Option<f32>,
-    /// TODO: support replaceable
+    /// Signal the commit tx can be replaced (BIP 125), so it can be bumped by `tx bump`.
     pub replaceable: bool,
     pub utxo_resources: BTreeMap<Resource, LocalUtxo>,
+    /// The utxos with resources in the wallet and the fee wallet, should not be spent as fee.
     pub utxo_with_resources: Vec<bdk::bitcoin::OutPoint>,
     pub reveal_inputs: Vec<LocalUtxo>,
     pub used_utxos: Vec<bdk::bitcoin::OutPoint>,
     pub outputs: Vec<(Option<Address>, u64)>,
     pub sats_amount: u64,
+    /// If set, the txs will not be signed and sent, but save the unsigned psbts to the file.
+    pub psbt_out: Option<std::path::PathBuf>,
+    /// The command of the external signer process.
+    pub signer: Option<String>,
+    /// If true, the txs will be built and simulated but not be sent.
+    pub dry_run: bool,
+    /// If true, the reveal tx will have an output without resources to the wallet, so it can
+    /// be accelerated by CPFP.
+    pub cpfp_anchor: bool,
 }
 
 impl Context {
@@ -67,6 +81,7 @@ impl Context {
         let mut res = Self {
             root_path,
             wallet,
+            fee_wallet: None,
             indexer,
             query_env_context,
             fee_rate: None,
@@ -77,6 +92,10 @@ impl Context {
             // At least one outputs
             outputs: vec![(None, 0)],
             sats_amount: 0,
+            psbt_out: None,
+            signer: None,
+            dry_run: false,
+            cpfp_anchor: false,
             used_utxos,
         };
 
@@ -95,8 +114,68 @@ impl Context {
         append_used_utxos(&self.root_path, utxos)
     }
 
-    pub fn with_fee_rate(mut self, fee_rate: &Option<f32>) -> Self {
-        self.fee_rate = *fee_rate;
+    /// Replace the used utxos, the inputs of the `replaced` tx by the ones of the new tx.
+    pub fn replace_used_utxos(&self, replaced: &[OutPoint], utxos: &[OutPoint]) -> Result<()> {
+        replace_used_utxos(&self.root_path, replaced, utxos)
+    }
+
+    /// Set the fee wallet, the utxos with resources in it will be excluded from the fee.
+    pub async fn with_fee_wallet(mut self, fee_wallet: Option<Wallet>) -> Result<Self> {
+        if let Some(fee_wallet) = &fee_wallet {
+            let fee_utxo_with_resources = self
+                .fetch_resources_in(fee_wallet)
+                .await
+                .context("get fee wallet 's utxo with resources failed")?;
+
+            self.utxo_with_resources
+                .extend(fee_utxo_with_resources.into_iter().map(|utxo| utxo.utxo.outpoint));
+        }
+
+        self.fee_wallet = fee_wallet;
+        Ok(self)
+    }
+
+    /// The wallet to pay the fee, the commit tx 's inputs and change are from it.
+    pub fn fee_wallet(&self) -> &Wallet {
+        self.fee_wallet.as_ref().unwrap_or(&self.wallet)
+    }
+
+    /// Flush the wallet and the fee wallet if had.
+    pub fn flush_wallets(&self) -> Result<()> {
+        self.wallet.flush().context("flush wallet")?;
+        if let Some(fee_wallet) = &self.fee_wallet {
+            fee_wallet.flush().context("flush fee wallet")?;
+        }
+
+        Ok(())
+    }
+
+    /// Set the fee rate, the fee rate targets will be estimated by the wallet 's electrum.
+    pub fn with_fee_rate(mut self, fee_rate: &Option<FeeRateArg>) -> Result<Self> {
+        self.fee_rate = fee_rate
+            .map(|fee_rate| fee_rate.resolve(&self.wallet.blockchain))
+            .transpose()
+            .context("resolve fee rate")?;
+        Ok(self)
+    }
+
+    pub fn with_psbt_out(mut self, psbt_out: &Option<std::path::PathBuf>) -> Self {
+        self.psbt_out = psbt_out.clone();
+        self
+    }
+
+    pub fn with_signer(mut self, signer: &Option<String>) -> Self {
+        self.signer = signer.clone();
+        self
+    }
+
+    pub fn with_dry_run(mut self, dry_run: &bool) -> Self {
+        self.dry_run = *dry_run;
+        self
+    }
+
+    pub fn with_cpfp_anchor(mut self, cpfp_anchor: &bool) -> Self {
+        self.cpfp_anchor = *cpfp_anchor;
         self
     }
 
@@ -189,6 +268,16 @@ impl Context {
         self.wallet.wallet.network()
     }
 
+    /// The protocol version to run the block at the height, same as the indexer.
+    pub fn protocol_version_at(&self, block_height: u32) -> u32 {
+        protocol_version_at(network_from_bdk(self.network()), block_height)
+    }
+
+    /// The protocol version for the txs to send, they will be in the next block at least.
+    pub fn protocol_version(&self) -> Result<u32> {
+        Ok(self.protocol_version_at(self.get_btc_block_height()? + 1))
+    }
+
     pub fn get_owned_resource(&self, resource: &Resource) -> Option<LocalUtxo> {
         self.utxo_resources.get(resource).cloned()
     }
@@ -216,14 +305,15 @@ impl Context {
 
     pub async fn try_get_pending_resources(
         &self,
+        wallet: &Wallet,
         unspents: &[LocalUtxo],
     ) -> Result<Vec<LocalResource>> {
         let mut resource_pendings = Vec::new();
 
-        let db = self.wallet.wallet.database();
+        let db = wallet.wallet.database();
 
         let mut processed_tx = BTreeSet::new();
-        let block_height = self.wallet.blockchain.get_height().context("get block height")?;
+        let block_height = wallet.blockchain.get_height().context("get block height")?;
 
         // process pendings
         for unspent in unspents.iter() {
@@ -281,13 +371,18 @@ impl Context {
     }
 
     pub async fn fetch_all_resources(&self) -> Result<Vec<LocalResource>> {
+        self.fetch_resources_in(&self.wallet).await
+    }
+
+    /// Fetch the resources in the unspents of the wallet, include the pending ones.
+    pub async fn fetch_resources_in(&self, wallet: &Wallet) -> Result<Vec<LocalResource>> {
         let mut res = Vec::new();
 
-        let outpoints = self.wallet.wallet.list_unspent().context("list unspents failed")?;
+        let outpoints = wallet.wallet.list_unspent().context("list unspents failed")?;
 
         // process pendings
         let mut pending_resources = self
-            .try_get_pending_resources(&outpoints)
+            .try_get_pending_resources(wallet, &outpoints)
             .await
             .context("try_get_pending_resources")?;
 
@@ -319,7 +414,7 @@ impl Context {
         Ok(res)
     }
 
-    pub async fn fetch_all_vrc20_by_name(&self, name: Name) -> Result<(U256, Vec<LocalResource>)> {
+    pub async fn fetch_all_vrc20_by_name(&self, name: Tag) -> Result<(U256, Vec<LocalResource>)> {
         let resource_type = ResourceType::vrc20(name);
 
         let owned_vrc20s = self
@@ -339,6 +434,15 @@ impl Context {
         Ok((sum, owned_vrc20s))
     }
 
+    /// Get the resources in the space of the tx from the indexer, they can be claimed by the
+    /// tx which spend its first output.
+    pub async fn get_space_resources(&self, txid: &bdk::bitcoin::Txid) -> Result<Vec<Resource>> {
+        self.indexer
+            .get_space(&Txid::from_byte_array(*txid.as_byte_array()))
+            .await
+            .with_context(|| format!("get space of {}", txid))
+    }
+
     pub fn get_btc_block_height(&self) -> Result<u32> {
         self.wallet.blockchain.get_height().context("get height")
     }
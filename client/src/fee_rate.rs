@@ -0,0 +1,123 @@
+//! The fee rate in sat/vB, or the target to estimate it by the electrum.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{bail, Context as AnyhowContext, Result};
+use bdk::blockchain::Blockchain;
+
+/// The min relay fee rate in sat/vB, used if the fee can not be estimated.
+const MIN_FEE_RATE: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeeRateArg {
+    /// Confirmed in the next block.
+    Fast,
+    /// Confirmed in 3 blocks.
+    Normal,
+    /// Confirmed in 6 blocks.
+    Slow,
+    /// The fee rate in sat/vB.
+    SatPerVb(f32),
+}
+
+impl FeeRateArg {
+    /// Resolve to the fee rate in sat/vB, the targets will be estimated by the electrum
+    /// `estimatefee`.
+    pub fn resolve(&self, blockchain: &impl Blockchain) -> Result<f32> {
+        let target = match self {
+            Self::Fast => 1,
+            Self::Normal => 3,
+            Self::Slow => 6,
+            Self::SatPerVb(fee_rate) => return Ok(*fee_rate),
+        };
+
+        let fee_rate = blockchain.estimate_fee(target).context("estimate fee")?.as_sat_per_vb();
+
+        Ok(self.estimated_or_min(fee_rate))
+    }
+
+    /// The estimated fee rate, or the min fee rate if it is less than the min.
+    fn estimated_or_min(&self, fee_rate: f32) -> f32 {
+        // the node got -1 if it can not estimate, such as in the regtest.
+        if fee_rate.is_nan() || fee_rate < MIN_FEE_RATE {
+            println!(
+                "the fee rate for {} estimated {}, use the min fee rate {} sat/vB",
+                self, fee_rate, MIN_FEE_RATE
+            );
+            return MIN_FEE_RATE;
+        }
+
+        println!("the fee rate for {} is {} sat/vB", self, fee_rate);
+
+        fee_rate
+    }
+}
+
+impl FromStr for FeeRateArg {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "fast" => Ok(Self::Fast),
+            "normal" => Ok(Self::Normal),
+            "slow" => Ok(Self::Slow),
+            _ => {
+                let fee_rate = f32::from_str(s)
+                    .context("the fee rate should be fast, normal, slow or the sat/vB")?;
+                if !fee_rate.is_finite() || fee_rate <= 0.0 {
+                    bail!("the fee rate should be positive");
+                }
+
+                Ok(Self::SatPerVb(fee_rate))
+            }
+        }
+    }
+}
+
+impl fmt::Display for FeeRateArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fast => write!(f, "fast"),
+            Self::Normal => write!(f, "normal"),
+            Self::Slow => write!(f, "slow"),
+            Self::SatPerVb(fee_rate) => write!(f, "{} sat/vB", fee_rate),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fee_rate_targets() {
+        assert_eq!(FeeRateArg::from_str("fast").expect("fast"), FeeRateArg::Fast);
+        assert_eq!(FeeRateArg::from_str("normal").expect("normal"), FeeRateArg::Normal);
+        assert_eq!(FeeRateArg::from_str("slow").expect("slow"), FeeRateArg::Slow);
+
+        assert!(FeeRateArg::from_str("Fast").is_err(), "the targets are lowercase");
+        assert!(FeeRateArg::from_str("").is_err());
+    }
+
+    #[test]
+    fn test_parse_fee_rate_numbers() {
+        assert_eq!(FeeRateArg::from_str("1").expect("1"), FeeRateArg::SatPerVb(1.0));
+        assert_eq!(FeeRateArg::from_str("12.5").expect("12.5"), FeeRateArg::SatPerVb(12.5));
+        assert_eq!(FeeRateArg::from_str("0.5").expect("0.5"), FeeRateArg::SatPerVb(0.5));
+
+        for invalid in ["0", "-1", "-0.5", "NaN", "inf", "-inf", "1sat"] {
+            assert!(FeeRateArg::from_str(invalid).is_err(), "{} should be invalid", invalid);
+        }
+    }
+
+    #[test]
+    fn test_estimated_below_min_should_use_min() {
+        assert_eq!(FeeRateArg::Fast.estimated_or_min(20.0), 20.0);
+        assert_eq!(FeeRateArg::Slow.estimated_or_min(MIN_FEE_RATE), MIN_FEE_RATE);
+
+        // the node can not estimate.
+        assert_eq!(FeeRateArg::Normal.estimated_or_min(-1.0), MIN_FEE_RATE);
+        assert_eq!(FeeRateArg::Normal.estimated_or_min(0.5), MIN_FEE_RATE);
+        assert_eq!(FeeRateArg::Normal.estimated_or_min(f32::NAN), MIN_FEE_RATE);
+    }
+}
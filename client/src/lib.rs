@@ -1,5 +1,7 @@
 pub mod context;
+pub mod fee_rate;
 pub mod parser;
 pub mod resource;
+pub mod sent_p2tr;
 pub mod used_utxo;
 pub mod vital_script_runner;
@@ -1,5 +1,9 @@
 use bitcoin::Transaction;
 
+pub fn network_from_bdk(network: bdk::bitcoin::Network) -> bitcoin::Network {
+    bitcoin::Network::from_core_arg(network.to_core_arg()).expect("the networks should be same")
+}
+
 pub fn tx_from_bdk(tx: bdk::bitcoin::Transaction) -> Transaction {
     serde_json::from_value(serde_json::to_value(tx).expect("in should ok")).expect("out should ok")
 }
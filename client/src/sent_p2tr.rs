@@ -0,0 +1,407 @@
+//! The journal of the commit and reveal txs sent, so they can be rebuilt if need bump the fee.
+//!
+//! The txs are saved before broadcast, and the status updated after each broadcast, so if the
+//! reveal tx failed to send, it can be resumed, or the commit outputs can be recovered.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use bdk::{
+    bitcoin::{
+        address::NetworkUnchecked,
+        consensus::encode::{deserialize, serialize_hex},
+        Address, Network, Transaction, Txid,
+    },
+    database::Database,
+    LocalUtxo,
+};
+use serde::{Deserialize, Serialize};
+use vital_script_primitives::types::bitwork::Bitwork;
+use vital_script_runner::parse_vital_scripts;
+use wallet::Wallet;
+
+use crate::parser::tx_from_bdk;
+
+const SENT_P2TR_DIR: &str = "sent_p2tr";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SentStatus {
+    /// The txs built but not sent.
+    #[default]
+    Built,
+    /// The commit tx sent, but the reveal tx not.
+    CommitSent,
+    /// Both the txs sent.
+    RevealSent,
+    /// The commit outputs had been sent back to the wallet.
+    Recovered,
+}
+
+impl SentStatus {
+    /// The reveal tx not sent and the commit outputs not recovered.
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::Built | Self::CommitSent)
+    }
+}
+
+impl std::fmt::Display for SentStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Built => write!(f, "built"),
+            Self::CommitSent => write!(f, "commit sent"),
+            Self::RevealSent => write!(f, "reveal sent"),
+            Self::Recovered => write!(f, "recovered"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentP2tr {
+    pub commit_txid: Txid,
+    pub reveal_txid: Txid,
+    /// The datas of the vital scripts in hex, by the order of the reveal inputs.
+    pub datas: Vec<String>,
+    /// The resource inputs of the reveal tx.
+    pub reveal_inputs: Vec<LocalUtxo>,
+    /// The outputs of the reveal tx.
+    pub outputs: Vec<(String, u64)>,
+    /// The bitwork of the reveal txid in bworkc, 0 if not need.
+    #[serde(default)]
+    pub bworkc: u64,
+    #[serde(default)]
+    pub wait_commit_confirmed: bool,
+    /// The signed commit tx in hex.
+    #[serde(default)]
+    pub commit_tx: String,
+    /// The signed reveal tx in hex.
+    #[serde(default)]
+    pub reveal_tx: String,
+    #[serde(default)]
+    pub status: SentStatus,
+}
+
+impl SentP2tr {
+    pub fn new(
+        commit_tx: &Transaction,
+        reveal_tx: &Transaction,
+        datas: &[Vec<u8>],
+        reveal_inputs: &[LocalUtxo],
+        outputs: &[(Address, u64)],
+        bitwork: Option<&Bitwork>,
+        wait_commit_confirmed: bool,
+    ) -> Self {
+        Self {
+            commit_txid: commit_tx.txid(),
+            reveal_txid: reveal_tx.txid(),
+            datas: datas.iter().map(hex::encode).collect(),
+            reveal_inputs: reveal_inputs.to_vec(),
+            outputs: outputs.iter().map(|(to, amount)| (to.to_string(), *amount)).collect(),
+            bworkc: bitwork.map(|bitwork| bitwork.to_bworkc()).unwrap_or_default(),
+            wait_commit_confirmed,
+            commit_tx: serialize_hex(commit_tx),
+            reveal_tx: serialize_hex(reveal_tx),
+            status: SentStatus::Built,
+        }
+    }
+
+    /// New the record from the signed txs which not built by the `P2trBuilder` here, such as
+    /// the psbts signed in other place, the datas, reveal inputs and outputs are parsed from the
+    /// reveal tx, the resource inputs should be in the `wallet`, the bitwork is unknown.
+    pub fn from_signed_txs(
+        commit_tx: &Transaction,
+        reveal_tx: &Transaction,
+        wallet: &Wallet,
+        network: Network,
+        wait_commit_confirmed: bool,
+    ) -> Result<Self> {
+        let commit_txid = commit_tx.txid();
+
+        let datas = parse_vital_scripts(&tx_from_bdk(reveal_tx.clone()))
+            .context("parse vital scripts")?
+            .into_iter()
+            .map(|(_, datas)| datas)
+            .collect::<Vec<_>>();
+
+        let db = wallet.wallet.database();
+        let reveal_inputs = reveal_tx
+            .input
+            .iter()
+            .filter(|input| input.previous_output.txid != commit_txid)
+            .map(|input| {
+                db.get_utxo(&input.previous_output)
+                    .context("get utxo")?
+                    .ok_or_else(|| anyhow!("not found reveal input {}", input.previous_output))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let outputs = reveal_tx
+            .output
+            .iter()
+            .map(|output| {
+                let to = Address::from_script(&output.script_pubkey, network)
+                    .context("reveal output to address")?;
+
+                Ok((to, output.value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self::new(
+            commit_tx,
+            reveal_tx,
+            &datas,
+            &reveal_inputs,
+            &outputs,
+            None,
+            wait_commit_confirmed,
+        ))
+    }
+
+    pub fn commit_tx(&self) -> Result<Transaction> {
+        deserialize(&hex::decode(&self.commit_tx).context("decode commit tx")?)
+            .context("deserialize commit tx")
+    }
+
+    pub fn reveal_tx(&self) -> Result<Transaction> {
+        deserialize(&hex::decode(&self.reveal_tx).context("decode reveal tx")?)
+            .context("deserialize reveal tx")
+    }
+
+    /// Update the status and save it.
+    pub fn update_status(&mut self, root: &Path, status: SentStatus) -> Result<()> {
+        self.status = status;
+        self.save(root)
+    }
+
+    pub fn datas(&self) -> Result<Vec<Vec<u8>>> {
+        self.datas.iter().map(|data| hex::decode(data).context("decode data")).collect()
+    }
+
+    pub fn outputs(&self, network: Network) -> Result<Vec<(Address, u64)>> {
+        self.outputs
+            .iter()
+            .map(|(to, amount)| {
+                let to = Address::<NetworkUnchecked>::from_str(to)
+                    .context("parse address")?
+                    .require_network(network)
+                    .context("the address is not for the network")?;
+
+                Ok((to, *amount))
+            })
+            .collect()
+    }
+
+    pub fn bitwork(&self) -> Result<Option<Bitwork>> {
+        Bitwork::from_bworkc(self.bworkc)
+    }
+
+    fn path(root: &Path, commit_txid: &Txid) -> PathBuf {
+        root.join(SENT_P2TR_DIR).join(format!("{}.json", commit_txid))
+    }
+
+    pub fn load(root: &Path, commit_txid: &Txid) -> Result<Self> {
+        let path = Self::path(root, commit_txid);
+        if !path.exists() {
+            bail!("not found the sent txs for commit tx {}", commit_txid);
+        }
+
+        serde_json::from_str(fs::read_to_string(path).context("read file")?.as_str())
+            .context("json from str")
+    }
+
+    pub fn save(&self, root: &Path) -> Result<()> {
+        fs::create_dir_all(root.join(SENT_P2TR_DIR)).context("create dir")?;
+
+        fs::write(Self::path(root, &self.commit_txid), serde_json::to_string_pretty(self)?)
+            .context("write file")
+    }
+
+    /// All the records, order by the commit txid.
+    pub fn list(root: &Path) -> Result<Vec<Self>> {
+        let dir = root.join(SENT_P2TR_DIR);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut res = Vec::new();
+        for entry in fs::read_dir(dir).context("read dir")? {
+            let path = entry.context("read dir")?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+
+            let sent: Self = serde_json::from_str(fs::read_to_string(&path)?.as_str())
+                .with_context(|| format!("json from {}", path.display()))?;
+            res.push(sent);
+        }
+
+        res.sort_by_key(|sent| sent.commit_txid);
+
+        Ok(res)
+    }
+
+    /// Remove the record, for the commit tx had been replaced.
+    pub fn remove(root: &Path, commit_txid: &Txid) -> Result<()> {
+        let path = Self::path(root, commit_txid);
+        if path.exists() {
+            fs::remove_file(path).context("remove file")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk::bitcoin::{absolute, OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Witness};
+
+    use super::*;
+
+    const NETWORK: Network = Network::Regtest;
+
+    fn address(seed: u8) -> Address {
+        Address::p2wsh(&ScriptBuf::from(vec![seed]), NETWORK)
+    }
+
+    /// The commit tx and the reveal tx which spend it, different by the seed.
+    fn txs(seed: u8) -> (Transaction, Transaction) {
+        let tx = |previous_output, value, to: Address| Transaction {
+            version: 1,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut { value, script_pubkey: to.script_pubkey() }],
+        };
+
+        let commit_tx = tx(OutPoint::null(), 10000 + seed as u64, address(seed));
+        let reveal_tx = tx(OutPoint::new(commit_tx.txid(), 0), 600, address(seed + 1));
+
+        (commit_tx, reveal_tx)
+    }
+
+    fn sent(seed: u8, bitwork: Option<&Bitwork>) -> SentP2tr {
+        let (commit_tx, reveal_tx) = txs(seed);
+
+        SentP2tr::new(
+            &commit_tx,
+            &reveal_tx,
+            &[vec![seed, 1, 2]],
+            &[],
+            &[(address(seed + 1), 600)],
+            bitwork,
+            true,
+        )
+    }
+
+    fn to_json(sent: &SentP2tr) -> String {
+        serde_json::to_string(sent).expect("to json")
+    }
+
+    #[test]
+    fn test_save_and_load_should_work() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (commit_tx, reveal_tx) = txs(1);
+        let bitwork = Bitwork::from_hex_prefix("0000")?;
+
+        let mut sent = sent(1, Some(&bitwork));
+        sent.save(dir.path())?;
+
+        let loaded = SentP2tr::load(dir.path(), &commit_tx.txid())?;
+        assert_eq!(to_json(&loaded), to_json(&sent));
+        assert_eq!(loaded.status, SentStatus::Built);
+        assert_eq!(loaded.commit_tx()?, commit_tx);
+        assert_eq!(loaded.reveal_tx()?, reveal_tx);
+        assert_eq!(loaded.reveal_txid, reveal_tx.txid());
+        assert_eq!(loaded.datas()?, vec![vec![1, 1, 2]]);
+        assert_eq!(loaded.outputs(NETWORK)?, vec![(address(2), 600)]);
+        assert!(loaded.outputs(Network::Bitcoin).is_err(), "the address is for regtest");
+        assert_eq!(loaded.bitwork()?, Some(bitwork));
+        assert!(loaded.wait_commit_confirmed);
+
+        sent.update_status(dir.path(), SentStatus::CommitSent)?;
+        let loaded = SentP2tr::load(dir.path(), &commit_tx.txid())?;
+        assert_eq!(loaded.status, SentStatus::CommitSent);
+
+        let (other, _) = txs(2);
+        assert!(SentP2tr::load(dir.path(), &other.txid()).is_err(), "not found");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_bitwork_should_be_none() -> Result<()> {
+        let sent = sent(1, None);
+        assert_eq!(sent.bworkc, 0);
+        assert_eq!(sent.bitwork()?, None);
+
+        // the records saved before the bworkc added.
+        let mut value = serde_json::to_value(&sent)?;
+        value.as_object_mut().expect("object").remove("bworkc");
+        let sent: SentP2tr = serde_json::from_value(value)?;
+        assert_eq!(sent.bitwork()?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_should_order_by_commit_txid() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert!(SentP2tr::list(dir.path())?.is_empty(), "no dir");
+
+        let sents = (0..4).map(|seed| sent(seed * 2, None)).collect::<Vec<_>>();
+        for sent in sents.iter() {
+            sent.save(dir.path())?;
+        }
+
+        // the files not json should be skipped.
+        fs::write(dir.path().join(SENT_P2TR_DIR).join("notes.txt"), "not a record")?;
+        fs::create_dir(dir.path().join(SENT_P2TR_DIR).join("backup"))?;
+
+        let mut txids = sents.iter().map(|sent| sent.commit_txid).collect::<Vec<_>>();
+        txids.sort();
+
+        let listed = SentP2tr::list(dir.path())?;
+        assert_eq!(listed.iter().map(|sent| sent.commit_txid).collect::<Vec<_>>(), txids);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_should_work() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (sent1, sent2) = (sent(1, None), sent(3, None));
+        sent1.save(dir.path())?;
+        sent2.save(dir.path())?;
+
+        SentP2tr::remove(dir.path(), &sent1.commit_txid)?;
+        assert!(SentP2tr::load(dir.path(), &sent1.commit_txid).is_err());
+        assert_eq!(
+            SentP2tr::list(dir.path())?
+                .iter()
+                .map(|sent| sent.commit_txid)
+                .collect::<Vec<_>>(),
+            vec![sent2.commit_txid]
+        );
+
+        // remove the not existed one is ok.
+        SentP2tr::remove(dir.path(), &sent1.commit_txid)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_pending() {
+        assert!(SentStatus::Built.is_pending());
+        assert!(SentStatus::CommitSent.is_pending());
+        assert!(!SentStatus::RevealSent.is_pending());
+        assert!(!SentStatus::Recovered.is_pending());
+    }
+}
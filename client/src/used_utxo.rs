@@ -28,3 +28,22 @@ pub fn append_used_utxos(path: &std::path::Path, utxos: &[OutPoint]) -> Result<(
 
     save_used_utxos(path, &current_utxos)
 }
+
+/// The inputs of the replaced tx are not used by it any more, so remove them, and append the
+/// inputs of the new tx.
+pub fn replace_used_utxos(
+    path: &std::path::Path,
+    replaced: &[OutPoint],
+    utxos: &[OutPoint],
+) -> Result<()> {
+    let mut current_utxos = load_used_utxos(path)?;
+    current_utxos.retain(|utxo| !replaced.contains(utxo));
+
+    for utxo in utxos.iter() {
+        if !current_utxos.contains(utxo) {
+            current_utxos.push(*utxo);
+        }
+    }
+
+    save_used_utxos(path, &current_utxos)
+}
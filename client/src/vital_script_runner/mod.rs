@@ -20,20 +20,21 @@ impl<'a> LocalRunner<'a> {
         &self,
         block_height: u32,
         tx: &Transaction,
-    ) -> RunnerContext<SimulatorEnvInterface<IndexerClient>> {
+    ) -> Result<RunnerContext<SimulatorEnvInterface<IndexerClient>>> {
         let env_interface = SimulatorEnvInterface::new(self.context.indexer.clone());
+        let protocol_version = self.context.protocol_version_at(block_height);
 
-        RunnerContext::simulator(env_interface, tx, block_height)
+        RunnerContext::simulator(env_interface, tx, block_height, protocol_version)
     }
 
+    /// Simulate the tx, return the resources sent to the outputs by all the scripts,
+    /// the scripts run in the order of their inputs just like the indexer.
     pub async fn run(&self, block_height: u32, tx: &Transaction) -> Result<Vec<(u8, Resource)>> {
         // need got commit tx
         let scripts = parse_vital_scripts(tx).context("parse_vital_scripts")?;
-        if scripts.len() > 1 {
-            todo!("Currently we not support more than one script");
-        }
+        log::debug!("simulate {} scripts in tx {}", scripts.len(), tx.txid());
 
-        let mut ctx = self.new_runner_context(block_height, tx);
+        let mut ctx = self.new_runner_context(block_height, tx).context("new runner context")?;
         if let Err(err) = ctx.pre_check() {
             bail!("failed to pre-check by {}", err);
         }
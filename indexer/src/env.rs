@@ -0,0 +1,86 @@
+//! The env functions for the block runner.
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::{bail, Result};
+
+use bitcoin::OutPoint;
+use vital_script::{primitives::resources::Resource, runner::traits::EnvFunctions};
+
+use crate::store::{BlockChanges, Store};
+
+/// A overlay on the store, caches all the changes by a block.
+#[derive(Clone)]
+pub struct BlockEnv {
+    store: Store,
+    changes: Arc<Mutex<BlockChanges>>,
+}
+
+impl BlockEnv {
+    pub fn new(store: Store) -> Self {
+        Self { store, changes: Default::default() }
+    }
+
+    /// Take all the changes cached.
+    pub fn take_changes(&self) -> BlockChanges {
+        std::mem::take(&mut *self.changes.lock().expect("lock"))
+    }
+}
+
+impl EnvFunctions for BlockEnv {
+    fn get_resources(&self, input_id: &OutPoint) -> Result<Option<Resource>> {
+        if let Some(res) = self.changes.lock().expect("lock").resources.get(input_id) {
+            return Ok(res.clone());
+        }
+
+        self.store.get_resource(input_id)
+    }
+
+    fn maybe_bound(&self, outpoint: &OutPoint) -> bool {
+        if let Some(res) = self.changes.lock().expect("lock").resources.get(outpoint) {
+            return res.is_some();
+        }
+
+        self.store.is_bound(outpoint)
+    }
+
+    fn bind_resource(&self, output: OutPoint, res: Resource) -> Result<()> {
+        if self.get_resources(&output)?.is_some() {
+            bail!("the resource had bind to {}", output);
+        }
+
+        self.changes.lock().expect("lock").resources.insert(output, Some(res));
+
+        Ok(())
+    }
+
+    fn unbind_resource(&self, input: &OutPoint) -> Result<()> {
+        if self.get_resources(input)?.is_none() {
+            bail!("not found resource to unbind by {}", input);
+        }
+
+        self.changes.lock().expect("lock").resources.insert(*input, None);
+
+        Ok(())
+    }
+
+    fn storage_get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        if let Some(value) = self.changes.lock().expect("lock").storage.get(key) {
+            return Ok(value.clone());
+        }
+
+        self.store.get_storage(key)
+    }
+
+    fn storage_set(&self, key: Vec<u8>, value: Vec<u8>) -> Result<()> {
+        self.changes.lock().expect("lock").storage.insert(key, Some(value));
+
+        Ok(())
+    }
+
+    fn storage_remove(&self, key: &[u8]) -> Result<()> {
+        self.changes.lock().expect("lock").storage.insert(key.to_vec(), None);
+
+        Ok(())
+    }
+}
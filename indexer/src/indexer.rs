@@ -0,0 +1,244 @@
+//! Index the blocks from the source into the store.
+
+use anyhow::{bail, Context, Result};
+
+use bitcoin::{Block, Network};
+use vital_script::{
+    block_handler::{BlockRunner, TxRunStatus},
+    primitives::consts::protocol_version_at,
+};
+
+use crate::{env::BlockEnv, source::BlockSource, store::Store, TARGET};
+
+pub struct Indexer<Source> {
+    store: Store,
+    source: Source,
+    /// The network of the blocks, decide the protocol version to run them by the heights.
+    network: Network,
+    /// The height to start index if the store is empty.
+    start_height: u32,
+}
+
+impl<Source> Indexer<Source>
+where
+    Source: BlockSource,
+{
+    pub fn new(store: Store, source: Source, network: Network, start_height: u32) -> Self {
+        Self { store, source, network, start_height }
+    }
+
+    /// Index all the blocks to the best of the source, return the tip height after sync.
+    /// The blocks not in the best chain of the source will be rollback first.
+    pub async fn sync(&self) -> Result<Option<u32>> {
+        let best = self.source.best_height().await.context("get best height")?;
+
+        while let Some((tip, hash)) = self.store.tip()? {
+            if tip <= best && self.source.block_hash(tip).await? == hash {
+                break;
+            }
+
+            log::warn!(target: TARGET, "the block {} {} is not in the best chain", tip, hash);
+            self.rollback_block(tip)?;
+        }
+
+        let mut height = match self.store.tip()? {
+            Some((tip, _)) => tip + 1,
+            None => self.start_height,
+        };
+
+        while height <= best {
+            let block = self
+                .source
+                .block_by_height(height)
+                .await
+                .with_context(|| format!("get block {}", height))?;
+
+            self.index_block(height, &block)?;
+            height += 1;
+        }
+
+        Ok(self.store.tip()?.map(|(tip, _)| tip))
+    }
+
+    /// Run the block and commit all the changes by it into the store.
+    pub fn index_block(&self, height: u32, block: &Block) -> Result<()> {
+        if let Some((tip, hash)) = self.store.tip()? {
+            if block.header.prev_blockhash != hash {
+                bail!(
+                    "the block {} {} is not on the tip {} {}",
+                    height,
+                    block.block_hash(),
+                    tip,
+                    hash
+                );
+            }
+        }
+
+        let env = BlockEnv::new(self.store.clone());
+        let protocol_version = protocol_version_at(self.network, height);
+        let res = BlockRunner::new(block, height, protocol_version)
+            .run(env.clone())
+            .with_context(|| format!("run block {}", height))?;
+
+        for tx in res.txs.iter() {
+            match tx.status {
+                TxRunStatus::Success => {
+                    log::info!(target: TARGET, "tx {} in {} run success", tx.tx_id, height);
+                }
+                TxRunStatus::Failed => {
+                    log::info!(target: TARGET, "tx {} in {} run failed", tx.tx_id, height);
+                }
+                TxRunStatus::NotVital => {
+                    log::info!(target: TARGET, "tx {} in {} spent resources", tx.tx_id, height);
+                }
+            }
+
+            for event in tx.events.iter() {
+                log::info!(target: TARGET, "  {:?}", event);
+            }
+        }
+
+        self.store.commit_block(height, block.block_hash(), env.take_changes())?;
+
+        if height % 1000 == 0 {
+            log::info!(target: TARGET, "indexed block {}", height);
+        }
+
+        Ok(())
+    }
+
+    /// Undo all the changes by the tip block.
+    pub fn rollback_block(&self, height: u32) -> Result<()> {
+        let env = BlockEnv::new(self.store.clone());
+        BlockRunner::rollback(height, env.clone())?;
+
+        self.store.rollback_block(height, env.take_changes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use bitcoin::{BlockHash, Network};
+
+    use crate::tests::blocks_after;
+
+    use super::*;
+
+    struct MockSource {
+        blocks: Vec<Block>,
+    }
+
+    #[async_trait::async_trait]
+    impl BlockSource for MockSource {
+        async fn best_height(&self) -> Result<u32> {
+            Ok(self.blocks.len() as u32 - 1)
+        }
+
+        async fn block_hash(&self, height: u32) -> Result<BlockHash> {
+            self.block_by_height(height).await.map(|block| block.block_hash())
+        }
+
+        async fn block_by_height(&self, height: u32) -> Result<Block> {
+            self.blocks
+                .get(height as usize)
+                .cloned()
+                .ok_or_else(|| anyhow!("not found block {}", height))
+        }
+    }
+
+    fn blocks(count: usize) -> Vec<Block> {
+        let genesis = bitcoin::blockdata::constants::genesis_block(Network::Regtest);
+
+        [vec![genesis.clone()], blocks_after(&genesis, count)].concat()
+    }
+
+    #[tokio::test]
+    async fn test_sync_should_work() -> Result<()> {
+        let blocks = blocks(5);
+        let store = Store::in_memory()?;
+
+        let indexer = Indexer::new(
+            store.clone(),
+            MockSource { blocks: blocks[..3].to_vec() },
+            Network::Regtest,
+            0,
+        );
+        assert_eq!(indexer.sync().await?, Some(2));
+
+        // continue from the tip
+        let indexer =
+            Indexer::new(store.clone(), MockSource { blocks: blocks.clone() }, Network::Regtest, 0);
+        assert_eq!(indexer.sync().await?, Some(5));
+
+        for (height, block) in blocks.iter().enumerate() {
+            assert_eq!(store.block_hash(height as u32)?, Some(block.block_hash()));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_from_start_height_should_work() -> Result<()> {
+        let blocks = blocks(5);
+        let store = Store::in_memory()?;
+
+        let indexer =
+            Indexer::new(store.clone(), MockSource { blocks: blocks.clone() }, Network::Regtest, 3);
+        assert_eq!(indexer.sync().await?, Some(5));
+
+        assert_eq!(store.block_hash(2)?, None);
+        assert_eq!(store.block_hash(3)?, Some(blocks[3].block_hash()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_block_not_on_tip_should_failed() -> Result<()> {
+        let blocks = blocks(2);
+        let fork = blocks_after(&blocks[0], 2);
+
+        let store = Store::in_memory()?;
+        let indexer =
+            Indexer::new(store.clone(), MockSource { blocks: blocks.clone() }, Network::Regtest, 0);
+        indexer.sync().await?;
+
+        let res = indexer.index_block(3, &fork[1]);
+        assert!(res.expect_err("should failed").to_string().starts_with("the block 3"));
+        assert_eq!(store.tip()?, Some((2, blocks[2].block_hash())));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_with_reorg_should_work() -> Result<()> {
+        let blocks = blocks(5);
+        let store = Store::in_memory()?;
+
+        let indexer =
+            Indexer::new(store.clone(), MockSource { blocks: blocks.clone() }, Network::Regtest, 0);
+        assert_eq!(indexer.sync().await?, Some(5));
+
+        // the blocks after 2 are replaced by a longer fork.
+        let fork = [blocks[..3].to_vec(), blocks_after(&blocks[2], 4)].concat();
+        let indexer =
+            Indexer::new(store.clone(), MockSource { blocks: fork.clone() }, Network::Regtest, 0);
+        assert_eq!(indexer.sync().await?, Some(6));
+
+        for (height, block) in fork.iter().enumerate() {
+            assert_eq!(store.block_hash(height as u32)?, Some(block.block_hash()));
+        }
+
+        // a shorter fork
+        let fork = [blocks[..2].to_vec(), blocks_after(&blocks[1], 2)].concat();
+        let indexer =
+            Indexer::new(store.clone(), MockSource { blocks: fork.clone() }, Network::Regtest, 0);
+        assert_eq!(indexer.sync().await?, Some(3));
+
+        assert_eq!(store.block_hash(3)?, Some(fork[3].block_hash()));
+        assert_eq!(store.block_hash(4)?, None);
+
+        Ok(())
+    }
+}
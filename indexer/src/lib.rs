@@ -0,0 +1,184 @@
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+
+use bitcoin::Network;
+
+mod env;
+mod indexer;
+mod rpc;
+pub mod source;
+mod store;
+
+use indexer::Indexer;
+use source::{BlkFilesSource, BlockSource, RestSource};
+use store::Store;
+
+pub(crate) const TARGET: &str = "indexer";
+
+/// The local indexer for vitalicals
+#[derive(Debug, Parser)]
+#[command(name = "vitalicals-indexer")]
+#[command(about = "A local indexer for vitalicals", long_about = None)]
+struct Cli {
+    /// if show debug log
+    #[arg(long, default_value = "false")]
+    pub debug: bool,
+
+    /// Sets the network.
+    #[arg(
+        short = 'n',
+        long = "network",
+        default_value = "testnet",
+        value_parser = ["bitcoin", "testnet", "signet", "regtest"],
+    )]
+    pub network: String,
+
+    /// The blocks dir of bitcoind, will index all the blocks in the `blk*.dat` files.
+    #[arg(long, conflicts_with = "rest", required_unless_present = "rest")]
+    pub blocks_dir: Option<PathBuf>,
+
+    /// The url of the bitcoind REST interface, like `http://127.0.0.1:18332`.
+    #[arg(long)]
+    pub rest: Option<String>,
+
+    /// Sets the indexer data directory.
+    #[clap(
+        name = "DATADIR",
+        short = 'd',
+        long = "datadir",
+        default_value = "./.vitalicals-indexer"
+    )]
+    pub datadir: PathBuf,
+
+    /// The addr for the json rpc server.
+    #[arg(long, default_value = "127.0.0.1:9944")]
+    pub rpc_addr: SocketAddr,
+
+    /// The height to start index, only used if the store is empty.
+    #[arg(long, default_value = "0")]
+    pub start_height: u32,
+
+    /// The seconds to wait for new blocks from the REST interface.
+    #[arg(long, default_value = "10")]
+    pub poll_interval: u64,
+}
+
+impl Cli {
+    /// Get the network parameters.
+    pub fn network(&self) -> Network {
+        match self.network.as_str() {
+            "bitcoin" => Network::Bitcoin,
+            "testnet" => Network::Testnet,
+            "signet" => Network::Signet,
+            "regtest" => Network::Regtest,
+            _ => panic!("Invalid network params {}", self.network),
+        }
+    }
+}
+
+pub async fn run() -> Result<()> {
+    let cli = Cli::parse();
+
+    let log_level = if cli.debug { log::LevelFilter::Debug } else { log::LevelFilter::Info };
+    let _ = env_logger::Builder::from_default_env()
+        .format_module_path(true)
+        .format_level(true)
+        .filter_level(log_level)
+        .try_init();
+
+    log::debug!("Run indexer {:?}", cli);
+
+    std::fs::create_dir_all(&cli.datadir)
+        .with_context(|| format!("create datadir {}", cli.datadir.display()))?;
+
+    let store = Store::open(cli.datadir.join("indexer.redb"))?;
+    let (_, rpc) = rpc::start(cli.rpc_addr, store.clone()).await?;
+
+    if let Some(dir) = cli.blocks_dir.as_ref() {
+        // the blk files will not be changed by us, so just index once.
+        let source = BlkFilesSource::new(dir, cli.network()).context("open blocks dir")?;
+        let tip = Indexer::new(store, source, cli.network(), cli.start_height).sync().await?;
+        log::info!(target: TARGET, "indexed all the blocks to {:?}", tip);
+
+        tokio::signal::ctrl_c().await?;
+    } else if let Some(url) = cli.rest.as_ref() {
+        let indexer = Indexer::new(store, RestSource::new(url), cli.network(), cli.start_height);
+        poll(&indexer, Duration::from_secs(cli.poll_interval)).await?;
+    }
+
+    rpc.stop()?;
+
+    Ok(())
+}
+
+/// Sync the indexer by the interval, until ctrl-c.
+async fn poll<Source: BlockSource>(indexer: &Indexer<Source>, interval: Duration) -> Result<()> {
+    loop {
+        match indexer.sync().await {
+            Ok(tip) => log::debug!(target: TARGET, "synced to {:?}", tip),
+            Err(err) => log::warn!(target: TARGET, "sync failed by {:#}", err),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            res = tokio::signal::ctrl_c() => return res.context("wait ctrl-c"),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use bitcoin::{
+        absolute::LockTime,
+        block::{Header, Version},
+        hashes::Hash,
+        transaction, Amount, Block, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxMerkleNode,
+        TxOut, Witness,
+    };
+
+    /// Build the blocks chained after the prev, the blocks are not valid for pow.
+    pub(crate) fn blocks_after(prev: &Block, count: usize) -> Vec<Block> {
+        // make all the blocks not the same.
+        static NONCE: AtomicU32 = AtomicU32::new(0);
+
+        let mut res: Vec<Block> = Vec::with_capacity(count);
+        for _ in 0..count {
+            let prev = res.last().unwrap_or(prev);
+
+            let coinbase = Transaction {
+                version: transaction::Version::TWO,
+                lock_time: LockTime::ZERO,
+                input: vec![TxIn {
+                    previous_output: OutPoint::null(),
+                    script_sig: ScriptBuf::from_bytes(
+                        NONCE.fetch_add(1, Ordering::SeqCst).to_le_bytes().to_vec(),
+                    ),
+                    sequence: Sequence::MAX,
+                    witness: Witness::new(),
+                }],
+                output: vec![TxOut { value: Amount::ZERO, script_pubkey: ScriptBuf::new() }],
+            };
+
+            let mut block = Block {
+                header: Header {
+                    version: Version::TWO,
+                    prev_blockhash: prev.block_hash(),
+                    merkle_root: TxMerkleNode::all_zeros(),
+                    time: prev.header.time + 1,
+                    bits: prev.header.bits,
+                    nonce: 0,
+                },
+                txdata: vec![coinbase],
+            };
+            block.header.merkle_root = block.compute_merkle_root().expect("has coinbase");
+
+            res.push(block);
+        }
+
+        res
+    }
+}
@@ -0,0 +1,126 @@
+//! The json rpc server, the same methods as `IndexerClient` calls.
+
+use std::net::SocketAddr;
+
+use anyhow::{Context, Result};
+use jsonrpsee::{
+    server::{ServerBuilder, ServerHandle},
+    RpcModule,
+};
+
+use bitcoin::{OutPoint, Txid};
+use vital_script::{primitives::traits::EnvContext as _, runner::EnvContext};
+
+use crate::{env::BlockEnv, store::Store, TARGET};
+
+pub async fn start(addr: SocketAddr, store: Store) -> Result<(SocketAddr, ServerHandle)> {
+    let server = ServerBuilder::default().build(addr).await.context("build rpc server")?;
+    let addr = server.local_addr().context("rpc server addr")?;
+
+    let handle = server.start(rpc_module(store)?).context("start rpc server")?;
+
+    log::info!(target: TARGET, "rpc server started at {}", addr);
+
+    Ok((addr, handle))
+}
+
+fn rpc_module(store: Store) -> Result<RpcModule<Store>> {
+    let mut module = RpcModule::new(store);
+
+    module.register_method("vital.resource", |params, store| {
+        let (txid, vout) = params.parse::<(Txid, u32)>()?;
+
+        Ok(store.get_resource(&OutPoint { txid, vout })?)
+    })?;
+
+    module.register_method("vital.storage", |params, store| {
+        let (key,) = params.parse::<(Vec<u8>,)>()?;
+
+        Ok(store.get_storage(&key)?)
+    })?;
+
+    // the resources parked or stored in the space of the tx, can be claimed by its first output.
+    module.register_method("vital.space", |params, store| {
+        let (txid,) = params.parse::<(Txid,)>()?;
+
+        let height = store.tip()?.map(|(height, _)| height).unwrap_or_default();
+        let env = EnvContext::new_for_query(BlockEnv::new(store.clone()), height);
+
+        Ok(env.get_space_resources(&txid)?)
+    })?;
+
+    Ok(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{absolute::LockTime, hashes::Hash, transaction, BlockHash, Transaction, TxIn};
+    use vital_script::{
+        primitives::{names::Name, resources::Resource},
+        runner::traits::EnvFunctions,
+    };
+
+    use crate::store::BlockChanges;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rpc_methods_should_work() -> Result<()> {
+        let store = Store::in_memory()?;
+
+        let outpoint = OutPoint { txid: Txid::all_zeros(), vout: 1 };
+        let name = Resource::name(Name::must_from("abc"));
+
+        let mut changes = BlockChanges::default();
+        changes.resources.insert(outpoint, Some(name.clone()));
+        changes.storage.insert(b"key".to_vec(), Some(b"value".to_vec()));
+        store.commit_block(0, BlockHash::all_zeros(), changes)?;
+
+        let module = rpc_module(store)?;
+
+        let res: Option<Resource> = module.call("vital.resource", (outpoint.txid, 1)).await?;
+        assert_eq!(res, Some(name));
+
+        let res: Option<Resource> = module.call("vital.resource", (outpoint.txid, 2)).await?;
+        assert_eq!(res, None);
+
+        let res: Option<Vec<u8>> = module.call("vital.storage", (b"key".to_vec(),)).await?;
+        assert_eq!(res, Some(b"value".to_vec()));
+
+        let res: Option<Vec<u8>> = module.call("vital.storage", (b"none".to_vec(),)).await?;
+        assert_eq!(res, None);
+
+        let res: Vec<Resource> = module.call("vital.space", (outpoint.txid,)).await?;
+        assert!(res.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rpc_query_space_should_work() -> Result<()> {
+        let store = Store::in_memory()?;
+        let env = BlockEnv::new(store.clone());
+
+        let name = Resource::name(Name::must_from("abc"));
+        let outpoint = OutPoint { txid: Txid::all_zeros(), vout: 1 };
+        env.bind_resource(outpoint, name.clone())?;
+        store.commit_block(0, BlockHash::all_zeros(), env.take_changes())?;
+
+        // spent by a tx without vital script, the name will be parked into its space.
+        let spent = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn { previous_output: outpoint, ..Default::default() }],
+            output: vec![],
+        };
+        vital_script::runner::park_spent_resources(&spent, &env)?;
+        store.commit_block(1, BlockHash::all_zeros(), env.take_changes())?;
+
+        let module = rpc_module(store)?;
+
+        let res: Vec<Resource> = module.call("vital.space", (spent.txid(),)).await?;
+        assert_eq!(res, vec![name]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,385 @@
+//! Read the blocks from the `blk*.dat` files of bitcoind.
+//!
+//! Each record in the files is `magic | size (u32 le) | block`, the blocks are not
+//! stored by the height, so need link them by the prev block hash to find the best chain.
+//! Since bitcoind v28 the files are xor-ed by the key in `xor.dat`.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{ErrorKind, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use bitcoin::{block::Header, consensus, pow::Work, Block, BlockHash, Network};
+
+use super::BlockSource;
+use crate::TARGET;
+
+const XOR_KEY_FILE: &str = "xor.dat";
+const RECORD_HEAD_LEN: usize = 8;
+const BLOCK_HEADER_LEN: usize = 80;
+
+#[derive(Debug, Clone, Copy)]
+struct BlockLocation {
+    hash: BlockHash,
+    file: usize,
+    offset: u64,
+    size: u32,
+}
+
+/// The header info read from the files, used to find the best chain.
+#[derive(Debug, Clone, Copy)]
+struct HeaderEntry {
+    prev: BlockHash,
+    location: BlockLocation,
+    work: Work,
+    /// The order the block seen in the files.
+    seq: usize,
+}
+
+/// The block source from the bitcoind 's blocks dir, all the blocks should be synced,
+/// it will not see the blocks which written after it created.
+pub struct BlkFilesSource {
+    files: Vec<PathBuf>,
+    xor_key: Option<[u8; 8]>,
+    /// The blocks in the best chain, by the height.
+    chain: Vec<BlockLocation>,
+    /// Keep the last opened file.
+    reader: Mutex<Option<(usize, BlkReader)>>,
+}
+
+impl BlkFilesSource {
+    pub fn new(dir: impl AsRef<Path>, network: Network) -> Result<Self> {
+        let dir = dir.as_ref();
+
+        let mut files = fs::read_dir(dir)
+            .with_context(|| format!("read dir {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+                name.starts_with("blk") && name.ends_with(".dat")
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+
+        let xor_key = read_xor_key(dir).context("read xor key")?;
+        let magic = network.magic().to_bytes();
+
+        let mut headers = HashMap::new();
+        for (index, path) in files.iter().enumerate() {
+            let mut reader = BlkReader::open(path, xor_key)?;
+
+            let mut offset = 0_u64;
+            let mut head = [0_u8; RECORD_HEAD_LEN + BLOCK_HEADER_LEN];
+            while reader.read_at(offset, &mut head)? {
+                // the bitcoind will pre-allocate the file by zeros.
+                if head[..4] == [0_u8; 4] {
+                    break;
+                }
+
+                if head[..4] != magic {
+                    bail!("invalid magic at {} in {}", offset, path.display());
+                }
+
+                let size = u32::from_le_bytes(head[4..8].try_into().expect("4 bytes"));
+                let header: Header = consensus::deserialize(&head[RECORD_HEAD_LEN..])
+                    .with_context(|| {
+                        format!("decode header at {} in {}", offset, path.display())
+                    })?;
+
+                let location = BlockLocation {
+                    hash: header.block_hash(),
+                    file: index,
+                    offset: offset + RECORD_HEAD_LEN as u64,
+                    size,
+                };
+                let seq = headers.len();
+                headers.entry(location.hash).or_insert(HeaderEntry {
+                    prev: header.prev_blockhash,
+                    location,
+                    work: header.work(),
+                    seq,
+                });
+
+                offset = location.offset + size as u64;
+            }
+        }
+
+        log::info!(target: TARGET, "found {} blocks in {} files", headers.len(), files.len());
+
+        let genesis = bitcoin::blockdata::constants::genesis_block(network).block_hash();
+        let chain = best_chain(genesis, &headers)?;
+
+        Ok(Self { files, xor_key, chain, reader: Mutex::new(None) })
+    }
+
+    fn location(&self, height: u32) -> Result<BlockLocation> {
+        self.chain
+            .get(height as usize)
+            .copied()
+            .ok_or_else(|| anyhow!("not found block by height {}", height))
+    }
+
+    fn read_block(&self, location: BlockLocation) -> Result<Block> {
+        let mut reader = self.reader.lock().expect("lock");
+
+        if !matches!(reader.as_ref(), Some((file, _)) if *file == location.file) {
+            let path = &self.files[location.file];
+            *reader = Some((location.file, BlkReader::open(path, self.xor_key)?));
+        }
+
+        let (_, reader) = reader.as_mut().expect("opened");
+
+        let mut datas = vec![0_u8; location.size as usize];
+        if !reader.read_at(location.offset, &mut datas)? {
+            bail!("the block at {} is not complete", location.offset);
+        }
+
+        consensus::deserialize(&datas).context("decode block")
+    }
+}
+
+#[async_trait::async_trait]
+impl BlockSource for BlkFilesSource {
+    async fn best_height(&self) -> Result<u32> {
+        if self.chain.is_empty() {
+            bail!("no blocks found");
+        }
+
+        Ok(self.chain.len() as u32 - 1)
+    }
+
+    async fn block_hash(&self, height: u32) -> Result<BlockHash> {
+        Ok(self.location(height)?.hash)
+    }
+
+    async fn block_by_height(&self, height: u32) -> Result<Block> {
+        let location = self.location(height)?;
+
+        self.read_block(location).with_context(|| format!("read block {}", height))
+    }
+}
+
+/// Find the chain with the most cumulative work from the genesis, if the chainworks are the
+/// same, the one whose tip seen first in the files wins.
+fn best_chain(
+    genesis: BlockHash,
+    headers: &HashMap<BlockHash, HeaderEntry>,
+) -> Result<Vec<BlockLocation>> {
+    let genesis_entry = headers
+        .get(&genesis)
+        .ok_or_else(|| anyhow!("not found the genesis block {}", genesis))?;
+
+    let mut children = HashMap::<BlockHash, Vec<BlockHash>>::new();
+    for (hash, entry) in headers.iter() {
+        children.entry(entry.prev).or_default().push(*hash);
+    }
+
+    // (chainwork, seq of the tip, height, tip)
+    let mut best = (genesis_entry.work, genesis_entry.seq, 0_u32, genesis);
+    let mut stack = vec![(genesis_entry.work, 0_u32, genesis)];
+    while let Some((chainwork, height, hash)) = stack.pop() {
+        let seq = headers[&hash].seq;
+        if chainwork > best.0 || (chainwork == best.0 && seq < best.1) {
+            best = (chainwork, seq, height, hash);
+        }
+
+        for child in children.get(&hash).into_iter().flatten() {
+            stack.push((chainwork + headers[child].work, height + 1, *child));
+        }
+    }
+
+    let mut chain = Vec::with_capacity(best.2 as usize + 1);
+    let mut curr = best.3;
+    loop {
+        let entry = headers[&curr];
+        chain.push(entry.location);
+
+        if curr == genesis {
+            break;
+        }
+        curr = entry.prev;
+    }
+    chain.reverse();
+
+    Ok(chain)
+}
+
+fn read_xor_key(dir: &Path) -> Result<Option<[u8; 8]>> {
+    let path = dir.join(XOR_KEY_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let key: [u8; 8] = fs::read(&path)?
+        .try_into()
+        .map_err(|_| anyhow!("the {} should be 8 bytes", XOR_KEY_FILE))?;
+
+    Ok(if key == [0_u8; 8] { None } else { Some(key) })
+}
+
+struct BlkReader {
+    file: File,
+    xor_key: Option<[u8; 8]>,
+}
+
+impl BlkReader {
+    fn open(path: &Path, xor_key: Option<[u8; 8]>) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+
+        Ok(Self { file, xor_key })
+    }
+
+    /// Read the datas at the pos, return false if the file is ended.
+    fn read_at(&mut self, pos: u64, buf: &mut [u8]) -> Result<bool> {
+        self.file.seek(SeekFrom::Start(pos))?;
+
+        match self.file.read_exact(buf) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(false),
+            Err(err) => return Err(err.into()),
+        }
+
+        if let Some(key) = self.xor_key {
+            for (i, b) in buf.iter_mut().enumerate() {
+                *b ^= key[(pos as usize + i) % key.len()];
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use bitcoin::CompactTarget;
+
+    use crate::tests::blocks_after;
+
+    use super::*;
+
+    fn write_blk_file(path: &Path, blocks: &[Block], xor_key: Option<[u8; 8]>) {
+        let mut datas = Vec::new();
+        for block in blocks {
+            let block = consensus::serialize(block);
+
+            datas.extend(Network::Regtest.magic().to_bytes());
+            datas.extend((block.len() as u32).to_le_bytes());
+            datas.extend(block);
+        }
+        // the pre-allocated zeros
+        datas.extend([0_u8; 64]);
+
+        if let Some(key) = xor_key {
+            for (i, b) in datas.iter_mut().enumerate() {
+                *b ^= key[i % 8];
+            }
+        }
+
+        File::create(path)
+            .and_then(|mut f| f.write_all(&datas))
+            .expect("write blk file");
+    }
+
+    #[tokio::test]
+    async fn test_read_blk_files_should_work() -> Result<()> {
+        let genesis = bitcoin::blockdata::constants::genesis_block(Network::Regtest);
+        let blocks = [vec![genesis.clone()], blocks_after(&genesis, 4)].concat();
+
+        for xor_key in [None, Some([1, 2, 3, 4, 5, 6, 7, 8])] {
+            let dir = tempfile::tempdir()?;
+            if let Some(key) = xor_key {
+                fs::write(dir.path().join(XOR_KEY_FILE), key)?;
+            }
+
+            // the blocks not in the height order.
+            write_blk_file(
+                &dir.path().join("blk00000.dat"),
+                &[&blocks[3..], &blocks[..1]].concat(),
+                xor_key,
+            );
+            write_blk_file(&dir.path().join("blk00001.dat"), &blocks[1..3], xor_key);
+
+            let source = BlkFilesSource::new(dir.path(), Network::Regtest)?;
+            assert_eq!(source.best_height().await?, 4);
+
+            for (height, block) in blocks.iter().enumerate() {
+                assert_eq!(&source.block_by_height(height as u32).await?, block);
+                assert_eq!(source.block_hash(height as u32).await?, block.block_hash());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_blk_files_should_use_the_longest_chain() -> Result<()> {
+        let genesis = bitcoin::blockdata::constants::genesis_block(Network::Regtest);
+        let main = blocks_after(&genesis, 3);
+        let fork = blocks_after(&main[0], 1);
+
+        let dir = tempfile::tempdir()?;
+        write_blk_file(
+            &dir.path().join("blk00000.dat"),
+            &[&[genesis], &fork[..], &main[..]].concat(),
+            None,
+        );
+
+        let source = BlkFilesSource::new(dir.path(), Network::Regtest)?;
+        assert_eq!(source.best_height().await?, 3);
+        assert_eq!(source.block_by_height(2).await?, main[1]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_blk_files_should_use_the_chain_with_most_work() -> Result<()> {
+        let genesis = bitcoin::blockdata::constants::genesis_block(Network::Regtest);
+        let main = blocks_after(&genesis, 3);
+
+        // the fork is shorter, but its tip has more work.
+        let mut fork = blocks_after(&main[0], 1);
+        fork[0].header.bits = CompactTarget::from_consensus(0x1d00ffff);
+
+        let dir = tempfile::tempdir()?;
+        write_blk_file(
+            &dir.path().join("blk00000.dat"),
+            &[&[genesis], &main[..], &fork[..]].concat(),
+            None,
+        );
+
+        let source = BlkFilesSource::new(dir.path(), Network::Regtest)?;
+        assert_eq!(source.best_height().await?, 2);
+        assert_eq!(source.block_by_height(1).await?, main[0]);
+        assert_eq!(source.block_by_height(2).await?, fork[0]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_blk_files_with_same_work_should_use_the_first_seen() -> Result<()> {
+        let genesis = bitcoin::blockdata::constants::genesis_block(Network::Regtest);
+        let main = blocks_after(&genesis, 2);
+        let fork = blocks_after(&main[0], 1);
+
+        for (blocks, tip) in [
+            ([&[genesis.clone()], &fork[..], &main[..]].concat(), &fork[0]),
+            ([&[genesis.clone()], &main[..], &fork[..]].concat(), &main[1]),
+        ] {
+            let dir = tempfile::tempdir()?;
+            write_blk_file(&dir.path().join("blk00000.dat"), &blocks, None);
+
+            let source = BlkFilesSource::new(dir.path(), Network::Regtest)?;
+            assert_eq!(source.best_height().await?, 2);
+            assert_eq!(&source.block_by_height(2).await?, tip);
+        }
+
+        Ok(())
+    }
+}
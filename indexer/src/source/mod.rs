@@ -0,0 +1,23 @@
+//! The sources to read the raw blocks.
+
+use anyhow::Result;
+
+use bitcoin::{Block, BlockHash};
+
+mod blk_files;
+mod rest;
+
+pub use blk_files::BlkFilesSource;
+pub use rest::RestSource;
+
+#[async_trait::async_trait]
+pub trait BlockSource: Send + Sync {
+    /// The height of the best block in the source.
+    async fn best_height(&self) -> Result<u32>;
+
+    /// Get the hash of the block in the best chain by the height.
+    async fn block_hash(&self, height: u32) -> Result<BlockHash>;
+
+    /// Get the block in the best chain by the height.
+    async fn block_by_height(&self, height: u32) -> Result<Block>;
+}
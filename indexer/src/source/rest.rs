@@ -0,0 +1,64 @@
+//! Read the blocks by the bitcoind REST interface (`-rest`), or any server compatible with it.
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use hyper::{body::Bytes, client::HttpConnector, Client, Uri};
+
+use bitcoin::{consensus, Block, BlockHash};
+
+use super::BlockSource;
+
+pub struct RestSource {
+    client: Client<HttpConnector>,
+    url: String,
+}
+
+impl RestSource {
+    pub fn new(url: impl Into<String>) -> Self {
+        let url = url.into().trim_end_matches('/').to_string();
+
+        Self { client: Client::new(), url }
+    }
+
+    async fn get(&self, path: &str) -> Result<Bytes> {
+        let uri = Uri::from_str(&format!("{}/rest/{}", self.url, path))
+            .with_context(|| format!("invalid rest url {}", self.url))?;
+
+        let res = self.client.get(uri).await.with_context(|| format!("get {}", path))?;
+        if !res.status().is_success() {
+            bail!("get {} failed by status {}", path, res.status());
+        }
+
+        hyper::body::to_bytes(res.into_body())
+            .await
+            .with_context(|| format!("read {}", path))
+    }
+}
+
+#[async_trait::async_trait]
+impl BlockSource for RestSource {
+    async fn best_height(&self) -> Result<u32> {
+        let res = self.get("chaininfo.json").await?;
+        let info: serde_json::Value = serde_json::from_slice(&res).context("decode chain info")?;
+
+        let height = info["blocks"].as_u64().ok_or_else(|| anyhow!("no blocks in chain info"))?;
+
+        Ok(height as u32)
+    }
+
+    async fn block_hash(&self, height: u32) -> Result<BlockHash> {
+        let res = self.get(&format!("blockhashbyheight/{}.hex", height)).await?;
+
+        std::str::from_utf8(&res)
+            .map_err(|err| anyhow!("invalid block hash by {}", err))
+            .and_then(|hash| BlockHash::from_str(hash.trim()).context("decode block hash"))
+    }
+
+    async fn block_by_height(&self, height: u32) -> Result<Block> {
+        let hash = self.block_hash(height).await?;
+        let res = self.get(&format!("block/{}.bin", hash)).await?;
+
+        consensus::deserialize(&res).with_context(|| format!("decode block {}", hash))
+    }
+}
@@ -0,0 +1,327 @@
+//! The embedded kv store for the indexer state.
+//!
+//! All the changes by a block will be written in one transaction, so the store is
+//! always at a block boundary.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use parity_scale_codec::{Decode, Encode};
+use redb::{Database, ReadableTable, TableDefinition, WriteTransaction};
+
+use bitcoin::{consensus, hashes::Hash, BlockHash, OutPoint};
+use vital_script::primitives::resources::Resource;
+
+/// The outpoint (consensus encoded) -> the resource bind to it (scale encoded).
+const RESOURCES: TableDefinition<&[u8], &[u8]> = TableDefinition::new("resources");
+/// The vital storage key -> value.
+const STORAGE: TableDefinition<&[u8], &[u8]> = TableDefinition::new("storage");
+/// The height -> block hash for all the indexed blocks.
+const BLOCKS: TableDefinition<u32, &[u8]> = TableDefinition::new("blocks");
+
+/// The changes to the store by a block, `None` means the resource had been unbind
+/// or the storage value had been removed.
+#[derive(Debug, Default, Clone)]
+pub struct BlockChanges {
+    pub resources: BTreeMap<OutPoint, Option<Resource>>,
+    pub storage: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+#[derive(Clone)]
+pub struct Store {
+    db: Arc<Database>,
+    /// The outpoints with resources bind, loaded from the resources table when open, so the
+    /// outpoints never bound can be checked without reading the db.
+    bound: Arc<RwLock<HashSet<OutPoint>>>,
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let db = Database::create(path.as_ref())
+            .with_context(|| format!("open db {}", path.as_ref().display()))?;
+
+        Self::new(db)
+    }
+
+    #[cfg(test)]
+    pub fn in_memory() -> Result<Self> {
+        let db = redb::Builder::new()
+            .create_with_backend(redb::backends::InMemoryBackend::new())
+            .context("create in memory db")?;
+
+        Self::new(db)
+    }
+
+    fn new(db: Database) -> Result<Self> {
+        // create the tables, so the read txs can open them.
+        let txn = db.begin_write()?;
+        txn.open_table(RESOURCES)?;
+        txn.open_table(STORAGE)?;
+        txn.open_table(BLOCKS)?;
+        txn.commit()?;
+
+        let mut bound = HashSet::new();
+        {
+            let txn = db.begin_read()?;
+            let table = txn.open_table(RESOURCES)?;
+            for entry in table.iter()? {
+                let (key, _) = entry?;
+                let outpoint = consensus::deserialize::<OutPoint>(key.value())
+                    .context("decode the outpoint of resource")?;
+                bound.insert(outpoint);
+            }
+        }
+
+        Ok(Self { db: Arc::new(db), bound: Arc::new(RwLock::new(bound)) })
+    }
+
+    /// The height and hash of the last indexed block.
+    pub fn tip(&self) -> Result<Option<(u32, BlockHash)>> {
+        let txn = self.db.begin_read()?;
+        let table = txn.open_table(BLOCKS)?;
+
+        let res = table.last()?;
+        res.map(|(height, hash)| Ok((height.value(), decode_block_hash(hash.value())?)))
+            .transpose()
+    }
+
+    #[cfg(test)]
+    pub fn block_hash(&self, height: u32) -> Result<Option<BlockHash>> {
+        let txn = self.db.begin_read()?;
+        let table = txn.open_table(BLOCKS)?;
+
+        let res = table.get(height)?;
+        res.map(|hash| decode_block_hash(hash.value())).transpose()
+    }
+
+    pub fn get_resource(&self, outpoint: &OutPoint) -> Result<Option<Resource>> {
+        let txn = self.db.begin_read()?;
+        let table = txn.open_table(RESOURCES)?;
+
+        let res = table.get(consensus::serialize(outpoint).as_slice())?;
+        res.map(|value| {
+            Resource::decode(&mut value.value())
+                .map_err(|err| anyhow!("decode resource for {} failed by {:?}", outpoint, err))
+        })
+        .transpose()
+    }
+
+    /// If the outpoint has a resource bind, not need read the db.
+    pub fn is_bound(&self, outpoint: &OutPoint) -> bool {
+        self.bound.read().expect("lock").contains(outpoint)
+    }
+
+    pub fn get_storage(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let txn = self.db.begin_read()?;
+        let table = txn.open_table(STORAGE)?;
+
+        let res = table.get(key)?;
+        Ok(res.map(|value| value.value().to_vec()))
+    }
+
+    /// Write all the changes by the block, the block should be the next one of the tip.
+    pub fn commit_block(&self, height: u32, hash: BlockHash, changes: BlockChanges) -> Result<()> {
+        if let Some((tip, _)) = self.tip()? {
+            if height != tip + 1 {
+                bail!("the block {} is not the next of the tip {}", height, tip);
+            }
+        }
+
+        let txn = self.db.begin_write()?;
+        {
+            Self::apply_changes(&txn, &changes)?;
+
+            let mut blocks = txn.open_table(BLOCKS)?;
+            blocks.insert(height, hash.to_byte_array().as_slice())?;
+        }
+        txn.commit().with_context(|| format!("commit block {}", height))?;
+        self.apply_bound(&changes);
+
+        Ok(())
+    }
+
+    /// Write the changes by the rollback of the tip block, then remove the tip.
+    pub fn rollback_block(&self, height: u32, changes: BlockChanges) -> Result<()> {
+        let tip = self.tip()?.map(|(tip, _)| tip);
+        if tip != Some(height) {
+            bail!("the block {} to rollback is not the tip {:?}", height, tip);
+        }
+
+        let txn = self.db.begin_write()?;
+        {
+            Self::apply_changes(&txn, &changes)?;
+
+            let mut blocks = txn.open_table(BLOCKS)?;
+            blocks.remove(height)?;
+        }
+        txn.commit().with_context(|| format!("rollback block {}", height))?;
+        self.apply_bound(&changes);
+
+        Ok(())
+    }
+
+    /// Update the bound outpoints by the changes which had been written.
+    fn apply_bound(&self, changes: &BlockChanges) {
+        let mut bound = self.bound.write().expect("lock");
+        for (outpoint, resource) in changes.resources.iter() {
+            match resource {
+                Some(_) => bound.insert(*outpoint),
+                None => bound.remove(outpoint),
+            };
+        }
+    }
+
+    fn apply_changes(txn: &WriteTransaction, changes: &BlockChanges) -> Result<()> {
+        let mut resources = txn.open_table(RESOURCES)?;
+        for (outpoint, resource) in changes.resources.iter() {
+            let key = consensus::serialize(outpoint);
+            match resource {
+                Some(resource) => resources.insert(key.as_slice(), resource.encode().as_slice())?,
+                None => resources.remove(key.as_slice())?,
+            };
+        }
+
+        let mut storage = txn.open_table(STORAGE)?;
+        for (key, value) in changes.storage.iter() {
+            match value {
+                Some(value) => storage.insert(key.as_slice(), value.as_slice())?,
+                None => storage.remove(key.as_slice())?,
+            };
+        }
+
+        Ok(())
+    }
+}
+
+fn decode_block_hash(bytes: &[u8]) -> Result<BlockHash> {
+    BlockHash::from_slice(bytes).map_err(|err| anyhow!("invalid block hash by {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use vital_script::primitives::{names::Name, H256};
+
+    use super::*;
+
+    fn outpoint(vout: u32) -> OutPoint {
+        OutPoint { txid: bitcoin::Txid::all_zeros(), vout }
+    }
+
+    #[test]
+    fn test_commit_block_should_work() -> Result<()> {
+        let store = Store::in_memory()?;
+        assert_eq!(store.tip()?, None);
+
+        let name = Resource::name(Name::must_from("abc"));
+        let vrc721 = Resource::vrc721(H256::repeat_byte(1));
+
+        let mut changes = BlockChanges::default();
+        changes.resources.insert(outpoint(0), Some(name.clone()));
+        changes.resources.insert(outpoint(1), Some(vrc721.clone()));
+        changes.storage.insert(b"key".to_vec(), Some(b"value".to_vec()));
+
+        let hash = BlockHash::from_byte_array([1; 32]);
+        store.commit_block(100, hash, changes)?;
+
+        assert_eq!(store.tip()?, Some((100, hash)));
+        assert_eq!(store.block_hash(100)?, Some(hash));
+        assert_eq!(store.get_resource(&outpoint(0))?, Some(name));
+        assert_eq!(store.get_resource(&outpoint(1))?, Some(vrc721.clone()));
+        assert_eq!(store.get_storage(b"key")?, Some(b"value".to_vec()));
+
+        // unbind the name by the next block.
+        let mut changes = BlockChanges::default();
+        changes.resources.insert(outpoint(0), None);
+        changes.storage.insert(b"key".to_vec(), None);
+
+        let hash = BlockHash::from_byte_array([2; 32]);
+        store.commit_block(101, hash, changes)?;
+
+        assert_eq!(store.tip()?, Some((101, hash)));
+        assert_eq!(store.get_resource(&outpoint(0))?, None);
+        assert_eq!(store.get_resource(&outpoint(1))?, Some(vrc721));
+        assert_eq!(store.get_storage(b"key")?, None);
+        assert!(!store.is_bound(&outpoint(0)));
+        assert!(store.is_bound(&outpoint(1)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rollback_block_should_work() -> Result<()> {
+        let store = Store::in_memory()?;
+        let name = Resource::name(Name::must_from("abc"));
+
+        store.commit_block(100, BlockHash::from_byte_array([1; 32]), Default::default())?;
+
+        let mut changes = BlockChanges::default();
+        changes.resources.insert(outpoint(0), Some(name.clone()));
+        store.commit_block(101, BlockHash::from_byte_array([2; 32]), changes)?;
+
+        let res = store.rollback_block(100, Default::default());
+        assert_eq!(
+            res.expect_err("should failed").to_string(),
+            "the block 100 to rollback is not the tip Some(101)"
+        );
+
+        let mut changes = BlockChanges::default();
+        changes.resources.insert(outpoint(0), None);
+        store.rollback_block(101, changes)?;
+
+        assert_eq!(store.tip()?, Some((100, BlockHash::from_byte_array([1; 32]))));
+        assert_eq!(store.block_hash(101)?, None);
+        assert_eq!(store.get_resource(&outpoint(0))?, None);
+        assert!(!store.is_bound(&outpoint(0)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bound_outpoints_should_be_loaded_when_open() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("store.redb");
+
+        {
+            let store = Store::open(&path)?;
+
+            let mut changes = BlockChanges::default();
+            changes
+                .resources
+                .insert(outpoint(0), Some(Resource::name(Name::must_from("abc"))));
+            changes
+                .resources
+                .insert(outpoint(1), Some(Resource::name(Name::must_from("abd"))));
+            store.commit_block(100, BlockHash::from_byte_array([1; 32]), changes)?;
+
+            let mut changes = BlockChanges::default();
+            changes.resources.insert(outpoint(1), None);
+            store.commit_block(101, BlockHash::from_byte_array([2; 32]), changes)?;
+        }
+
+        let store = Store::open(&path)?;
+        assert!(store.is_bound(&outpoint(0)));
+        assert!(!store.is_bound(&outpoint(1)));
+        assert!(!store.is_bound(&outpoint(2)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_block_not_next_should_failed() -> Result<()> {
+        let store = Store::in_memory()?;
+
+        store.commit_block(100, BlockHash::all_zeros(), Default::default())?;
+
+        let res = store.commit_block(102, BlockHash::all_zeros(), Default::default());
+        assert_eq!(
+            res.expect_err("should failed").to_string(),
+            "the block 102 is not the next of the tip 100"
+        );
+
+        Ok(())
+    }
+}
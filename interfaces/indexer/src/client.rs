@@ -9,7 +9,7 @@ use jsonrpsee::{
     rpc_params,
 };
 
-use bitcoin::OutPoint;
+use bitcoin::{OutPoint, Txid};
 use vital_script::primitives::resources::Resource;
 
 use crate::traits::IndexerClientT;
@@ -49,6 +49,16 @@ impl IndexerClientT for IndexerClient {
 
         Ok(res)
     }
+
+    async fn get_space(&self, txid: &Txid) -> Result<Vec<Resource>> {
+        let res = self
+            .client
+            .request("vital.space", rpc_params![txid])
+            .await
+            .with_context(|| format!("request by {}", txid))?;
+
+        Ok(res)
+    }
 }
 
 #[cfg(test)]
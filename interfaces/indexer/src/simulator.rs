@@ -75,6 +75,11 @@ impl SimulatorStatus {
         Ok(())
     }
 
+    fn storage_remove(&mut self, key: &[u8]) {
+        self.storage.remove(key);
+        self.removed_storage.insert(key.to_vec());
+    }
+
     fn is_storage_removed(&self, key: &[u8]) -> bool {
         self.removed_storage.contains(key)
     }
@@ -174,4 +179,10 @@ where
 
         Ok(())
     }
+
+    fn storage_remove(&self, key: &[u8]) -> Result<()> {
+        self.cache.lock().expect("lock").storage_remove(key);
+
+        Ok(())
+    }
 }
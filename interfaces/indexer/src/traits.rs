@@ -1,6 +1,6 @@
 use anyhow::Result;
 
-use bitcoin::OutPoint;
+use bitcoin::{OutPoint, Txid};
 use vital_script::primitives::resources::Resource;
 
 /// A Trait for indexer
@@ -11,4 +11,7 @@ pub trait IndexerClientT: Clone + Send + Sync {
 
     /// Get vital storage by key-value pair
     async fn get_storage(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Get the resources in the space of the tx, which can be claimed by its first output.
+    async fn get_space(&self, txid: &Txid) -> Result<Vec<Resource>>;
 }
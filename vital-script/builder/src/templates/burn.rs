@@ -0,0 +1,94 @@
+use anyhow::{bail, Context, Result};
+use vital_script_ops::{
+    builder::instruction::ScriptBuilderFromInstructions,
+    instruction::{
+        assert_input::InstructionInputAssert, assert_output::InstructionOutputAssert,
+        resource_burn::InstructionResourceBurn, Instruction,
+    },
+};
+use vital_script_primitives::{
+    resources::{Resource, Tag, VRC20},
+    U256,
+};
+
+use super::Outputs;
+
+/// Build a script to burn the short names / names, the inputs is from 1.
+pub fn burn_names(names: &[Tag]) -> Result<Vec<u8>> {
+    let mut instructions = Vec::with_capacity(names.len() * 2);
+
+    // input assert
+    for (input_index, name) in names.iter().enumerate() {
+        let input_index = input_index + 1; // all inputs is from 1
+        if input_index >= u8::MAX as usize {
+            bail!("the input index too large");
+        }
+
+        instructions.push(Instruction::Input(InstructionInputAssert {
+            index: input_index as u8,
+            resource: (*name).into(),
+        }))
+    }
+
+    for name in names.iter() {
+        instructions.push(Instruction::Burn(InstructionResourceBurn { resource: (*name).into() }));
+    }
+
+    let ops_bytes =
+        ScriptBuilderFromInstructions::build(instructions).context("build script failed")?;
+
+    Ok(ops_bytes)
+}
+
+/// Build a script to burn the amount of vrc20 from the inputs, the charge will move to the
+/// output 0 if had.
+pub fn burn_vrc20s_with_charge(
+    name: Tag,
+    inputs: Vec<(u8, U256)>,
+    amount: U256,
+) -> Result<(Outputs, Vec<u8>)> {
+    if amount.is_zero() {
+        bail!("the burn amount should not be zero");
+    }
+
+    let input_sum = {
+        let mut sum = U256::zero();
+        for (_, amount) in inputs.iter() {
+            sum += *amount;
+        }
+
+        sum
+    };
+
+    if input_sum < amount {
+        bail!("the input {} less then burn amount {}", input_sum, amount);
+    }
+
+    let charge = input_sum - amount;
+    let outputs = if charge.is_zero() { vec![] } else { vec![0] };
+
+    let mut instructions = Vec::with_capacity(inputs.len() + 3);
+    if !outputs.is_empty() {
+        instructions.push(Instruction::Output(InstructionOutputAssert { indexs: outputs.clone() }));
+    }
+
+    for (input_index, input_amount) in inputs.into_iter() {
+        instructions.push(Instruction::Input(InstructionInputAssert {
+            index: input_index,
+            resource: Resource::VRC20(VRC20::new(name, input_amount)),
+        }));
+    }
+
+    instructions.push(Instruction::Burn(InstructionResourceBurn {
+        resource: Resource::VRC20(VRC20::new(name, amount)),
+    }));
+
+    if !charge.is_zero() {
+        instructions.push(Instruction::move_vrc20_to(0, name, charge));
+    }
+
+    let ops_bytes =
+        ScriptBuilderFromInstructions::build(instructions).context("build script failed")?;
+
+    Ok((outputs, ops_bytes))
+}
@@ -0,0 +1,38 @@
+use anyhow::{bail, Context, Result};
+use vital_script_ops::{
+    builder::instruction::ScriptBuilderFromInstructions,
+    instruction::{
+        assert_output::InstructionOutputAssert, claim_space::InstructionClaimSpace, Instruction,
+    },
+};
+use vital_script_primitives::resources::Resource;
+
+/// Build a script to claim the resources in the space by the input, which spent the first
+/// output of the tx, then move each resource to the output by its index from 0.
+pub fn claim_space(input_index: u32, resources: Vec<Resource>) -> Result<Vec<u8>> {
+    if input_index >= u8::MAX as u32 {
+        bail!("the input index not supported >= {}", u8::MAX);
+    }
+
+    if resources.len() >= u8::MAX as usize {
+        bail!("the resources count not supported >= {}", u8::MAX);
+    }
+
+    let outputs = (0..resources.len() as u8).collect::<Vec<_>>();
+
+    let mut instructions = Vec::with_capacity(resources.len() + 2);
+    instructions.push(Instruction::ClaimSpace(InstructionClaimSpace {
+        index: input_index as u8,
+        resources: resources.clone(),
+    }));
+    instructions.push(Instruction::Output(InstructionOutputAssert { indexs: outputs }));
+
+    for (output_index, resource) in resources.into_iter().enumerate() {
+        instructions.push(Instruction::move_to(output_index as u8, resource));
+    }
+
+    let ops_bytes =
+        ScriptBuilderFromInstructions::build(instructions).context("build script failed")?;
+
+    Ok(ops_bytes)
+}
@@ -5,10 +5,13 @@ use vital_script_ops::{
         assert_input::InstructionInputAssert, resource_deploy::InstructionVRC20Deploy, Instruction,
     },
 };
-use vital_script_primitives::{names::Name, resources::Resource, types::vrc20::VRC20MetaData};
+use vital_script_primitives::{
+    resources::{Resource, Tag},
+    types::vrc20::VRC20MetaData,
+};
 
 /// Build a script to mint a short name / name to a output index.
-pub fn deploy_vrc20(input_index: u32, name: Name, meta: VRC20MetaData) -> Result<Vec<u8>> {
+pub fn deploy_vrc20(input_index: u32, name: Tag, meta: VRC20MetaData) -> Result<Vec<u8>> {
     if input_index >= u8::MAX as u32 {
         bail!("the output index not supported >= {}", u8::MAX);
     }
@@ -0,0 +1,48 @@
+use anyhow::{bail, Context, Result};
+use vital_script_ops::{
+    builder::instruction::ScriptBuilderFromInstructions,
+    instruction::{
+        assert_input::InstructionInputAssert, assert_output::InstructionOutputAssert,
+        resource_deploy::InstructionVRC721Deploy, Instruction,
+    },
+};
+use vital_script_primitives::{
+    resources::{Resource, Tag},
+    types::vrc721::VRC721MetaData,
+};
+
+/// Build a script to deploy a vrc721 collection by the name from the input index, the name
+/// will be moved to the output index as the owner of the collection.
+pub fn deploy_vrc721(
+    input_index: u32,
+    output_index: u32,
+    name: Tag,
+    meta: VRC721MetaData,
+) -> Result<Vec<u8>> {
+    if input_index >= u8::MAX as u32 {
+        bail!("the input index not supported >= {}", u8::MAX);
+    }
+
+    if output_index >= u8::MAX as u32 {
+        bail!("the output index not supported >= {}", u8::MAX);
+    }
+
+    let input_index = input_index as u8;
+    let output_index = output_index as u8;
+
+    let instructions = [
+        Instruction::Input(InstructionInputAssert {
+            index: input_index,
+            resource: Resource::Name(name),
+        }),
+        Instruction::Output(InstructionOutputAssert { indexs: vec![output_index] }),
+        Instruction::DeployVRC721(InstructionVRC721Deploy { name_input: input_index, name, meta }),
+        Instruction::move_to(output_index, Resource::Name(name)),
+    ]
+    .to_vec();
+
+    let ops_bytes =
+        ScriptBuilderFromInstructions::build(instructions).context("build script failed")?;
+
+    Ok(ops_bytes)
+}
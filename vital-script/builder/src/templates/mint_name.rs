@@ -4,12 +4,26 @@ use vital_script_ops::{
     instruction::{assert_output::InstructionOutputAssert, Instruction},
 };
 use vital_script_primitives::{
-    names::{Name, ShortName, NAME_LEN_MAX, SHORT_NAME_LEN_MAX},
+    names::{LongName, Name, ShortName, LONG_NAME_LEN_MAX, NAME_LEN_MAX, SHORT_NAME_LEN_MAX},
     resources::ResourceType,
 };
 
-/// Build a script to mint a short name / name to a output index.
+/// Build a script to mint a short name / name / long name to a output index.
 pub fn mint_name(output_index: u32, name: impl Into<String>) -> Result<Vec<u8>> {
+    build_mint_name(output_index, name, Instruction::mint)
+}
+
+/// Build a script to distributed mint a short name / name to a output index,
+/// the commit tx need be confirmed before the reveal tx.
+pub fn dmint_name(output_index: u32, name: impl Into<String>) -> Result<Vec<u8>> {
+    build_mint_name(output_index, name, Instruction::dmint)
+}
+
+fn build_mint_name(
+    output_index: u32,
+    name: impl Into<String>,
+    mint: fn(u8, ResourceType) -> Instruction,
+) -> Result<Vec<u8>> {
     if output_index >= u8::MAX as u32 {
         bail!("the output index not supported >= {}", u8::MAX);
     }
@@ -22,12 +36,15 @@ pub fn mint_name(output_index: u32, name: impl Into<String>) -> Result<Vec<u8>>
     let name: String = name.into();
     let mint_instruction = if name.len() <= SHORT_NAME_LEN_MAX {
         let name = ShortName::try_from(name).context("the name format not valid")?;
-        Instruction::mint(output_index, ResourceType::name(name))
+        mint(output_index, ResourceType::name(name))
     } else if name.len() <= NAME_LEN_MAX {
         let name = Name::try_from(name).context("the name format not valid")?;
-        Instruction::mint(output_index, ResourceType::name(name))
+        mint(output_index, ResourceType::name(name))
+    } else if name.len() <= LONG_NAME_LEN_MAX {
+        let name = LongName::try_from(name).context("the name format not valid")?;
+        mint(output_index, ResourceType::name(name))
     } else {
-        bail!("not support long name with length {}, need <= {}", name.len(), NAME_LEN_MAX);
+        bail!("not support name with length {}, need <= {}", name.len(), LONG_NAME_LEN_MAX);
     };
 
     instructions.push(mint_instruction);
@@ -3,10 +3,24 @@ use vital_script_ops::{
     builder::instruction::ScriptBuilderFromInstructions,
     instruction::{assert_output::InstructionOutputAssert, Instruction},
 };
-use vital_script_primitives::{names::Name, resources::ResourceType};
+use vital_script_primitives::resources::{ResourceType, Tag};
 
 /// Build a script to mint a short name / name to a output index.
-pub fn mint_vrc20(output_index: u32, vrc20_name: Name) -> Result<Vec<u8>> {
+pub fn mint_vrc20(output_index: u32, vrc20_name: Tag) -> Result<Vec<u8>> {
+    build_mint_vrc20(output_index, vrc20_name, Instruction::mint)
+}
+
+/// Build a script to distributed mint a vrc20 to a output index,
+/// the commit tx need be confirmed before the reveal tx.
+pub fn dmint_vrc20(output_index: u32, vrc20_name: Tag) -> Result<Vec<u8>> {
+    build_mint_vrc20(output_index, vrc20_name, Instruction::dmint)
+}
+
+fn build_mint_vrc20(
+    output_index: u32,
+    vrc20_name: Tag,
+    mint: fn(u8, ResourceType) -> Instruction,
+) -> Result<Vec<u8>> {
     if output_index >= u8::MAX as u32 {
         bail!("the output index not supported >= {}", u8::MAX);
     }
@@ -15,7 +29,7 @@ pub fn mint_vrc20(output_index: u32, vrc20_name: Name) -> Result<Vec<u8>> {
 
     let instructions = [
         Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
-        Instruction::mint(output_index, ResourceType::vrc20(vrc20_name)),
+        mint(output_index, ResourceType::vrc20(vrc20_name)),
     ]
     .to_vec();
 
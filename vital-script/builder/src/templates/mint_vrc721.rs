@@ -0,0 +1,89 @@
+use anyhow::{bail, Context, Result};
+use vital_script_ops::{
+    builder::instruction::ScriptBuilderFromInstructions,
+    instruction::{
+        assert_input::InstructionInputAssert, assert_output::InstructionOutputAssert,
+        resource_mint::InstructionCollectionMint, Instruction,
+    },
+};
+use vital_script_primitives::{
+    resources::{Resource, ResourceType, Tag},
+    H256,
+};
+
+/// Build a script to mint a vrc721 by its content hash to a output index.
+pub fn mint_vrc721(output_index: u32, hash: H256) -> Result<Vec<u8>> {
+    build_mint_vrc721(output_index, hash, Instruction::mint)
+}
+
+/// Build a script to distributed mint a vrc721 by its content hash to a output index,
+/// the commit tx need be confirmed before the reveal tx.
+pub fn dmint_vrc721(output_index: u32, hash: H256) -> Result<Vec<u8>> {
+    build_mint_vrc721(output_index, hash, Instruction::dmint)
+}
+
+/// Build a script to mint a vrc721 by its content hash in the collection to a output index,
+/// the name of the collection from the input index will be moved to the owner output index.
+pub fn mint_vrc721_in_collection(
+    input_index: u32,
+    output_index: u32,
+    owner_output_index: u32,
+    collection: Tag,
+    hash: H256,
+) -> Result<Vec<u8>> {
+    if input_index >= u8::MAX as u32 {
+        bail!("the input index not supported >= {}", u8::MAX);
+    }
+
+    if output_index >= u8::MAX as u32 || owner_output_index >= u8::MAX as u32 {
+        bail!("the output index not supported >= {}", u8::MAX);
+    }
+
+    if output_index == owner_output_index {
+        bail!("the vrc721 and the name cannot be in the same output");
+    }
+
+    let (input_index, output_index, owner_output_index) =
+        (input_index as u8, output_index as u8, owner_output_index as u8);
+
+    let instructions = [
+        Instruction::Input(InstructionInputAssert {
+            index: input_index,
+            resource: Resource::Name(collection),
+        }),
+        Instruction::Output(InstructionOutputAssert {
+            indexs: vec![output_index, owner_output_index],
+        }),
+        Instruction::CollectionMint(InstructionCollectionMint { output_index, collection, hash }),
+        Instruction::move_to(owner_output_index, Resource::Name(collection)),
+    ]
+    .to_vec();
+
+    let ops_bytes =
+        ScriptBuilderFromInstructions::build(instructions).context("build script failed")?;
+
+    Ok(ops_bytes)
+}
+
+fn build_mint_vrc721(
+    output_index: u32,
+    hash: H256,
+    mint: impl FnOnce(u8, ResourceType) -> Instruction,
+) -> Result<Vec<u8>> {
+    if output_index >= u8::MAX as u32 {
+        bail!("the output index not supported >= {}", u8::MAX);
+    }
+
+    let output_index = output_index as u8;
+
+    let instructions = [
+        Instruction::Output(InstructionOutputAssert { indexs: vec![output_index] }),
+        mint(output_index, ResourceType::vrc721(hash)),
+    ]
+    .to_vec();
+
+    let ops_bytes =
+        ScriptBuilderFromInstructions::build(instructions).context("build script failed")?;
+
+    Ok(ops_bytes)
+}
@@ -2,17 +2,27 @@
 
 mod utils;
 
+mod burn;
+mod claim_space;
 mod deploy_vrc20;
+mod deploy_vrc721;
 mod mint_name;
 mod mint_vrc20;
+mod mint_vrc721;
 mod move_name;
 mod move_vrc20;
+mod move_vrc721;
 
+pub use burn::*;
+pub use claim_space::*;
 pub use deploy_vrc20::*;
+pub use deploy_vrc721::*;
 pub use mint_name::*;
 pub use mint_vrc20::*;
+pub use mint_vrc721::*;
 pub use move_name::*;
 pub use move_vrc20::*;
+pub use move_vrc721::*;
 
 /// The outputs used by template
 pub type Outputs = Vec<u8>;
@@ -5,10 +5,10 @@ use vital_script_ops::{
         assert_input::InstructionInputAssert, assert_output::InstructionOutputAssert, Instruction,
     },
 };
-use vital_script_primitives::names::Name;
+use vital_script_primitives::resources::Tag;
 
 /// Build a script to move a short name / name to a output index.
-pub fn move_names_with_index(output_index_name: &[(u32, Name)]) -> Result<Vec<u8>> {
+pub fn move_names_with_index(output_index_name: &[(u32, Tag)]) -> Result<Vec<u8>> {
     let outputs = output_index_name
         .iter()
         .map(|(output_index, _)| {
@@ -54,7 +54,7 @@ pub fn move_names_with_index(output_index_name: &[(u32, Name)]) -> Result<Vec<u8
 }
 
 /// Build a script to move a short name / name to output index from 0.
-pub fn move_names(names: &[Name], start_output_index: Option<u32>) -> Result<Vec<u8>> {
+pub fn move_names(names: &[Tag], start_output_index: Option<u32>) -> Result<Vec<u8>> {
     let start_output_index = start_output_index.unwrap_or_default();
 
     let outputs = (0..names.len())
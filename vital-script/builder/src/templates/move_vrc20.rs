@@ -8,15 +8,14 @@ use vital_script_ops::{
     },
 };
 use vital_script_primitives::{
-    names::Name,
-    resources::{Resource, VRC20},
+    resources::{Resource, Tag, VRC20},
     U256,
 };
 
 use super::Outputs;
 
 pub fn move_vrc20_with_charge(
-    name: Name,
+    name: Tag,
     input_index: u8,
     in_amount: U256,
     to_amount: U256,
@@ -45,7 +44,7 @@ pub fn move_vrc20_with_charge(
 }
 
 pub fn move_vrc20s_with_charge(
-    name: Name,
+    name: Tag,
     inputs: Vec<(u8, U256)>,
     to_amount: U256,
 ) -> Result<(Outputs, Vec<u8>)> {
@@ -84,7 +83,7 @@ pub fn move_vrc20s_with_charge(
     Ok((outputs, res))
 }
 
-pub fn merge_vrc20s(name: Name, inputs: Vec<(u8, U256)>) -> Result<Vec<u8>> {
+pub fn merge_vrc20s(name: Tag, inputs: Vec<(u8, U256)>) -> Result<Vec<u8>> {
     let to_amount = {
         let mut sum = U256::zero();
         for (_, amount) in inputs.iter() {
@@ -105,7 +104,7 @@ pub fn merge_vrc20s(name: Name, inputs: Vec<(u8, U256)>) -> Result<Vec<u8>> {
     builder.build()
 }
 
-pub fn split_vrc20s(name: Name, input_index: u8, outputs: Vec<U256>) -> Result<Vec<u8>> {
+pub fn split_vrc20s(name: Tag, input_index: u8, outputs: Vec<U256>) -> Result<Vec<u8>> {
     let input_amount = {
         let mut sum = U256::zero();
         for amount in outputs.iter() {
@@ -134,8 +133,8 @@ pub fn split_vrc20s(name: Name, input_index: u8, outputs: Vec<U256>) -> Result<V
 
 #[derive(Default)]
 pub struct MoveVrc20InstructionBuilder {
-    inputs: Vec<(u8, Name, U256)>,
-    outputs: Vec<(u8, Name, U256)>,
+    inputs: Vec<(u8, Tag, U256)>,
+    outputs: Vec<(u8, Tag, U256)>,
 }
 
 impl MoveVrc20InstructionBuilder {
@@ -147,11 +146,11 @@ impl MoveVrc20InstructionBuilder {
         self.merged_inputs() == self.merged_outputs()
     }
 
-    pub fn append_input(&mut self, input_index: u8, name: Name, amount: U256) {
+    pub fn append_input(&mut self, input_index: u8, name: Tag, amount: U256) {
         self.inputs.push((input_index, name, amount))
     }
 
-    pub fn append_output(&mut self, output_index: u8, name: Name, amount: U256) {
+    pub fn append_output(&mut self, output_index: u8, name: Tag, amount: U256) {
         self.outputs.push((output_index, name, amount))
     }
 
@@ -190,7 +189,7 @@ impl MoveVrc20InstructionBuilder {
         Ok(ops_bytes)
     }
 
-    fn merged_inputs(&self) -> Vec<(Name, U256)> {
+    fn merged_inputs(&self) -> Vec<(Tag, U256)> {
         let mut inputs_map = BTreeMap::new();
 
         for (_, name, amount) in self.inputs.iter() {
@@ -210,7 +209,7 @@ impl MoveVrc20InstructionBuilder {
         res
     }
 
-    fn merged_outputs(&self) -> Vec<(Name, U256)> {
+    fn merged_outputs(&self) -> Vec<(Tag, U256)> {
         let mut outputs_map = BTreeMap::new();
 
         for (_, name, amount) in self.outputs.iter() {
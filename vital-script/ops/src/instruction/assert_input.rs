@@ -12,7 +12,11 @@ use vital_script_primitives::{
 
 use crate::{
     instruction::utils::*,
-    op_basic::{BasicOpcodeBase, InputAssertName, InputAssertShortName, InputVRC721Assert},
+    op_basic::{
+        BasicOpcodeBase, InputAssertLongName, InputAssertName, InputAssertShortName,
+        InputVRC721Assert,
+    },
+    op_extension::{ExtensionOpcode, InputLongVRC20Assert},
     TARGET,
 };
 
@@ -82,9 +86,15 @@ impl InstructionInputAssert {
                 InputAssertShortName { name: n.try_into().expect("should ok"), index }.encode(),
             )
         } else if name_len <= NAME_LEN_MAX {
-            (<InputAssertName as BasicOpcodeBase>::ID, InputAssertName { name: n, index }.encode())
+            (
+                <InputAssertName as BasicOpcodeBase>::ID,
+                InputAssertName { name: n.try_into().expect("should ok"), index }.encode(),
+            )
         } else {
-            bail!("not support long name")
+            (
+                <InputAssertLongName as BasicOpcodeBase>::ID,
+                InputAssertLongName { name: n, index }.encode(),
+            )
         };
 
         let mut bytes = Vec::with_capacity(4 + res.len());
@@ -95,7 +105,11 @@ impl InstructionInputAssert {
     }
 
     fn into_input_vrc20(v: VRC20, index: u8) -> Result<Vec<u8>> {
-        Vrc20ResourceOperand::new(v).into_input_vrc20_opcode_bytes(index)
+        if v.name.is_long() {
+            return Ok(InputLongVRC20Assert { amount: v.amount, name: v.name, index }.encode_op());
+        }
+
+        Vrc20ResourceOperand::new(v)?.into_input_vrc20_opcode_bytes(index)
     }
 
     fn into_input_vrc721(v: VRC721, index: u8) -> Result<Vec<u8>> {
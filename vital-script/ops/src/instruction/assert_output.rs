@@ -4,8 +4,9 @@ use alloc::vec::Vec;
 use anyhow::{bail, Result};
 use vital_script_primitives::traits::*;
 
-use crate::op_basic::{
-    BasicOpcode, OutputIndexAssert, OutputIndexFlag16Assert, OutputIndexFlag32Assert,
+use crate::{
+    op_basic::{BasicOpcode, OutputIndexAssert, OutputIndexFlag16Assert, OutputIndexFlag32Assert},
+    op_extension::{ExtensionOpcode, OutputIndexFlag64Assert},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -71,13 +72,40 @@ impl Instruction for InstructionOutputAssert {
             return Ok(op.encode_op());
         }
 
-        todo!("not support output index >= 32")
+        let is_all_less_than_64 = self.indexs.iter().all(|a| *a < 64_u8);
+        if is_all_less_than_64 {
+            let mut mask = 0_u64;
+            for i in self.indexs {
+                let m = 1_u64 << i;
+                mask |= m;
+            }
+
+            let op = OutputIndexFlag64Assert { index_flag: mask.to_le_bytes() };
+
+            return Ok(op.encode_op());
+        }
+
+        // the index >= 64 can only be asserted one by one.
+        let (lows, highs): (Vec<u8>, Vec<u8>) = self.indexs.into_iter().partition(|a| *a < 64_u8);
+
+        let mut res =
+            if lows.is_empty() { Vec::new() } else { Self { indexs: lows }.into_ops_bytes()? };
+
+        for index in highs {
+            res.append(&mut OutputIndexAssert { index }.encode_op());
+        }
+
+        Ok(res)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::opcodes::BasicOp;
+    use crate::{
+        instruction::Instruction as Ins,
+        opcodes::{BasicOp, ExtensionOp},
+        parser::Parser,
+    };
     use vital_script_runner::mock::*;
 
     use super::*;
@@ -177,4 +205,123 @@ mod tests {
 
         Ok(())
     }
+
+    fn output_index_flag64_assert_ops_bytes(indexs: Vec<u8>, o: [u8; 8]) {
+        let output = InstructionOutputAssert { indexs: indexs.clone() };
+
+        let bytes = output
+            .into_ops_bytes()
+            .unwrap_or_else(|_| panic!("into should ok for {:?}", indexs));
+
+        let op = (ExtensionOp::OutputIndexFlag64Assert as u16).to_be_bytes();
+        assert_eq!(bytes.len(), 2 + 8);
+        assert_eq!(bytes, [op.to_vec(), o.to_vec()].concat(), "assert {:?}", indexs);
+    }
+
+    #[test]
+    fn test_output_64_into_ops_bytes() -> Result<()> {
+        output_index_flag64_assert_ops_bytes(
+            vec![0, 32],
+            [0b00000001, 0, 0, 0, 0b00000001, 0, 0, 0],
+        );
+        output_index_flag64_assert_ops_bytes(
+            vec![31, 63],
+            [0, 0, 0, 0b10000000, 0, 0, 0, 0b10000000],
+        );
+        output_index_flag64_assert_ops_bytes((0..64).collect(), [0b11111111; 8]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_more_than_64_into_ops_bytes() -> Result<()> {
+        let bytes = InstructionOutputAssert { indexs: vec![64, 255] }.into_ops_bytes()?;
+        assert_eq!(
+            bytes,
+            vec![BasicOp::OutputIndexAssert as u8, 64, BasicOp::OutputIndexAssert as u8, 255]
+        );
+
+        // the indexs < 64 will use the flag ops.
+        let bytes = InstructionOutputAssert { indexs: vec![100, 1, 2] }.into_ops_bytes()?;
+        assert_eq!(
+            bytes,
+            vec![
+                BasicOp::OutputIndexFlag16Assert as u8,
+                0b00000110,
+                0,
+                BasicOp::OutputIndexAssert as u8,
+                100
+            ]
+        );
+
+        let bytes = InstructionOutputAssert { indexs: vec![5, 70] }.into_ops_bytes()?;
+        assert_eq!(
+            bytes,
+            vec![BasicOp::OutputIndexAssert as u8, 5, BasicOp::OutputIndexAssert as u8, 70]
+        );
+
+        Ok(())
+    }
+
+    fn round_trip(indexs: Vec<u8>) {
+        let bytes = InstructionOutputAssert { indexs: indexs.clone() }
+            .into_ops_bytes()
+            .unwrap_or_else(|_| panic!("into should ok for {:?}", indexs));
+
+        let mut parsed = Parser::new(&bytes)
+            .parse()
+            .unwrap_or_else(|_| panic!("parse should ok for {:?}", indexs))
+            .into_iter()
+            .flat_map(|ins| match ins {
+                Ins::Output(output) => output.indexs,
+                _ => panic!("should be output assert for {:?}", indexs),
+            })
+            .collect::<Vec<_>>();
+        parsed.sort();
+
+        let mut expected = indexs;
+        expected.sort();
+
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_output_assert_round_trip() {
+        // single
+        round_trip(vec![0]);
+        round_trip(vec![255]);
+        // flag16
+        round_trip(vec![0, 15]);
+        round_trip((0..16).collect());
+        // flag32
+        round_trip(vec![3, 16, 31]);
+        round_trip((0..32).collect());
+        // flag64
+        round_trip(vec![0, 32, 63]);
+        round_trip((0..64).collect());
+        // the indexs >= 64 one by one
+        round_trip(vec![64, 65]);
+        round_trip((64..=255).collect());
+        // mixed
+        round_trip(vec![7, 200]);
+        round_trip(vec![1, 2, 254]);
+        round_trip(vec![1, 20, 64, 128]);
+        round_trip(vec![1, 40, 63, 64, 255]);
+        round_trip((0..=255).collect());
+    }
+
+    #[test]
+    fn test_output_assert_more_than_32_outputs() -> Result<()> {
+        let env_interface = EnvMock::new();
+
+        let indexs = [0_u8, 31, 32, 63, 64, 99].to_vec();
+        let ops_bytes = InstructionOutputAssert { indexs: indexs.clone() }.into_ops_bytes()?;
+        let res = TestCtx::new(&env_interface)
+            .with_ops_bytes(&ops_bytes)
+            .with_outputs(100, 1000)
+            .run();
+        assert!(res.is_ok(), "assert outputs {:?} should ok", indexs);
+
+        Ok(())
+    }
 }
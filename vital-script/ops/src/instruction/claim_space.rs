@@ -0,0 +1,74 @@
+//! The Claim Space instruction
+//!
+//! The input resources which not costed by a tx will be stored into its space, the tx
+//! which spend the first output of it can claim them, then use them as the input resources.
+
+use alloc::vec::Vec;
+use anyhow::{bail, Context as AnyhowContext, Result};
+use vital_script_primitives::{consts::MAX_INPUT_INDEX, resources::Resource, traits::*};
+
+use crate::{
+    op_extension::{ClaimSpace, ExtensionOpcode},
+    TARGET,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionClaimSpace {
+    pub index: u8,
+    pub resources: Vec<Resource>,
+}
+
+impl core::fmt::Display for InstructionClaimSpace {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "ClaimSpace:({}, [", self.index)?;
+        for (i, resource) in self.resources.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", resource)?;
+        }
+        write!(f, "])")
+    }
+}
+
+impl Instruction for InstructionClaimSpace {
+    fn pre_check(&self) -> Result<()> {
+        if self.index > MAX_INPUT_INDEX {
+            bail!("index too large")
+        }
+
+        if self.resources.is_empty() {
+            bail!("no resources to claim")
+        }
+
+        Ok(())
+    }
+
+    fn exec(&self, context: &mut impl Context) -> Result<()> {
+        // Note the input not need assert, it can also have a resource bind to it.
+        if context.run_mod().is_skip_check() {
+            log::debug!(target: TARGET, "skip claim space check by in sim mode")
+        } else {
+            let claimed =
+                context.env_mut().claim_space_resources(self.index).context("claim space")?;
+
+            if claimed != self.resources {
+                log::debug!(target: TARGET, "space resources {:?} expect {:?}", claimed, self.resources);
+                bail!("the space resources not expected")
+            }
+        }
+
+        for resource in self.resources.iter() {
+            context
+                .input_resource_mut()
+                .push_claimed(self.index, resource.clone())
+                .context("push claimed resource")?;
+        }
+
+        Ok(())
+    }
+
+    fn into_ops_bytes(self) -> Result<Vec<u8>> {
+        Ok(ClaimSpace { resources: self.resources, index: self.index }.encode_op())
+    }
+}
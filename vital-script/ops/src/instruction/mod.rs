@@ -9,11 +9,14 @@ mod utils;
 
 pub mod assert_input;
 pub mod assert_output;
+pub mod claim_space;
 pub mod resource_burn;
 pub mod resource_deploy;
+pub mod resource_dmint;
 pub mod resource_mint;
 pub mod resource_move;
 
+pub use resource_dmint::*;
 pub use resource_mint::*;
 
 use vital_script_primitives::{
@@ -29,10 +32,14 @@ pub enum Instruction {
     Input(assert_input::InstructionInputAssert),
     Output(assert_output::InstructionOutputAssert),
     Mint(resource_mint::InstructionResourceMint),
+    DMint(resource_dmint::InstructionResourceDMint),
     Deploy(resource_deploy::InstructionVRC20Deploy),
+    DeployVRC721(resource_deploy::InstructionVRC721Deploy),
+    CollectionMint(resource_mint::InstructionCollectionMint),
     Move(resource_move::InstructionResourceMove),
     MoveAll(resource_move::InstructionResourceMoveAll),
     Burn(resource_burn::InstructionResourceBurn),
+    ClaimSpace(claim_space::InstructionClaimSpace),
 }
 
 impl core::fmt::Display for Instruction {
@@ -41,10 +48,14 @@ impl core::fmt::Display for Instruction {
             Self::Input(i) => i.fmt(f),
             Self::Output(i) => i.fmt(f),
             Self::Mint(i) => i.fmt(f),
+            Self::DMint(i) => i.fmt(f),
             Self::Deploy(i) => i.fmt(f),
+            Self::DeployVRC721(i) => i.fmt(f),
+            Self::CollectionMint(i) => i.fmt(f),
             Self::Move(i) => i.fmt(f),
             Self::MoveAll(i) => i.fmt(f),
             Self::Burn(i) => i.fmt(f),
+            Self::ClaimSpace(i) => i.fmt(f),
         }
     }
 }
@@ -55,10 +66,14 @@ impl InstructionT for Instruction {
             Self::Input(i) => i.pre_check(),
             Self::Output(i) => i.pre_check(),
             Self::Mint(i) => i.pre_check(),
+            Self::DMint(i) => i.pre_check(),
             Self::Deploy(i) => i.pre_check(),
+            Self::DeployVRC721(i) => i.pre_check(),
+            Self::CollectionMint(i) => i.pre_check(),
             Self::Move(i) => i.pre_check(),
             Self::MoveAll(i) => i.pre_check(),
             Self::Burn(i) => i.pre_check(),
+            Self::ClaimSpace(i) => i.pre_check(),
         }
     }
 
@@ -67,10 +82,14 @@ impl InstructionT for Instruction {
             Self::Input(i) => i.exec(context),
             Self::Output(i) => i.exec(context),
             Self::Mint(i) => i.exec(context),
+            Self::DMint(i) => i.exec(context),
             Self::Deploy(i) => i.exec(context),
+            Self::DeployVRC721(i) => i.exec(context),
+            Self::CollectionMint(i) => i.exec(context),
             Self::Move(i) => i.exec(context),
             Self::MoveAll(i) => i.exec(context),
             Self::Burn(i) => i.exec(context),
+            Self::ClaimSpace(i) => i.exec(context),
         }
     }
 
@@ -79,10 +98,14 @@ impl InstructionT for Instruction {
             Self::Input(i) => i.into_ops_bytes(),
             Self::Output(i) => i.into_ops_bytes(),
             Self::Mint(i) => i.into_ops_bytes(),
+            Self::DMint(i) => i.into_ops_bytes(),
             Self::Deploy(i) => i.into_ops_bytes(),
+            Self::DeployVRC721(i) => i.into_ops_bytes(),
+            Self::CollectionMint(i) => i.into_ops_bytes(),
             Self::Move(i) => i.into_ops_bytes(),
             Self::MoveAll(i) => i.into_ops_bytes(),
             Self::Burn(i) => i.into_ops_bytes(),
+            Self::ClaimSpace(i) => i.into_ops_bytes(),
         }
     }
 }
@@ -92,6 +115,10 @@ impl Instruction {
         Self::Mint(InstructionResourceMint::new(index, resource_type))
     }
 
+    pub fn dmint(index: u8, resource_type: ResourceType) -> Self {
+        Self::DMint(InstructionResourceDMint::new(index, resource_type))
+    }
+
     pub fn move_to(index: u8, resource: impl Into<Resource>) -> Self {
         Self::Move(InstructionResourceMove::new(index, resource))
     }
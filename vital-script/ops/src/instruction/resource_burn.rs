@@ -28,6 +28,14 @@ impl Instruction for InstructionResourceBurn {
             .cost(&self.resource)
             .context("cost resource failed")?;
 
+        if let Resource::VRC20(v) = &self.resource {
+            // for vrc20, we need record the burned amount for the supply
+            context
+                .env_mut()
+                .increase_vrc20_burned(v.name, v.amount)
+                .context("increase burned failed")?;
+        }
+
         Ok(())
     }
 
@@ -42,7 +50,10 @@ impl Instruction for InstructionResourceBurn {
 mod tests {
     use anyhow::Result;
 
-    use vital_script_primitives::resources::{Name, Resource};
+    use vital_script_primitives::{
+        resources::{Name, Resource, Tag},
+        traits::{EnvContext, MetaDataType},
+    };
     use vital_script_runner::mock::*;
 
     use vital_script_ops::instruction::{
@@ -161,4 +172,88 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_burn_vrc20_should_record_burned() -> Result<()> {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.deploy_vrc20("abcce", 10000);
+        let outpoint01 = ctx.mint_vrc20("abcce");
+        let outpoint02 = ctx.mint_vrc20("abcce");
+
+        let name = Tag::try_from("abcce")?;
+        let query = vital_script_runner::EnvContext::new_for_query(
+            env_interface.clone(),
+            MOCK_BLOCK_HEIGHT,
+        );
+
+        let status = query.get_vrc20_metadata(name)?.expect("deployed");
+        assert_eq!(status.minted(), 20000.into());
+        assert_eq!(query.get_vrc20_burned(name)?, 0.into());
+
+        TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Input(InstructionInputAssert {
+                    index: 1,
+                    resource: Resource::vrc20("abcce", 10000.into())?,
+                }),
+                Instruction::Burn(InstructionResourceBurn {
+                    resource: Resource::vrc20("abcce", 3000.into())?,
+                }),
+            ])
+            .with_ops()
+            .with_input(outpoint01)
+            .with_output(2001)
+            .run()
+            .expect("should ok");
+
+        TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Input(InstructionInputAssert {
+                    index: 1,
+                    resource: Resource::vrc20("abcce", 10000.into())?,
+                }),
+                Instruction::Burn(InstructionResourceBurn {
+                    resource: Resource::vrc20("abcce", 10000.into())?,
+                }),
+            ])
+            .with_ops()
+            .with_input(outpoint02)
+            .with_output(2002)
+            .run()
+            .expect("should ok");
+
+        let status = query.get_vrc20_metadata(name)?.expect("deployed");
+        assert_eq!(status.minted(), 20000.into());
+        assert_eq!(query.get_vrc20_burned(name)?, 13000.into());
+        assert_eq!(status.circulating(13000.into()), 7000.into());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_vrc20_status_stored_before_burn() -> Result<()> {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.deploy_vrc20("abcce", 10000);
+
+        let name = Tag::try_from("abcce")?;
+        let mut query = vital_script_runner::EnvContext::new_for_query(
+            env_interface.clone(),
+            MOCK_BLOCK_HEIGHT,
+        );
+        let meta = query.get_vrc20_metadata(name)?.expect("deployed").meta;
+
+        // the status record stored before burn supported, which is `(mint_count, meta)`.
+        query.set_metadata(name, MetaDataType::VRC20, (5_u64, meta.clone()))?;
+
+        let status = query.get_vrc20_metadata(name)?.expect("should decode the old record");
+        assert_eq!(status.mint_count, 5);
+        assert_eq!(status.meta, meta);
+        assert_eq!(query.get_vrc20_burned(name)?, 0.into());
+
+        Ok(())
+    }
 }
@@ -3,14 +3,20 @@
 use alloc::vec::Vec;
 use anyhow::{bail, Context as AnyhowContext, Result};
 use vital_script_primitives::{
-    consts::MAX_INPUT_INDEX,
+    consts::{MAX_INPUT_INDEX, PROTOCOL_VERSION_V2},
     names::{NAME_LEN_MAX, SHORT_NAME_LEN_MAX},
     resources::{Resource, Tag},
     traits::*,
-    types::vrc20::VRC20MetaData,
+    types::{
+        bitwork::Bitwork,
+        vrc20::VRC20MetaData,
+        vrc721::{VRC721MetaData, ROYALTY_BASE},
+    },
 };
 
-use crate::op_extension::{DeployVRC20, DeployVRC20S, ExtensionOpcode};
+use crate::op_extension::{
+    DeployLongVRC20, DeployVRC20, DeployVRC20S, DeployVRC721, ExtensionOpcode,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InstructionVRC20Deploy {
@@ -43,6 +49,11 @@ impl Instruction for InstructionVRC20Deploy {
     }
 
     fn exec(&self, context: &mut impl Context) -> Result<()> {
+        // the bworkc is checked since the protocol version 2, it is any value before.
+        if context.protocol_version() >= PROTOCOL_VERSION_V2 {
+            Bitwork::from_bworkc(self.meta.bworkc).context("Invalid bworkc")?;
+        }
+
         // cost the name, check if the vrc20 had deployed.
         let metadata = context.env().get_vrc20_metadata(self.name).context("get vrc20 metadata")?;
         if metadata.is_some() {
@@ -79,29 +90,115 @@ impl Instruction for InstructionVRC20Deploy {
             }
             .encode_op()
         } else if name_len <= NAME_LEN_MAX {
-            DeployVRC20 { name_input: self.name_input, name: self.name, meta: self.meta }
-                .encode_op()
+            DeployVRC20 {
+                name_input: self.name_input,
+                name: self.name.try_into()?,
+                meta: self.meta,
+            }
+            .encode_op()
         } else {
-            bail!("not support long name")
+            DeployLongVRC20 { name_input: self.name_input, name: self.name, meta: self.meta }
+                .encode_op()
         };
 
         Ok(res)
     }
 }
 
+/// Deploy a vrc721 collection by a name, like the vrc20, the name will be costed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionVRC721Deploy {
+    pub name_input: u8,
+    pub name: Tag,
+    pub meta: VRC721MetaData,
+}
+
+impl core::fmt::Display for InstructionVRC721Deploy {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "VRC721Deploy:({}, {}, {:?})", self.name_input, self.name, self.meta)
+    }
+}
+
+impl Instruction for InstructionVRC721Deploy {
+    fn pre_check(&self) -> Result<()> {
+        if self.name_input > MAX_INPUT_INDEX {
+            bail!("name input too large")
+        }
+
+        if !self.name.is_valid() {
+            bail!("Invalid name format");
+        }
+
+        if self.name.is_empty() {
+            bail!("Invalid name by empty");
+        }
+
+        if self.meta.max_supply == 0 {
+            bail!("Invalid max supply by zero");
+        }
+
+        if self.meta.royalty > ROYALTY_BASE {
+            bail!("Invalid royalty more than {}", ROYALTY_BASE);
+        }
+
+        Ok(())
+    }
+
+    fn exec(&self, context: &mut impl Context) -> Result<()> {
+        let metadata =
+            context.env().get_vrc721_metadata(self.name).context("get vrc721 metadata")?;
+        if metadata.is_some() {
+            bail!("the vrc721 collection had deployed");
+        }
+
+        let name_resource =
+            context.env().get_input_resource(self.name_input).context("get resource")?;
+        if !matches!(name_resource, Resource::Name(n) if n == self.name) {
+            bail!("the name input is invalid");
+        }
+        if !context.input_resource().all().contains(&self.name_input) {
+            bail!("the name input is not asserted");
+        }
+
+        // the name resource is not costed, it is kept as the owner of the collection, only the
+        // one holds the name can mint in the collection.
+        // a name can only be costed once, so it cannot be both a vrc20 and a collection.
+        context.env_mut().cost_name(self.name).context("cost name")?;
+
+        context
+            .env_mut()
+            .deploy_vrc721(self.name, self.meta.clone())
+            .context("deploy")?;
+
+        Ok(())
+    }
+
+    fn into_ops_bytes(self) -> Result<Vec<u8>> {
+        Ok(DeployVRC721 { name_input: self.name_input, name: self.name, meta: self.meta }
+            .encode_op())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
 
     use vital_script_primitives::{
+        consts::{PROTOCOL_VERSION_V1, PROTOCOL_VERSION_V2},
         resources::{Name, Resource},
-        types::vrc20::{VRC20MetaData, VRC20MintMeta},
+        traits::{Context, EnvContext},
+        types::{
+            vrc20::{VRC20MetaData, VRC20MintMeta},
+            vrc721::{VRC721MetaData, VRC721StatusData},
+        },
     };
-    use vital_script_runner::mock::*;
+    use vital_script_runner::{mock::*, traits::EnvFunctions};
 
     use vital_script_ops::instruction::{
-        assert_input::InstructionInputAssert, assert_output::InstructionOutputAssert,
-        resource_deploy::InstructionVRC20Deploy, Instruction,
+        assert_input::InstructionInputAssert,
+        assert_output::InstructionOutputAssert,
+        resource_deploy::{InstructionVRC20Deploy, InstructionVRC721Deploy},
+        Instruction,
     };
 
     #[test]
@@ -126,7 +223,7 @@ mod tests {
         let res = TestCtx::new(&env_interface)
             .with_instructions(vec![Instruction::Deploy(InstructionVRC20Deploy {
                 name_input: 1,
-                name: name1,
+                name: name1.into(),
                 meta: VRC20MetaData {
                     decimals: 5,
                     nonce: 1000000,
@@ -153,7 +250,7 @@ mod tests {
                 }),
                 Instruction::Deploy(InstructionVRC20Deploy {
                     name_input: 1,
-                    name: name1,
+                    name: name1.into(),
                     meta: VRC20MetaData {
                         decimals: 5,
                         nonce: 1000000,
@@ -186,7 +283,7 @@ mod tests {
                 }),
                 Instruction::Deploy(InstructionVRC20Deploy {
                     name_input: 1,
-                    name: name1,
+                    name: name1.into(),
                     meta: VRC20MetaData {
                         decimals: 5,
                         nonce: 1000000,
@@ -239,7 +336,7 @@ mod tests {
                 Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
                 Instruction::Deploy(InstructionVRC20Deploy {
                     name_input: 1,
-                    name: name1,
+                    name: name1.into(),
                     meta: VRC20MetaData {
                         decimals: 5,
                         nonce: 1000000,
@@ -261,7 +358,7 @@ mod tests {
 
         assert_err_str(res, "had already costed", "not use a name resource will failed");
 
-        // 2. if had deployed, will error, note in mock env, the metadata will set even the tx run failed
+        // 2. the failed tx will not change the env, so the deploy can be success.
         let res = TestCtx::new(&env_interface)
             .with_instructions(vec![
                 Instruction::Input(InstructionInputAssert {
@@ -270,7 +367,7 @@ mod tests {
                 }),
                 Instruction::Deploy(InstructionVRC20Deploy {
                     name_input: 1,
-                    name: name1,
+                    name: name1.into(),
                     meta: VRC20MetaData {
                         decimals: 5,
                         nonce: 1000000,
@@ -289,9 +386,39 @@ mod tests {
             .with_output(1000)
             .run();
 
-        assert_err_str(res, "the vrc20 had deployed", "not use a name resource will failed");
+        assert!(res.is_ok(), "the failed deploy should not set the metadata");
 
-        // 3. if the name had costed, will failed
+        // 3. if had deployed, will error
+        let res = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Input(InstructionInputAssert {
+                    index: 1,
+                    resource: name_res2.clone(),
+                }),
+                Instruction::Deploy(InstructionVRC20Deploy {
+                    name_input: 1,
+                    name: name1.into(),
+                    meta: VRC20MetaData {
+                        decimals: 5,
+                        nonce: 1000000,
+                        bworkc: 1000000,
+                        mint: VRC20MintMeta {
+                            mint_amount: 1000,
+                            mint_height: 0,
+                            max_mints: 100000000,
+                        },
+                        meta: None,
+                    },
+                }),
+            ])
+            .with_ops()
+            .with_input(outpoint02)
+            .with_output(1000)
+            .run();
+
+        assert_err_str(res, "the vrc20 had deployed", "deploy a vrc20 twice will failed");
+
+        // 4. if the name had costed, will failed
         let res = TestCtx::new(&env_interface)
             .with_instructions(vec![
                 Instruction::Input(InstructionInputAssert {
@@ -302,7 +429,7 @@ mod tests {
                 Instruction::move_to(0, name_res2),
                 Instruction::Deploy(InstructionVRC20Deploy {
                     name_input: 1,
-                    name: name2,
+                    name: name2.into(),
                     meta: VRC20MetaData {
                         decimals: 5,
                         nonce: 1000000,
@@ -325,4 +452,170 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_deploy_invalid_bworkc_by_protocol_version() -> Result<()> {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.mint_name("abcde");
+
+        let outpoint01 = ctx.get_name_outpoint("abcde").expect("should exist");
+
+        let name1 = Name::must_from("abcde");
+        let name_res1 = Resource::name(name1);
+
+        // the bitwork prefix len is 15, too long to mine.
+        let deploy = |protocol_version: u32| {
+            TestCtx::new(&env_interface)
+                .with_instructions(vec![
+                    Instruction::Input(InstructionInputAssert {
+                        index: 1,
+                        resource: name_res1.clone(),
+                    }),
+                    Instruction::Deploy(InstructionVRC20Deploy {
+                        name_input: 1,
+                        name: name1.into(),
+                        meta: VRC20MetaData {
+                            decimals: 5,
+                            nonce: 1000000,
+                            bworkc: 0x0f000000_00000000,
+                            mint: VRC20MintMeta {
+                                mint_amount: 1000,
+                                mint_height: 0,
+                                max_mints: 100000000,
+                            },
+                            meta: None,
+                        },
+                    }),
+                ])
+                .with_ops()
+                .with_input(outpoint01)
+                .with_output(1000)
+                .with_protocol_version(protocol_version)
+                .run()
+        };
+
+        // 1. since the protocol version 2, the invalid bworkc will failed
+        let res = deploy(PROTOCOL_VERSION_V2);
+        assert_err_str(res, "the bitwork prefix too long", "the invalid bworkc will failed");
+
+        // 2. the bworkc not checked before, so the deploys keep valid
+        deploy(PROTOCOL_VERSION_V1)?;
+
+        let status = vital_script_runner::EnvContext::new_for_query(
+            env_interface.clone(),
+            MOCK_BLOCK_HEIGHT,
+        )
+        .get_vrc20_metadata(name1.into())?
+        .expect("should deployed");
+        assert_eq!(status.meta.bworkc, 0x0f000000_00000000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deploy_vrc721_should_costed_the_name() -> Result<()> {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.mint_name("abcde");
+        ctx.mint_name("abe");
+
+        let outpoint01 = ctx.get_name_outpoint("abcde").expect("should exist");
+        let outpoint02 = ctx.get_name_outpoint("abe").expect("should exist");
+
+        let name1 = Name::must_from("abcde");
+        let meta = VRC721MetaData { max_supply: 100, royalty: 500, meta: None };
+
+        let deploy = |name: Name, outpoint, meta: VRC721MetaData| {
+            TestCtx::new(&env_interface)
+                .with_instructions(vec![
+                    Instruction::Input(InstructionInputAssert {
+                        index: 1,
+                        resource: Resource::name(name),
+                    }),
+                    Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                    Instruction::DeployVRC721(InstructionVRC721Deploy {
+                        name_input: 1,
+                        name: name.into(),
+                        meta,
+                    }),
+                    Instruction::move_to(0, Resource::name(name)),
+                ])
+                .with_ops()
+                .with_input(outpoint)
+                .with_output(1000)
+                .run()
+        };
+
+        // 1. the meta should be valid.
+        let invalid = VRC721MetaData { max_supply: 0, ..meta.clone() };
+        assert_err_str(deploy(name1, outpoint01, invalid), "Invalid max supply by zero", "zero");
+
+        let invalid = VRC721MetaData { royalty: 10001, ..meta.clone() };
+        assert_err_str(
+            deploy(name1, outpoint01, invalid),
+            "Invalid royalty more than 10000",
+            "royalty too large",
+        );
+
+        // 2. deploy will cost the name and set the metadata, the name resource is kept as the
+        //    owner of the collection.
+        let context = deploy(name1, outpoint01, meta.clone())?;
+        let status = context.env().get_vrc721_metadata(name1.into())?.expect("should deployed");
+        assert_eq!(status, VRC721StatusData { mint_count: 0, meta: meta.clone() });
+        assert_eq!(env_interface.get_resources(&outpoint01)?, None);
+        assert_eq!(
+            env_interface.get_resources(&context.env().get_output(0))?,
+            Some(Resource::name(name1))
+        );
+
+        // the name kept can not be used to deploy a vrc20.
+        let res = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Input(InstructionInputAssert {
+                    index: 1,
+                    resource: Resource::name(name1),
+                }),
+                Instruction::Deploy(InstructionVRC20Deploy {
+                    name_input: 1,
+                    name: name1.into(),
+                    meta: VRC20MetaData {
+                        decimals: 5,
+                        nonce: 1000000,
+                        bworkc: 1000000,
+                        mint: VRC20MintMeta { mint_amount: 10000, mint_height: 0, max_mints: 100 },
+                        meta: None,
+                    },
+                }),
+            ])
+            .with_ops()
+            .with_input(context.env().get_output(0))
+            .with_output(1001)
+            .run();
+        assert_err_str(res, "the name had costed", "deploy vrc20 by the collection name");
+
+        // 3. the collection cannot be deployed twice.
+        let res = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Input(InstructionInputAssert {
+                    index: 1,
+                    resource: Resource::name(Name::must_from("abe")),
+                }),
+                Instruction::DeployVRC721(InstructionVRC721Deploy {
+                    name_input: 1,
+                    name: name1.into(),
+                    meta,
+                }),
+            ])
+            .with_ops()
+            .with_input(outpoint02)
+            .with_output(1000)
+            .run();
+
+        assert_err_str(res, "the vrc721 collection had deployed", "deploy twice");
+
+        Ok(())
+    }
 }
@@ -0,0 +1,210 @@
+//! The Resource Distributed Mint instruction
+//!
+//! A distributed mint is the same as a mint, but its commit tx should be confirmed in
+//! a block before the reveal tx 's block, so the one who commit first will got the resource,
+//! others cannot front-run it by a higher fee reveal tx after see it in mempool.
+
+use alloc::vec::Vec;
+use anyhow::{anyhow, bail, Result};
+use vital_script_primitives::{
+    names::{NAME_LEN_MAX, SHORT_NAME_LEN_MAX},
+    resources::ResourceType,
+    traits::*,
+};
+
+use crate::{
+    op_basic::{
+        BasicOpcode, DMintLongName, DMintName, DMintShortName, DMintShortVRC20, DMintVRC20,
+        DMintVRC721,
+    },
+    op_extension::{DMintLongVRC20, ExtensionOpcode},
+};
+
+use super::resource_mint::InstructionResourceMint;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionResourceDMint {
+    pub output_index: u8,
+    pub resource_type: ResourceType,
+}
+
+impl core::fmt::Display for InstructionResourceDMint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "ResourceDMint:({}, {})", self.output_index, self.resource_type)
+    }
+}
+
+impl InstructionResourceDMint {
+    pub fn new(index: u8, resource_type: ResourceType) -> Self {
+        Self { output_index: index, resource_type }
+    }
+
+    fn as_mint(&self) -> InstructionResourceMint {
+        InstructionResourceMint::new(self.output_index, self.resource_type.clone())
+    }
+}
+
+impl Instruction for InstructionResourceDMint {
+    fn pre_check(&self) -> Result<()> {
+        self.as_mint().pre_check()
+    }
+
+    fn exec(&self, context: &mut impl Context) -> Result<()> {
+        // the simulator run the reveal tx before the commit tx confirmed, so skip it.
+        if !context.run_mod().is_skip_check() {
+            let input_index = context
+                .runner()
+                .current_script()
+                .ok_or_else(|| anyhow!("the dmint not in a script"))?;
+            if !context.env().is_commit_in_prev_blocks(input_index) {
+                bail!("the commit tx not confirmed before the reveal tx 's block");
+            }
+        }
+
+        self.as_mint().exec(context)
+    }
+
+    fn into_ops_bytes(self) -> Result<Vec<u8>> {
+        let bytes = match self.resource_type {
+            ResourceType::Name { name } => {
+                let l = name.len();
+                if l <= SHORT_NAME_LEN_MAX {
+                    let name = name.try_into().expect("the name should be short");
+                    DMintShortName { name, index: self.output_index }.encode_op()
+                } else if l <= NAME_LEN_MAX {
+                    DMintName { name: name.try_into()?, index: self.output_index }.encode_op()
+                } else {
+                    DMintLongName { name, index: self.output_index }.encode_op()
+                }
+            }
+            ResourceType::VRC20 { name } => {
+                let l = name.len();
+                if l <= SHORT_NAME_LEN_MAX {
+                    let name = name.try_into().expect("the name should be short");
+                    DMintShortVRC20 { name, index: self.output_index }.encode_op()
+                } else if l <= NAME_LEN_MAX {
+                    DMintVRC20 { name: name.try_into()?, index: self.output_index }.encode_op()
+                } else {
+                    DMintLongVRC20 { name, index: self.output_index }.encode_op()
+                }
+            }
+            ResourceType::VRC721 { hash } => {
+                DMintVRC721 { hash, index: self.output_index }.encode_op()
+            }
+        };
+
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use vital_script_primitives::{
+        resources::{Name, Resource},
+        traits::{Context, EnvContext},
+        H256,
+    };
+    use vital_script_runner::{mock::*, traits::EnvFunctions};
+
+    use vital_script_ops::{
+        builder::instruction::ScriptBuilderFromInstructions,
+        instruction::{assert_output::InstructionOutputAssert, Instruction},
+        parser::Parser,
+    };
+
+    #[test]
+    fn test_dmint_name() -> Result<()> {
+        let env_interface = EnvMock::new();
+
+        let name_res = Resource::name(Name::must_from("abcde"));
+        let dmint_name = vec![
+            Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+            Instruction::dmint(0, name_res.resource_type()),
+        ];
+
+        // 1. the commit tx in the same block, should failed.
+        let res = TestCtx::new(&env_interface)
+            .with_instructions(dmint_name.clone())
+            .with_ops()
+            .with_output(1000)
+            .with_commit_in_curr_block()
+            .run();
+        assert_err_str(
+            res,
+            "the commit tx not confirmed before the reveal tx 's block",
+            "commit in same block",
+        );
+
+        // 2. the commit tx in prev blocks.
+        let ctx = TestCtx::new(&env_interface)
+            .with_instructions(dmint_name.clone())
+            .with_ops()
+            .with_output(2000)
+            .run()?;
+
+        let out = ctx.env().get_output(0);
+        assert_eq!(env_interface.get_resources(&out)?, Some(name_res.clone()));
+
+        // 3. the dmint is a mint, so cannot mint a name two times.
+        let res = TestCtx::new(&env_interface)
+            .with_instructions(dmint_name)
+            .with_ops()
+            .with_output(3000)
+            .run();
+        assert_err_str(res, "the name had created", "dmint names two times will failed");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dmint_vrc20_and_vrc721() -> Result<()> {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.deploy_vrc20("abcde", 1000);
+
+        let vrc20_res = Resource::vrc20("abcde", 1000.into())?;
+        let vrc721_res = Resource::vrc721(H256::random());
+
+        for (i, res) in [vrc20_res, vrc721_res].into_iter().enumerate() {
+            let ctx = TestCtx::new(&env_interface)
+                .with_instructions(vec![
+                    Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                    Instruction::dmint(0, res.resource_type()),
+                ])
+                .with_ops()
+                .with_output((i as u64 + 1) * 1000)
+                .run()?;
+
+            let out = ctx.env().get_output(0);
+            assert_eq!(env_interface.get_resources(&out)?, Some(res));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dmint_encode_and_parse() -> Result<()> {
+        let resource_types = [
+            Resource::name(Name::must_from("abc")).resource_type(),
+            Resource::name(Name::must_from("abcdef")).resource_type(),
+            Resource::vrc20("abc", 1000.into())?.resource_type(),
+            Resource::vrc20("abcdef", 1000.into())?.resource_type(),
+            Resource::vrc721(H256::random()).resource_type(),
+        ];
+
+        for (opcode, resource_type) in (0x2c..=0x30).zip(resource_types) {
+            let instruction = Instruction::dmint(3, resource_type);
+
+            let bytes = ScriptBuilderFromInstructions::build(vec![instruction.clone()])?;
+            assert_eq!(bytes[0], opcode);
+
+            let parsed = Parser::new(&bytes).parse()?;
+            assert_eq!(parsed, vec![instruction]);
+        }
+
+        Ok(())
+    }
+}
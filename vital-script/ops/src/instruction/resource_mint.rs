@@ -3,14 +3,19 @@
 use alloc::vec::Vec;
 use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
 use vital_script_primitives::{
+    consts::PROTOCOL_VERSION_V2,
     names::{NAME_LEN_MAX, SHORT_NAME_LEN_MAX},
-    resources::{Resource, ResourceType, VRC20},
+    resources::{Resource, ResourceType, Tag, VRC20},
     traits::*,
-    U256,
+    types::bitwork::Bitwork,
+    H256, U256,
 };
 
-use crate::op_basic::{
-    BasicOpcode, MintName, MintShortName, MintShortVRC20, MintVRC20, MintVRC721,
+use crate::{
+    op_basic::{
+        BasicOpcode, MintLongName, MintName, MintShortName, MintShortVRC20, MintVRC20, MintVRC721,
+    },
+    op_extension::{ExtensionOpcode, MintCollectionVRC721, MintLongVRC20},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -48,6 +53,24 @@ impl InstructionResourceMint {
                     bail!("mint count had reached max");
                 }
 
+                // the mint tx should be in a block which height >= mint_height.
+                if context.env().get_block_height() < status_data.meta.mint.mint_height {
+                    bail!("mint height had not reached");
+                }
+
+                // the reveal txid should match the bitwork if the vrc20 need, since the protocol
+                // version 2, the vrc20 deployed before with an invalid bworkc not need.
+                let bitwork = if context.protocol_version() >= PROTOCOL_VERSION_V2 {
+                    Bitwork::from_bworkc(status_data.meta.bworkc).ok().flatten()
+                } else {
+                    None
+                };
+                if let Some(bitwork) = bitwork {
+                    if !bitwork.is_match_txid(context.env().get_reveal_tx_id()) {
+                        bail!("the reveal txid not match the bitwork");
+                    }
+                }
+
                 Resource::VRC20(VRC20 { name, amount: U256::from(amount) })
             }
             ResourceType::VRC721 { hash } => Resource::vrc721(hash),
@@ -101,12 +124,7 @@ impl Instruction for InstructionResourceMint {
                     .context("increase mint count failed")?;
             }
             Resource::VRC721(v) => {
-                // for vrc721, need check if the h256 had mint
-                if context.env().vrc721_had_mint(v.hash)? {
-                    bail!("vrc721 had mint");
-                } else {
-                    context.env_mut().mint_vrc721(v.hash).context("mint 721")?;
-                }
+                mint_vrc721(context, v.hash, None)?;
             }
         }
 
@@ -123,9 +141,9 @@ impl Instruction for InstructionResourceMint {
                     let name = name.try_into().expect("the name should be short");
                     MintShortName { name, index: self.output_index }.encode_op()
                 } else if l <= NAME_LEN_MAX {
-                    MintName { name, index: self.output_index }.encode_op()
+                    MintName { name: name.try_into()?, index: self.output_index }.encode_op()
                 } else {
-                    bail!("not support long name")
+                    MintLongName { name, index: self.output_index }.encode_op()
                 }
             }
             ResourceType::VRC20 { name } => {
@@ -134,9 +152,9 @@ impl Instruction for InstructionResourceMint {
                     let name = name.try_into().expect("the name should be short");
                     MintShortVRC20 { name, index: self.output_index }.encode_op()
                 } else if l <= NAME_LEN_MAX {
-                    MintVRC20 { name, index: self.output_index }.encode_op()
+                    MintVRC20 { name: name.try_into()?, index: self.output_index }.encode_op()
                 } else {
-                    bail!("not support long name")
+                    MintLongVRC20 { name, index: self.output_index }.encode_op()
                 }
             }
             ResourceType::VRC721 { hash } => {
@@ -148,18 +166,98 @@ impl Instruction for InstructionResourceMint {
     }
 }
 
+/// Mint a vrc721 in a collection, the mint count of the collection can not be
+/// more than its max supply, and only the owner, who holds the name of the collection
+/// in the inputs, can mint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionCollectionMint {
+    pub output_index: u8,
+    pub collection: Tag,
+    pub hash: H256,
+}
+
+impl core::fmt::Display for InstructionCollectionMint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "CollectionMint:({}, {}, {:?})", self.output_index, self.collection, self.hash)
+    }
+}
+
+impl Instruction for InstructionCollectionMint {
+    fn pre_check(&self) -> Result<()> {
+        if !self.collection.is_valid() || self.collection.is_empty() {
+            bail!("Invalid collection name");
+        }
+
+        if self.hash.is_zero() {
+            bail!("Invalid hash by zero");
+        }
+
+        Ok(())
+    }
+
+    fn exec(&self, context: &mut impl Context) -> Result<()> {
+        context.runner_mut().try_mint()?;
+
+        context
+            .env_mut()
+            .increase_vrc721_mint_count(self.collection)
+            .context("increase mint count failed")?;
+
+        // the name should be in the inputs and not costed, it will be kept by the owner.
+        let is_owner =
+            context.input_resource().uncosted().iter().any(
+                |(_, resource)| matches!(resource, Resource::Name(n) if *n == self.collection),
+            );
+        if !is_owner {
+            bail!("only the owner of the collection can mint, need the name in the inputs");
+        }
+
+        mint_vrc721(context, self.hash, Some(self.collection))?;
+
+        context.send_resource_to_output(self.output_index, Resource::vrc721(self.hash))?;
+
+        Ok(())
+    }
+
+    fn into_ops_bytes(self) -> Result<Vec<u8>> {
+        Ok(MintCollectionVRC721 {
+            collection: self.collection,
+            hash: self.hash,
+            index: self.output_index,
+        }
+        .encode_op())
+    }
+}
+
+fn mint_vrc721(context: &mut impl Context, hash: H256, collection: Option<Tag>) -> Result<()> {
+    // for vrc721, need check if the h256 had mint
+    if context.env().vrc721_had_mint(hash)? {
+        bail!("vrc721 had mint");
+    }
+
+    context.env_mut().mint_vrc721(hash, collection).context("mint 721")
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::{anyhow, Result};
 
     use vital_script_primitives::{
+        consts::{PROTOCOL_VERSION_V1, PROTOCOL_VERSION_V2},
         resources::{Name, Resource},
         traits::{Context, EnvContext},
+        types::{
+            bitwork::Bitwork,
+            vrc20::{VRC20MetaData, VRC20MintMeta},
+        },
         H256,
     };
     use vital_script_runner::{mock::*, traits::EnvFunctions};
 
-    use vital_script_ops::instruction::{assert_output::InstructionOutputAssert, Instruction};
+    use vital_script_ops::instruction::{
+        assert_input::InstructionInputAssert, assert_output::InstructionOutputAssert,
+        resource_mint::InstructionCollectionMint, Instruction,
+    };
 
     #[test]
     fn test_mint_short_name_invalid_will_failed() -> Result<()> {
@@ -399,6 +497,117 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_mint_vrc20_before_mint_height_should_failed() -> Result<()> {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        let vrc20_res1 = Resource::vrc20("abcde", 1000.into())?;
+        let vrc20_res2 = Resource::vrc20("abe", 1000.into())?;
+
+        let meta_with_height = |mint_height| VRC20MetaData {
+            decimals: 5,
+            nonce: 1000000,
+            bworkc: 1000000,
+            mint: VRC20MintMeta { mint_amount: 1000, mint_height, max_mints: 100 },
+            meta: None,
+        };
+
+        ctx.deploy_vrc20_with_meta("abcde", meta_with_height(MOCK_BLOCK_HEIGHT + 1));
+        ctx.deploy_vrc20_with_meta("abe", meta_with_height(MOCK_BLOCK_HEIGHT));
+
+        // the mint height is after current block, will failed
+        let res = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::mint(0, vrc20_res1.resource_type()),
+            ])
+            .with_ops()
+            .with_output(1000)
+            .run();
+
+        assert_err_str(res, "mint height had not reached", "the `abcde` can not mint now");
+
+        let status = vital_script_runner::EnvContext::new_for_query(
+            env_interface.clone(),
+            MOCK_BLOCK_HEIGHT,
+        )
+        .get_vrc20_metadata(Name::must_from("abcde").into())?
+        .ok_or(anyhow!("should deployed"))?;
+        assert_eq!(status.mint_count, 0);
+
+        // the mint height is current block, will ok
+        let ctx = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::mint(0, vrc20_res2.resource_type()),
+            ])
+            .with_ops()
+            .with_output(2000)
+            .run()?;
+
+        let out = ctx.env().get_output(0);
+        assert_eq!(env_interface.get_resources(&out)?.ok_or(anyhow!("should found"))?, vrc20_res2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mint_vrc20_with_bitwork() -> Result<()> {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        let vrc20_res1 = Resource::vrc20("abcde", 1000.into())?;
+        let vrc20_res2 = Resource::vrc20("abe", 1000.into())?;
+
+        let mint_ctx = |res: &Resource, sats: u64, protocol_version: u32| {
+            TestCtx::new(&env_interface)
+                .with_instructions(vec![
+                    Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                    Instruction::mint(0, res.resource_type()),
+                ])
+                .with_ops()
+                .with_output(sats)
+                .with_protocol_version(protocol_version)
+        };
+
+        // use the mint tx 's txid to make the bitwork, one is match and other is not.
+        let txid = mint_ctx(&vrc20_res1, 1000, PROTOCOL_VERSION_V2).reveal_txid().to_string();
+        let not_match = if txid.starts_with('0') { "1" } else { "0" };
+
+        let meta_with_bitwork = |bitwork: Bitwork| VRC20MetaData {
+            decimals: 5,
+            nonce: 1000000,
+            bworkc: bitwork.to_bworkc(),
+            mint: VRC20MintMeta { mint_amount: 1000, mint_height: 0, max_mints: 100 },
+            meta: None,
+        };
+
+        ctx.deploy_vrc20_with_meta(
+            "abcde",
+            meta_with_bitwork(Bitwork::from_hex_prefix(&txid[..4])?),
+        );
+        ctx.deploy_vrc20_with_meta("abe", meta_with_bitwork(Bitwork::from_hex_prefix(not_match)?));
+
+        // the txid not match the bitwork will failed
+        let res = mint_ctx(&vrc20_res2, 1000, PROTOCOL_VERSION_V2).run();
+        assert_err_str(res, "the reveal txid not match the bitwork", "the `abe` not match");
+
+        // the bitwork not checked before the protocol version 2
+        let ctx2 = mint_ctx(&vrc20_res2, 2000, PROTOCOL_VERSION_V1).run()?;
+
+        let out = ctx2.env().get_output(0);
+        assert_eq!(env_interface.get_resources(&out)?.ok_or(anyhow!("should found"))?, vrc20_res2);
+
+        // the txid match the bitwork will ok
+        let ctx1 = mint_ctx(&vrc20_res1, 1000, PROTOCOL_VERSION_V2).run()?;
+
+        let out = ctx1.env().get_output(0);
+        assert_eq!(env_interface.get_resources(&out)?.ok_or(anyhow!("should found"))?, vrc20_res1);
+
+        Ok(())
+    }
+
     #[test]
     fn mint_vrc721_should_work() -> Result<()> {
         let env_interface = EnvMock::new();
@@ -507,10 +716,155 @@ mod tests {
 
         assert_err_str(
             res,
-            "each tx can only have one mint",
+            "each script can only have one mint",
             "mint_vrc721_two_times_should_failed",
         );
 
         Ok(())
     }
+
+    #[test]
+    fn mint_vrc721_in_collection_should_limit_by_max_supply() -> Result<()> {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.deploy_vrc721("abcde", 2);
+
+        let collection = Name::must_from("abcde").into();
+        let owner = Resource::Name(collection);
+        // the amount of the output is just for make the txid not eq.
+        let collection_mint = |hash: H256, amount: u64| {
+            let owner_outpoint = env_interface.get_outpoint(&owner).expect("the owner");
+
+            TestCtx::new(&env_interface)
+                .with_instructions(vec![
+                    Instruction::Input(InstructionInputAssert {
+                        index: 1,
+                        resource: owner.clone(),
+                    }),
+                    Instruction::Output(InstructionOutputAssert { indexs: vec![0, 1] }),
+                    Instruction::CollectionMint(InstructionCollectionMint {
+                        output_index: 0,
+                        collection,
+                        hash,
+                    }),
+                    Instruction::move_to(1, owner.clone()),
+                ])
+                .with_ops()
+                .with_input(owner_outpoint)
+                .with_outputs(2, amount)
+                .run()
+        };
+
+        // 1. mint in the collection will record the collection.
+        let hash1 = H256::random();
+        let context = collection_mint(hash1, 2001)?;
+
+        assert_eq!(
+            env_interface.get_resources(&context.env().get_output(0))?,
+            Some(Resource::vrc721(hash1))
+        );
+        assert_eq!(context.env().get_vrc721_collection(hash1)?, Some(collection));
+        assert_eq!(context.env().get_vrc721_metadata(collection)?.expect("deployed").mint_count, 1);
+
+        // 2. the hash minted can not mint again, in or not in a collection.
+        assert_err_str(collection_mint(hash1, 2002), "vrc721 had mint", "mint in collection twice");
+
+        let hash2 = H256::random();
+        ctx.mint_vrc721(hash2);
+        assert_err_str(
+            collection_mint(hash2, 2003),
+            "vrc721 had mint",
+            "mint minted in collection",
+        );
+
+        // 3. the mint count can not more than the max supply.
+        let context = collection_mint(H256::random(), 2004)?;
+        assert_eq!(context.env().get_vrc721_metadata(collection)?.expect("deployed").mint_count, 2);
+
+        assert_err_str(
+            collection_mint(H256::random(), 2005),
+            "mint count had reached max supply",
+            "mint more than max supply",
+        );
+
+        // 4. the collection should be deployed.
+        let res = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::CollectionMint(InstructionCollectionMint {
+                    output_index: 0,
+                    collection: Name::must_from("other").into(),
+                    hash: H256::random(),
+                }),
+            ])
+            .with_ops()
+            .with_output(2000)
+            .run();
+
+        assert_err_str(res, "the vrc721 collection had not created", "not deployed collection");
+
+        Ok(())
+    }
+
+    #[test]
+    fn mint_vrc721_in_collection_by_not_owner_should_failed() -> Result<()> {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.deploy_vrc721("abcde", 10);
+        ctx.mint_name("other");
+
+        let collection = Name::must_from("abcde").into();
+        let collection_mint = |inputs: Vec<Instruction>, outpoint: Option<_>, amount: u64| {
+            let mut instructions = inputs;
+            instructions.append(&mut vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::CollectionMint(InstructionCollectionMint {
+                    output_index: 0,
+                    collection,
+                    hash: H256::random(),
+                }),
+            ]);
+
+            let mut test_ctx = TestCtx::new(&env_interface).with_instructions(instructions);
+            if let Some(outpoint) = outpoint {
+                test_ctx = test_ctx.with_input(outpoint);
+            }
+
+            test_ctx.with_ops().with_output(amount).run()
+        };
+
+        // 1. mint without the name of the collection.
+        assert_err_str(
+            collection_mint(vec![], None, 2001),
+            "only the owner of the collection can mint, need the name in the inputs",
+            "mint without owner",
+        );
+
+        // 2. mint by other name.
+        let other = Resource::name(Name::must_from("other"));
+        let other_outpoint = env_interface.get_outpoint(&other).expect("the other name");
+        assert_err_str(
+            collection_mint(
+                vec![Instruction::Input(InstructionInputAssert { index: 1, resource: other })],
+                Some(other_outpoint),
+                2002,
+            ),
+            "only the owner of the collection can mint, need the name in the inputs",
+            "mint by other name",
+        );
+
+        // 3. the owner can mint.
+        let owner = Resource::Name(collection);
+        let owner_outpoint = env_interface.get_outpoint(&owner).expect("the owner");
+        collection_mint(
+            vec![Instruction::Input(InstructionInputAssert { index: 1, resource: owner })],
+            Some(owner_outpoint),
+            2003,
+        )
+        .expect("mint by owner should ok");
+
+        Ok(())
+    }
 }
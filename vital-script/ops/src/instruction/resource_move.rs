@@ -10,7 +10,10 @@ use vital_script_primitives::{
 
 use crate::{
     instruction::utils::Vrc20ResourceOperand,
-    op_basic::{BasicOpcode, MoveAllVRC20, MoveAllVRC20S, MoveName, MoveShortName, MoveVRC721},
+    op_basic::{
+        BasicOpcode, MoveAllVRC20, MoveAllVRC20S, MoveLongName, MoveName, MoveShortName, MoveVRC721,
+    },
+    op_extension::{ExtensionOpcode, MoveAllLongVRC20, MoveLongVRC20},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -58,13 +61,17 @@ impl Instruction for InstructionResourceMove {
                 }
                 .encode_op(),
                 n if n <= NAME_LEN_MAX => {
-                    MoveName { name, output_index: self.output_index }.encode_op()
-                }
-                _ => {
-                    bail!("not support long name")
+                    MoveName { name: name.try_into()?, output_index: self.output_index }.encode_op()
                 }
+                _ => MoveLongName { name, output_index: self.output_index }.encode_op(),
             },
-            Resource::VRC20(vrc20) => Vrc20ResourceOperand::new(vrc20)
+            Resource::VRC20(vrc20) if vrc20.name.is_long() => MoveLongVRC20 {
+                name: vrc20.name,
+                amount: vrc20.amount,
+                output_index: self.output_index,
+            }
+            .encode_op(),
+            Resource::VRC20(vrc20) => Vrc20ResourceOperand::new(vrc20)?
                 .into_move_vrc20_opcode_bytes(self.output_index)
                 .context("use Vrc20ResourceOperand into opcode bytes")?,
             Resource::VRC721(vrc721) => {
@@ -130,11 +137,10 @@ impl Instruction for InstructionResourceMoveAll {
                 }
                 .encode_op(),
                 n if n <= NAME_LEN_MAX => {
-                    MoveAllVRC20 { name, output_index: self.output_index }.encode_op()
-                }
-                _ => {
-                    bail!("not support long name")
+                    MoveAllVRC20 { name: name.try_into()?, output_index: self.output_index }
+                        .encode_op()
                 }
+                _ => MoveAllLongVRC20 { name, output_index: self.output_index }.encode_op(),
             };
 
             Ok(raw)
@@ -149,7 +155,7 @@ mod tests {
     use anyhow::Result;
 
     use vital_script_primitives::{
-        resources::{Name, Resource},
+        resources::{Name, Resource, Tag},
         traits::{Context, EnvContext},
         H256, U256,
     };
@@ -172,8 +178,8 @@ mod tests {
     }
 
     fn test_move_name_impl(test_name: &str) -> Result<()> {
-        let test = Name::try_from(test_name).expect("name format");
-        let test_res = Resource::Name(test);
+        let test = Tag::try_from(test_name).expect("name format");
+        let test_res = Resource::name(test);
 
         let env_interface = EnvMock::new();
         let mut ctx = TestCtx::new(&env_interface);
@@ -253,8 +259,8 @@ mod tests {
     }
 
     fn test_move_name_had_costed_failed_impl(test_name: &str) -> Result<()> {
-        let test = Name::try_from(test_name).expect("name format");
-        let test_res = Resource::Name(test);
+        let test = Tag::try_from(test_name).expect("name format");
+        let test_res = Resource::name(test);
 
         let env_interface = EnvMock::new();
         let mut ctx = TestCtx::new(&env_interface);
@@ -287,6 +293,9 @@ mod tests {
         test_move_name_impl("a1").expect("test move a1 should ok");
         test_move_name_impl("abcde1234").expect("test move abcde1234 should ok");
         test_move_name_impl("abcde@1234").expect("test move abcde@1234 should ok");
+        test_move_name_impl("abcde@12345").expect("test move abcde@12345 should ok");
+        test_move_name_impl("abcdefghijklmnopqrstuvwxyz012345")
+            .expect("test move a long name should ok");
 
         Ok(())
     }
@@ -477,7 +486,7 @@ mod tests {
     }
 
     fn test_move_all_vrc20_impl(test_name: &str) -> Result<()> {
-        let test = Name::try_from(test_name).expect("name format");
+        let test = Tag::try_from(test_name).expect("name format");
         let mint_amount = u128::MAX - 1;
 
         let env_interface = EnvMock::new();
@@ -577,12 +586,14 @@ mod tests {
         test_move_vrc20_impl("a1").expect("test move a1 should ok");
         test_move_vrc20_impl("abcde1234").expect("test move abcde1234 should ok");
         test_move_vrc20_impl("abcde@1234").expect("test move abcde@1234 should ok");
+        test_move_vrc20_impl("abcde@12345").expect("test move abcde@12345 should ok");
 
         test_move_all_vrc20_impl("abcde").expect("test move abcde should ok");
         test_move_all_vrc20_impl("a").expect("test move a should ok");
         test_move_all_vrc20_impl("a1").expect("test move a1 should ok");
         test_move_all_vrc20_impl("abcde1234").expect("test move abcde1234 should ok");
         test_move_all_vrc20_impl("abcde@1234").expect("test move abcde@1234 should ok");
+        test_move_all_vrc20_impl("abcde@12345").expect("test move abcde@12345 should ok");
 
         Ok(())
     }
@@ -2,9 +2,9 @@
 //!
 
 use alloc::vec::Vec;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use vital_script_primitives::{
-    names::{ShortName, NAME_LEN_MAX, SHORT_NAME_LEN_MAX},
+    names::{Name, ShortName, NAME_LEN_MAX, SHORT_NAME_LEN_MAX},
     resources::VRC20,
     U256,
 };
@@ -19,14 +19,15 @@ pub struct Vrc20ResourceOperand {
 }
 
 impl Vrc20ResourceOperand {
-    pub fn new(res: VRC20) -> Self {
+    /// The vrc20 with long name should use the extension opcodes, so it will return err.
+    pub fn new(res: VRC20) -> Result<Self> {
         let name_len = res.name.len();
         let (name_bytes, name_typ_idx) = if name_len <= SHORT_NAME_LEN_MAX {
             (ShortName::try_from(res.name).expect("should ok").0.to_vec(), 0)
         } else if name_len <= NAME_LEN_MAX {
-            (res.name.0.to_vec(), 1)
+            (Name::try_from(res.name).expect("should ok").0.to_vec(), 1)
         } else {
-            todo!("not support long name")
+            bail!("not support long name by basic opcode")
         };
 
         let (amount_bytes, amount_typ_idx) = if res.amount <= U256::from(u32::MAX) {
@@ -42,7 +43,7 @@ impl Vrc20ResourceOperand {
             (raw.to_vec(), 3)
         };
 
-        Self { name_bytes, name_typ_idx: name_typ_idx * 4, amount_bytes, amount_typ_idx }
+        Ok(Self { name_bytes, name_typ_idx: name_typ_idx * 4, amount_bytes, amount_typ_idx })
     }
 
     pub fn into_input_vrc20_opcode_bytes(mut self, index: u8) -> Result<Vec<u8>> {
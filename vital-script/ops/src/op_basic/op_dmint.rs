@@ -1 +1,117 @@
+use parity_scale_codec::{Decode, Encode};
 
+use vital_script_derive::BasicOpcode;
+use vital_script_primitives::{
+    names::{LongName, Name, ShortName},
+    resources::ResourceType,
+    H256,
+};
+
+use crate::instruction::Instruction;
+
+/// Distributed mint short name
+#[derive(Debug, BasicOpcode, Encode, Decode)]
+pub struct DMintShortName {
+    pub name: ShortName,
+    pub index: u8,
+}
+
+impl From<DMintShortName> for Instruction {
+    fn from(value: DMintShortName) -> Self {
+        Instruction::dmint(value.index, ResourceType::name(value.name))
+    }
+}
+
+/// Distributed mint name
+#[derive(Debug, BasicOpcode, Encode, Decode)]
+pub struct DMintName {
+    pub name: Name,
+    pub index: u8,
+}
+
+impl From<DMintName> for Instruction {
+    fn from(value: DMintName) -> Self {
+        Instruction::dmint(value.index, ResourceType::name(value.name))
+    }
+}
+
+/// Distributed mint short vrc20
+#[derive(Debug, BasicOpcode, Encode, Decode)]
+pub struct DMintShortVRC20 {
+    pub name: ShortName,
+    pub index: u8,
+}
+
+impl From<DMintShortVRC20> for Instruction {
+    fn from(value: DMintShortVRC20) -> Self {
+        Instruction::dmint(value.index, ResourceType::vrc20(value.name))
+    }
+}
+
+/// Distributed mint vrc20
+#[derive(Debug, BasicOpcode, Encode, Decode)]
+pub struct DMintVRC20 {
+    pub name: Name,
+    pub index: u8,
+}
+
+impl From<DMintVRC20> for Instruction {
+    fn from(value: DMintVRC20) -> Self {
+        Instruction::dmint(value.index, ResourceType::vrc20(value.name))
+    }
+}
+
+/// Distributed mint vrc721
+#[derive(Debug, BasicOpcode, Encode, Decode)]
+pub struct DMintVRC721 {
+    pub hash: H256,
+    pub index: u8,
+}
+
+impl From<DMintVRC721> for Instruction {
+    fn from(value: DMintVRC721) -> Self {
+        Instruction::dmint(value.index, ResourceType::vrc721(value.hash))
+    }
+}
+
+/// Distributed mint long name
+#[derive(Debug, BasicOpcode, Encode, Decode)]
+pub struct DMintLongName {
+    pub name: LongName,
+    pub index: u8,
+}
+
+impl From<DMintLongName> for Instruction {
+    fn from(value: DMintLongName) -> Self {
+        Instruction::dmint(value.index, ResourceType::name(value.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vital_script_primitives::names::Name;
+
+    use super::*;
+    use crate::op_basic::tests::check_ops_encode_and_decode;
+
+    #[test]
+    fn test_dmint_ops_encode_and_decode() {
+        let short_name = ShortName::try_from("abc".to_string()).unwrap();
+        let name = Name::try_from("abcdef".to_string()).unwrap();
+
+        check_ops_encode_and_decode(DMintShortName { name: short_name, index: 128 });
+
+        check_ops_encode_and_decode(DMintName { name, index: 128 });
+
+        check_ops_encode_and_decode(DMintShortVRC20 { name: short_name, index: 128 });
+
+        check_ops_encode_and_decode(DMintVRC20 { name, index: 128 });
+
+        check_ops_encode_and_decode(DMintVRC721 { hash: H256::random(), index: 128 });
+
+        check_ops_encode_and_decode(DMintLongName {
+            name: LongName::must_from("abcdefghijklmn"),
+            index: 128,
+        });
+    }
+}
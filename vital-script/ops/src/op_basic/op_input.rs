@@ -2,8 +2,8 @@ use parity_scale_codec::{Decode, Encode};
 
 use vital_script_derive::BasicOpcode;
 use vital_script_primitives::{
-    names::{Name, ShortName},
-    resources::{Resource, Tag, VRC20, VRC721},
+    names::{LongName, Name, ShortName},
+    resources::{Resource, VRC20, VRC721},
     H256, U256,
 };
 
@@ -36,7 +36,7 @@ impl From<InputAssertName> for Instruction {
     fn from(value: InputAssertName) -> Self {
         Instruction::Input(InstructionInputAssert {
             index: value.index,
-            resource: Resource::Name(value.name),
+            resource: Resource::name(value.name),
         })
     }
 }
@@ -44,7 +44,7 @@ impl From<InputAssertName> for Instruction {
 /// Input Long Name Res Assert
 #[derive(Debug, BasicOpcode, Encode, Decode)]
 pub struct InputAssertLongName {
-    pub name: Tag, // TODO: add long name
+    pub name: LongName,
     pub index: u8,
 }
 
@@ -52,7 +52,7 @@ impl From<InputAssertLongName> for Instruction {
     fn from(value: InputAssertLongName) -> Self {
         Instruction::Input(InstructionInputAssert {
             index: value.index,
-            resource: Resource::Name(value.name),
+            resource: Resource::name(value.name),
         })
     }
 }
@@ -137,7 +137,7 @@ impl From<InputVRC20AssertA32> for Instruction {
     fn from(value: InputVRC20AssertA32) -> Self {
         Instruction::Input(InstructionInputAssert {
             index: value.index,
-            resource: Resource::VRC20(VRC20::new(value.name, value.amount.into())),
+            resource: Resource::VRC20(VRC20::new(value.name.into(), value.amount.into())),
         })
     }
 }
@@ -154,7 +154,7 @@ impl From<InputVRC20AssertA64> for Instruction {
     fn from(value: InputVRC20AssertA64) -> Self {
         Instruction::Input(InstructionInputAssert {
             index: value.index,
-            resource: Resource::VRC20(VRC20::new(value.name, value.amount.into())),
+            resource: Resource::VRC20(VRC20::new(value.name.into(), value.amount.into())),
         })
     }
 }
@@ -171,7 +171,7 @@ impl From<InputVRC20AssertA128> for Instruction {
     fn from(value: InputVRC20AssertA128) -> Self {
         Instruction::Input(InstructionInputAssert {
             index: value.index,
-            resource: Resource::VRC20(VRC20::new(value.name, value.amount.into())),
+            resource: Resource::VRC20(VRC20::new(value.name.into(), value.amount.into())),
         })
     }
 }
@@ -188,7 +188,7 @@ impl From<InputVRC20AssertA256> for Instruction {
     fn from(value: InputVRC20AssertA256) -> Self {
         Instruction::Input(InstructionInputAssert {
             index: value.index,
-            resource: Resource::VRC20(VRC20::new(value.name, value.amount)),
+            resource: Resource::VRC20(VRC20::new(value.name.into(), value.amount)),
         })
     }
 }
@@ -228,11 +228,10 @@ mod tests {
 
         check_ops_encode_and_decode(InputAssertName { name, index: 2 });
 
-        // TODO: support long name
-        // check_ops_encode_and_decode(InputAssertLongName{
-        //     name: name.into(),
-        //    index: 3,
-        // });
+        check_ops_encode_and_decode(InputAssertLongName {
+            name: LongName::must_from("abcdefghijklmn"),
+            index: 3,
+        });
 
         check_ops_encode_and_decode(InputVRC20AssertSa32 {
             amount: u32::MAX / 2 + 999,
@@ -295,13 +294,13 @@ mod tests {
             Instruction::from(InputAssertShortName { name: short_name, index: 1 }),
             Instruction::Input(InstructionInputAssert {
                 index: 1,
-                resource: Resource::Name(Name::from(short_name))
+                resource: Resource::name(Name::from(short_name))
             })
         );
 
         assert_eq!(
             Instruction::from(InputAssertName { name, index: 1 }),
-            Instruction::Input(InstructionInputAssert { index: 1, resource: Resource::Name(name) })
+            Instruction::Input(InstructionInputAssert { index: 1, resource: Resource::name(name) })
         );
 
         assert_eq!(
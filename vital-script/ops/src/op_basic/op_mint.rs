@@ -2,7 +2,7 @@ use parity_scale_codec::{Decode, Encode};
 
 use vital_script_derive::BasicOpcode;
 use vital_script_primitives::{
-    names::{Name, ShortName},
+    names::{LongName, Name, ShortName},
     resources::ResourceType,
     H256,
 };
@@ -74,6 +74,19 @@ impl From<MintVRC721> for Instruction {
     }
 }
 
+/// Mint long name
+#[derive(Debug, BasicOpcode, Encode, Decode)]
+pub struct MintLongName {
+    pub name: LongName,
+    pub index: u8,
+}
+
+impl From<MintLongName> for Instruction {
+    fn from(value: MintLongName) -> Self {
+        Instruction::mint(value.index, ResourceType::name(value.name))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use vital_script_primitives::names::Name;
@@ -95,5 +108,10 @@ mod tests {
         check_ops_encode_and_decode(MintVRC20 { name, index: 128 });
 
         check_ops_encode_and_decode(MintVRC721 { hash: H256::random(), index: 128 });
+
+        check_ops_encode_and_decode(MintLongName {
+            name: LongName::must_from("abcdefghijklmn"),
+            index: 128,
+        });
     }
 }
@@ -2,7 +2,7 @@ use parity_scale_codec::{Decode, Encode};
 
 use vital_script_derive::BasicOpcode;
 use vital_script_primitives::{
-    names::{Name, ShortName},
+    names::{LongName, Name, ShortName},
     resources::{Resource, ResourceType, VRC721},
     H256, U256,
 };
@@ -38,7 +38,18 @@ impl From<MoveName> for Instruction {
     }
 }
 
-// TODO: Move LongName.
+/// Move long name to a output for (LongName)
+#[derive(Debug, BasicOpcode, Encode, Decode)]
+pub struct MoveLongName {
+    pub name: LongName,
+    pub output_index: u8,
+}
+
+impl From<MoveLongName> for Instruction {
+    fn from(value: MoveLongName) -> Self {
+        Instruction::move_to(value.output_index, value.name)
+    }
+}
 
 /// Move all VRC20 Res to a output for (ShortName)
 #[derive(Debug, BasicOpcode, Encode, Decode)]
@@ -215,6 +226,11 @@ mod tests {
         check_ops_encode_and_decode(MoveShortName { name: short_name, output_index: 128 });
 
         check_ops_encode_and_decode(MoveName { name, output_index: 128 });
+
+        check_ops_encode_and_decode(MoveLongName {
+            name: LongName::must_from("abcdefghijklmn"),
+            output_index: 128,
+        });
     }
 
     #[test]
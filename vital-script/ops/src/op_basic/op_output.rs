@@ -6,7 +6,7 @@ use vital_script_derive::BasicOpcode;
 use crate::instruction::{assert_output::InstructionOutputAssert, Instruction};
 
 #[inline]
-fn u8_to_pos(i: u8, c: u8) -> Vec<u8> {
+pub(crate) fn u8_to_pos(i: u8, c: u8) -> Vec<u8> {
     let mut res = Vec::new();
 
     for pos in 0..8 {
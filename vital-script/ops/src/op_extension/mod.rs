@@ -5,10 +5,18 @@ use anyhow::{anyhow, Result};
 use bytes::Bytes;
 
 mod op_burn;
+mod op_collection;
 mod op_deploy;
+mod op_long_vrc20;
+mod op_output;
+mod op_space;
 
 pub use op_burn::*;
+pub use op_collection::*;
 pub use op_deploy::*;
+pub use op_long_vrc20::*;
+pub use op_output::*;
+pub use op_space::*;
 
 use crate::{instruction::Instruction, utils::Reader};
 
@@ -0,0 +1,67 @@
+//! The opcodes for the vrc721 collection.
+
+use parity_scale_codec::{Decode, Encode};
+
+use vital_script_derive::ExtensionOpcode;
+use vital_script_primitives::{names::LongName, H256};
+
+use crate::instruction::{resource_mint::InstructionCollectionMint, Instruction};
+
+/// Mint vrc721 in a collection
+#[derive(Debug, ExtensionOpcode, Encode, Decode)]
+pub struct MintCollectionVRC721 {
+    pub collection: LongName,
+    pub hash: H256,
+    pub index: u8,
+}
+
+impl From<MintCollectionVRC721> for Instruction {
+    fn from(value: MintCollectionVRC721) -> Self {
+        Instruction::CollectionMint(InstructionCollectionMint {
+            output_index: value.index,
+            collection: value.collection,
+            hash: value.hash,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vital_script_primitives::types::vrc721::VRC721MetaData;
+
+    use super::*;
+    use crate::{
+        instruction::resource_deploy::InstructionVRC721Deploy,
+        op_extension::{DeployVRC721, ExtensionOpcode},
+        parser::Parser,
+    };
+
+    #[test]
+    fn test_collection_ops_encode_and_decode() {
+        let name = LongName::must_from("abcdefghijkl");
+        let meta = VRC721MetaData { max_supply: 100, royalty: 500, meta: None };
+
+        let bytes = DeployVRC721 { name_input: 1, name, meta: meta.clone() }.encode_op();
+        assert_eq!(bytes[..2], [0x80, 0x0c]);
+
+        let parsed = Parser::new(&bytes).parse().expect("parse");
+        assert_eq!(
+            parsed,
+            vec![Instruction::DeployVRC721(InstructionVRC721Deploy { name_input: 1, name, meta })]
+        );
+
+        let hash = H256::random();
+        let bytes = MintCollectionVRC721 { collection: name, hash, index: 2 }.encode_op();
+        assert_eq!(bytes[..2], [0x80, 0x0d]);
+
+        let parsed = Parser::new(&bytes).parse().expect("parse");
+        assert_eq!(
+            parsed,
+            vec![Instruction::CollectionMint(InstructionCollectionMint {
+                output_index: 2,
+                collection: name,
+                hash
+            })]
+        );
+    }
+}
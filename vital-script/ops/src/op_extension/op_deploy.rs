@@ -4,11 +4,14 @@ use parity_scale_codec::{Decode, Encode};
 
 use vital_script_derive::ExtensionOpcode;
 use vital_script_primitives::{
-    names::{Name, ShortName},
-    types::vrc20::VRC20MetaData,
+    names::{LongName, Name, ShortName},
+    types::{vrc20::VRC20MetaData, vrc721::VRC721MetaData},
 };
 
-use crate::instruction::{resource_deploy::InstructionVRC20Deploy, Instruction};
+use crate::instruction::{
+    resource_deploy::{InstructionVRC20Deploy, InstructionVRC721Deploy},
+    Instruction,
+};
 
 /// Deploy VRC20 with ShortName
 #[derive(Debug, ExtensionOpcode, Encode, Decode)]
@@ -39,6 +42,42 @@ pub struct DeployVRC20 {
 impl From<DeployVRC20> for Instruction {
     fn from(value: DeployVRC20) -> Self {
         Instruction::Deploy(InstructionVRC20Deploy {
+            name_input: value.name_input,
+            name: value.name.into(),
+            meta: value.meta,
+        })
+    }
+}
+
+/// Deploy VRC20 with LongName
+#[derive(Debug, ExtensionOpcode, Encode, Decode)]
+pub struct DeployLongVRC20 {
+    pub name_input: u8,
+    pub name: LongName,
+    pub meta: VRC20MetaData,
+}
+
+impl From<DeployLongVRC20> for Instruction {
+    fn from(value: DeployLongVRC20) -> Self {
+        Instruction::Deploy(InstructionVRC20Deploy {
+            name_input: value.name_input,
+            name: value.name,
+            meta: value.meta,
+        })
+    }
+}
+
+/// Deploy VRC721 collection with LongName
+#[derive(Debug, ExtensionOpcode, Encode, Decode)]
+pub struct DeployVRC721 {
+    pub name_input: u8,
+    pub name: LongName,
+    pub meta: VRC721MetaData,
+}
+
+impl From<DeployVRC721> for Instruction {
+    fn from(value: DeployVRC721) -> Self {
+        Instruction::DeployVRC721(InstructionVRC721Deploy {
             name_input: value.name_input,
             name: value.name,
             meta: value.meta,
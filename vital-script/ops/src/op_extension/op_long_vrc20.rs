@@ -0,0 +1,134 @@
+//! The opcodes for the vrc20 with a long name.
+//!
+//! The vrc20 with a long name is rare, so all the opcodes for it are extension opcodes,
+//! and the amount always use u256.
+
+use parity_scale_codec::{Decode, Encode};
+
+use vital_script_derive::ExtensionOpcode;
+use vital_script_primitives::{
+    names::LongName,
+    resources::{Resource, ResourceType, VRC20},
+    U256,
+};
+
+use crate::instruction::{
+    assert_input::InstructionInputAssert, resource_move::InstructionResourceMoveAll, Instruction,
+};
+
+/// Mint vrc20 with LongName
+#[derive(Debug, ExtensionOpcode, Encode, Decode)]
+pub struct MintLongVRC20 {
+    pub name: LongName,
+    pub index: u8,
+}
+
+impl From<MintLongVRC20> for Instruction {
+    fn from(value: MintLongVRC20) -> Self {
+        Instruction::mint(value.index, ResourceType::vrc20(value.name))
+    }
+}
+
+/// Distributed mint vrc20 with LongName
+#[derive(Debug, ExtensionOpcode, Encode, Decode)]
+pub struct DMintLongVRC20 {
+    pub name: LongName,
+    pub index: u8,
+}
+
+impl From<DMintLongVRC20> for Instruction {
+    fn from(value: DMintLongVRC20) -> Self {
+        Instruction::dmint(value.index, ResourceType::vrc20(value.name))
+    }
+}
+
+/// Move all VRC20 Res to a output for (LongName)
+#[derive(Debug, ExtensionOpcode, Encode, Decode)]
+pub struct MoveAllLongVRC20 {
+    pub name: LongName,
+    pub output_index: u8,
+}
+
+impl From<MoveAllLongVRC20> for Instruction {
+    fn from(value: MoveAllLongVRC20) -> Self {
+        Instruction::MoveAll(InstructionResourceMoveAll::new(
+            value.output_index,
+            ResourceType::vrc20(value.name),
+        ))
+    }
+}
+
+/// Move VRC20 Res with a amount to a output for (LongName, u256)
+#[derive(Debug, ExtensionOpcode, Encode, Decode)]
+pub struct MoveLongVRC20 {
+    pub name: LongName,
+    pub amount: U256,
+    pub output_index: u8,
+}
+
+impl From<MoveLongVRC20> for Instruction {
+    fn from(value: MoveLongVRC20) -> Self {
+        Instruction::move_vrc20_to(value.output_index, value.name, value.amount)
+    }
+}
+
+/// Input VRC20 Res Assert for (LongName, u256 amount)
+#[derive(Debug, ExtensionOpcode, Encode, Decode)]
+pub struct InputLongVRC20Assert {
+    pub amount: U256,
+    pub name: LongName,
+    pub index: u8,
+}
+
+impl From<InputLongVRC20Assert> for Instruction {
+    fn from(value: InputLongVRC20Assert) -> Self {
+        Instruction::Input(InstructionInputAssert {
+            index: value.index,
+            resource: Resource::VRC20(VRC20::new(value.name, value.amount)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vital_script_primitives::{
+        traits::Instruction as InstructionT,
+        types::vrc20::{VRC20MetaData, VRC20MintMeta},
+    };
+
+    use super::*;
+    use crate::{instruction::resource_deploy::InstructionVRC20Deploy, parser::Parser};
+
+    #[test]
+    fn test_long_vrc20_instructions_encode_and_parse() {
+        let name = LongName::must_from("abcdefghijklmn");
+        let meta = VRC20MetaData {
+            decimals: 5,
+            nonce: 1000000,
+            bworkc: 1000000,
+            mint: VRC20MintMeta { mint_amount: 1000, mint_height: 0, max_mints: 100 },
+            meta: None,
+        };
+        let vrc20 = Resource::VRC20(VRC20::new(name, U256::from(u128::MAX) + 1));
+
+        let instructions = [
+            (0x8005, Instruction::Deploy(InstructionVRC20Deploy { name_input: 1, name, meta })),
+            (0x8006, Instruction::mint(0, ResourceType::vrc20(name))),
+            (0x8007, Instruction::dmint(0, ResourceType::vrc20(name))),
+            (
+                0x8008,
+                Instruction::MoveAll(InstructionResourceMoveAll::new(2, ResourceType::vrc20(name))),
+            ),
+            (0x8009, Instruction::move_to(3, vrc20.clone())),
+            (0x800a, Instruction::Input(InstructionInputAssert { index: 4, resource: vrc20 })),
+        ];
+
+        for (opcode, instruction) in instructions {
+            let bytes = instruction.clone().into_ops_bytes().expect("into ops bytes");
+            assert_eq!(u16::from_be_bytes([bytes[0], bytes[1]]), opcode);
+
+            let parsed = Parser::new(&bytes).parse().expect("parse");
+            assert_eq!(parsed, vec![instruction]);
+        }
+    }
+}
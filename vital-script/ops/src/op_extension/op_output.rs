@@ -0,0 +1,26 @@
+//! The Output assert opcode.
+
+use parity_scale_codec::{Decode, Encode};
+
+use vital_script_derive::ExtensionOpcode;
+
+use crate::{
+    instruction::{assert_output::InstructionOutputAssert, Instruction},
+    op_basic::u8_to_pos,
+};
+
+/// Output Index Assert By A u64 as FlagMask
+#[derive(Debug, ExtensionOpcode, Encode, Decode)]
+pub struct OutputIndexFlag64Assert {
+    pub index_flag: [u8; 8],
+}
+
+impl From<OutputIndexFlag64Assert> for Instruction {
+    fn from(value: OutputIndexFlag64Assert) -> Self {
+        let indexs = [0_u8, 1, 2, 3, 4, 5, 6, 7]
+            .map(|c| u8_to_pos(value.index_flag[c as usize], c))
+            .concat();
+
+        Instruction::Output(InstructionOutputAssert { indexs })
+    }
+}
@@ -0,0 +1,51 @@
+//! The Space opcode.
+
+use alloc::vec::Vec;
+use parity_scale_codec::{Decode, Encode};
+
+use vital_script_derive::ExtensionOpcode;
+use vital_script_primitives::resources::Resource;
+
+use crate::instruction::{claim_space::InstructionClaimSpace, Instruction};
+
+/// Claim the resources in the space by the input
+#[derive(Debug, ExtensionOpcode, Encode, Decode)]
+pub struct ClaimSpace {
+    pub resources: Vec<Resource>,
+    pub index: u8,
+}
+
+impl From<ClaimSpace> for Instruction {
+    fn from(value: ClaimSpace) -> Self {
+        Instruction::ClaimSpace(InstructionClaimSpace {
+            index: value.index,
+            resources: value.resources,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vital_script_primitives::{names::Name, H256};
+
+    use super::*;
+    use crate::{op_extension::ExtensionOpcode, parser::Parser};
+
+    #[test]
+    fn test_claim_space_ops_encode_and_decode() {
+        let resources = vec![
+            Resource::name(Name::must_from("abc")),
+            Resource::vrc20("abcdefghijkl", 1000.into()).expect("vrc20"),
+            Resource::vrc721(H256::random()),
+        ];
+
+        let bytes = ClaimSpace { resources: resources.clone(), index: 3 }.encode_op();
+        assert_eq!(bytes[..2], [0x80, 0x0b]);
+
+        let parsed = Parser::new(&bytes).parse().expect("parse");
+        assert_eq!(
+            parsed,
+            vec![Instruction::ClaimSpace(InstructionClaimSpace { index: 3, resources })]
+        );
+    }
+}
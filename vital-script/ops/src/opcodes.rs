@@ -57,6 +57,9 @@ pub enum BasicOp {
     DMintShortVRC20,       // = 0x2e,
     DMintVRC20,            // = 0x2f,
     DMintVRC721,           // = 0x30,
+
+    MintLongName = 0x31, // = 0x31,
+    DMintLongName,       // = 0x32,
 }
 
 impl BasicOp {
@@ -101,6 +104,8 @@ impl BasicOp {
             0x2e => Ok(Self::DMintShortVRC20),
             0x2f => Ok(Self::DMintVRC20),
             0x30 => Ok(Self::DMintVRC721),
+            0x31 => Ok(Self::MintLongName),
+            0x32 => Ok(Self::DMintLongName),
 
             _ => bail!("not supported op {}", v),
         }
@@ -120,6 +125,18 @@ pub enum ExtensionOp {
     DeployVRC20S = 0x8002,
     DeployVRC20 = 0x8003,
     BurnResource = 0x8004,
+
+    DeployLongVRC20 = 0x8005,
+    MintLongVRC20 = 0x8006,
+    DMintLongVRC20 = 0x8007,
+    MoveAllLongVRC20 = 0x8008,
+    MoveLongVRC20 = 0x8009,
+    InputLongVRC20Assert = 0x800a,
+
+    ClaimSpace = 0x800b,
+
+    DeployVRC721 = 0x800c,
+    MintCollectionVRC721 = 0x800d,
 }
 
 impl ExtensionOp {
@@ -129,6 +146,15 @@ impl ExtensionOp {
             0x8002 => Ok(Self::DeployVRC20S),
             0x8003 => Ok(Self::DeployVRC20),
             0x8004 => Ok(Self::BurnResource),
+            0x8005 => Ok(Self::DeployLongVRC20),
+            0x8006 => Ok(Self::MintLongVRC20),
+            0x8007 => Ok(Self::DMintLongVRC20),
+            0x8008 => Ok(Self::MoveAllLongVRC20),
+            0x8009 => Ok(Self::MoveLongVRC20),
+            0x800a => Ok(Self::InputLongVRC20Assert),
+            0x800b => Ok(Self::ClaimSpace),
+            0x800c => Ok(Self::DeployVRC721),
+            0x800d => Ok(Self::MintCollectionVRC721),
 
             _ => bail!("not supported op {}", v),
         }
@@ -1,5 +1,5 @@
 use alloc::vec::Vec;
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, Context, Result};
 use bytes::{Buf, Bytes};
 
 use crate::{
@@ -8,6 +8,31 @@ use crate::{
     opcodes::{BasicOp, ExtensionOp},
 };
 
+/// The errors when parse the ops bytes, the opcode is the basic opcode as `u8`
+/// or the extension opcode as `u16`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The extension opcode need two bytes, but the ops bytes is ended.
+    InvalidExtensionOpcode,
+    /// The opcode is not defined.
+    UnknownOpcode(u16),
+    /// The operand for the opcode cannot be decoded.
+    InvalidOperand(u16),
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidExtensionOpcode => write!(f, "Invalid opcodes for extend opcode"),
+            Self::UnknownOpcode(op) => write!(f, "unknown opcode {:#x}", op),
+            Self::InvalidOperand(op) => write!(f, "invalid operand for opcode {:#x}", op),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
 pub struct Parser {
     datas: Bytes,
 }
@@ -17,6 +42,8 @@ impl Parser {
         Self { datas: Bytes::copy_from_slice(datas) }
     }
 
+    /// Parse all the instructions, if the bytes is invalid, will return a error which
+    /// can be downcast to `ParseError`.
     pub fn parse(&mut self) -> Result<Vec<Instruction>> {
         let mut res = Vec::with_capacity(16);
 
@@ -30,7 +57,7 @@ impl Parser {
                     .context("parse_basic_instruction")?
             } else {
                 if remaining < 1 {
-                    bail!("Invalid opcodes for extend opcode")
+                    return Err(anyhow!(ParseError::InvalidExtensionOpcode));
                 }
                 let opcodes_1 = self.datas.get_u8();
                 let opcodes = u16::from_be_bytes([opcodes_0, opcodes_1]);
@@ -47,21 +74,22 @@ impl Parser {
     }
 
     fn parse_basic_instruction(&mut self, _remaining: usize, opcode: u8) -> Result<Instruction> {
-        let opcode = BasicOp::new(opcode).context("basic op")?;
+        let op =
+            BasicOp::new(opcode).map_err(|_| anyhow!(ParseError::UnknownOpcode(opcode as u16)))?;
 
         macro_rules! decode_operand {
             ( $x:ident ) => {
-                BasicOp::decode_operand::<op_basic::$x>(&mut self.datas)?
+                BasicOp::decode_operand::<op_basic::$x>(&mut self.datas)
+                    .with_context(|| ParseError::InvalidOperand(opcode as u16))?
             };
         }
 
         macro_rules! decode_operands {
             ( $($x:ident),* ) => {
-                match opcode {
+                match op {
                     $(
                         BasicOp::$x => decode_operand!($x),
                     )*
-                    _ => panic!("Not supported opcode")
                 }
             }
         }
@@ -72,7 +100,7 @@ impl Parser {
             OutputIndexFlag32Assert,
             InputAssertShortName,
             InputAssertName,
-            // InputAssertLongName
+            InputAssertLongName,
             InputVRC20AssertSa32,
             InputVRC20AssertSa64,
             InputVRC20AssertSa128,
@@ -84,7 +112,7 @@ impl Parser {
             InputVRC721Assert,
             MoveShortName,
             MoveName,
-            // MoveLongName,
+            MoveLongName,
             MoveAllVRC20S,
             MoveAllVRC20,
             MoveVRC20Sa32,
@@ -100,7 +128,14 @@ impl Parser {
             MintName,
             MintShortVRC20,
             MintVRC20,
-            MintVRC721
+            MintVRC721,
+            DMintShortName,
+            DMintName,
+            DMintShortVRC20,
+            DMintVRC20,
+            DMintVRC721,
+            MintLongName,
+            DMintLongName
         );
 
         Ok(res)
@@ -108,32 +143,108 @@ impl Parser {
 
     fn parse_extend_instruction(&mut self, _remaining: usize, opcode: u16) -> Result<Instruction> {
         log::debug!("opcode {}", opcode);
-        let opcode = ExtensionOp::new(opcode).context("extension op")?;
+        let op =
+            ExtensionOp::new(opcode).map_err(|_| anyhow!(ParseError::UnknownOpcode(opcode)))?;
 
         macro_rules! decode_operand {
             ( $x:ident ) => {
-                ExtensionOp::decode_operand::<op_extension::$x>(&mut self.datas)?
+                ExtensionOp::decode_operand::<op_extension::$x>(&mut self.datas)
+                    .with_context(|| ParseError::InvalidOperand(opcode))?
             };
         }
 
         macro_rules! decode_operands {
             ( $($x:ident),* ) => {
-                match opcode {
+                match op {
                     $(
                         ExtensionOp::$x => decode_operand!($x),
                     )*
-                    _ => panic!("Not supported opcode")
                 }
             }
         }
 
         let res = decode_operands!(
-            // OutputIndexFlag64Assert,
+            OutputIndexFlag64Assert,
             DeployVRC20S,
             DeployVRC20,
-            BurnResource
+            BurnResource,
+            DeployLongVRC20,
+            MintLongVRC20,
+            DMintLongVRC20,
+            MoveAllLongVRC20,
+            MoveLongVRC20,
+            InputLongVRC20Assert,
+            ClaimSpace,
+            DeployVRC721,
+            MintCollectionVRC721
         );
 
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn parse_error(datas: &[u8]) -> ParseError {
+        let err = Parser::new(datas).parse().expect_err("should parse failed");
+
+        *err.downcast_ref::<ParseError>().expect("should be a parse error")
+    }
+
+    #[test]
+    fn test_parse_long_name_opcodes_by_invalid_operand_should_failed() {
+        // InputAssertLongName with a invalid len nibble
+        assert_eq!(
+            parse_error(&[0x0f, 0, 0, 0, 0, 0, 0, 0, 0x0b, 0x00]),
+            ParseError::InvalidOperand(0x0f)
+        );
+        // MoveLongName without the long name len
+        assert_eq!(
+            parse_error(&[0x1b, 0, 0, 0, 0, 0, 0, 0, 0x0f]),
+            ParseError::InvalidOperand(0x1b)
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_opcodes_should_failed() {
+        assert_eq!(parse_error(&[0x00]), ParseError::UnknownOpcode(0x00));
+        assert_eq!(parse_error(&[0x7f]), ParseError::UnknownOpcode(0x7f));
+        assert_eq!(parse_error(&[0x80, 0x00]), ParseError::UnknownOpcode(0x8000));
+        assert_eq!(parse_error(&[0x80]), ParseError::InvalidExtensionOpcode);
+
+        // the operand is not enough
+        assert_eq!(parse_error(&[0x0a]), ParseError::InvalidOperand(0x0a));
+        assert_eq!(parse_error(&[0x0c, 0x01, 0x02]), ParseError::InvalidOperand(0x0c));
+        assert_eq!(parse_error(&[0x27, 0x04, 0x20, 0xc4]), ParseError::InvalidOperand(0x27));
+        assert_eq!(parse_error(&[0x80, 0x04, 0x01]), ParseError::InvalidOperand(0x8004));
+    }
+
+    /// The ops bytes start with a opcode, so the operand will be decoded.
+    fn ops_bytes() -> impl Strategy<Value = Vec<u8>> {
+        let basic = (0x0a_u8..=0x32).prop_map(|op| vec![op]);
+        let extension = (0x8001_u16..=0x800d).prop_map(|op| op.to_be_bytes().to_vec());
+
+        (prop_oneof![basic, extension], proptest::collection::vec(any::<u8>(), 0..128))
+            .prop_map(|(op, operand)| [op, operand].concat())
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panic_by_random_bytes(
+            datas in proptest::collection::vec(any::<u8>(), 0..256)
+        ) {
+            let _ = Parser::new(&datas).parse();
+        }
+
+        #[test]
+        fn test_parse_never_panic_by_opcodes(
+            datas in proptest::collection::vec(ops_bytes(), 1..8).prop_map(|ops| ops.concat())
+        ) {
+            let _ = Parser::new(&datas).parse();
+        }
+    }
+}
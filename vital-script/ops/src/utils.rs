@@ -23,9 +23,11 @@ impl<'a> parity_scale_codec::Input for Reader<'a> {
         &mut self,
         into: &mut [u8],
     ) -> core::prelude::v1::Result<(), parity_scale_codec::Error> {
-        let len = core::cmp::min(self.datas.remaining(), into.len());
+        if self.datas.remaining() < into.len() {
+            return Err("Not enough data to fill buffer".into());
+        }
 
-        Buf::copy_to_slice(&mut self.datas, &mut into[0..len]);
+        Buf::copy_to_slice(&mut self.datas, into);
 
         Ok(())
     }
@@ -1,4 +1,62 @@
 //! The const values
 
+use bitcoin::Network;
+
 /// The max value for input index used by vital scripts.
 pub const MAX_INPUT_INDEX: u8 = 64;
+
+/// The max len for all the datas in the pushes of a vital script.
+pub const MAX_SCRIPT_DATAS_LEN: usize = 4096;
+
+/// The max len for a push in tapscript, the datas larger than it will be chunked.
+pub const MAX_PUSH_DATAS_LEN: usize = 520;
+
+/// The first protocol version, the uncosted input resources will be stored into the space.
+pub const PROTOCOL_VERSION_V1: u32 = 1;
+
+/// The uncosted input resources will be returned to the first asserted output, and the bworkc
+/// of the vrc20 should be a valid bitwork when deploy, its mints need match the bitwork.
+pub const PROTOCOL_VERSION_V2: u32 = 2;
+
+/// The protocol version before any activation, used by the mocks if not set.
+pub const PROTOCOL_VERSION: u32 = PROTOCOL_VERSION_V1;
+
+/// The block heights since which the protocol version 2 is activated in the networks,
+/// `u32::MAX` means it is not activated yet.
+pub const PROTOCOL_VERSION_V2_HEIGHT_BITCOIN: u32 = u32::MAX;
+pub const PROTOCOL_VERSION_V2_HEIGHT_TESTNET: u32 = u32::MAX;
+pub const PROTOCOL_VERSION_V2_HEIGHT_SIGNET: u32 = u32::MAX;
+pub const PROTOCOL_VERSION_V2_HEIGHT_REGTEST: u32 = 0;
+
+/// The protocol version to run the block at the height in the network, it is a consensus rule,
+/// so the indexers and the clients should all use it.
+pub fn protocol_version_at(network: Network, height: u32) -> u32 {
+    let v2_height = match network {
+        Network::Bitcoin => PROTOCOL_VERSION_V2_HEIGHT_BITCOIN,
+        Network::Testnet => PROTOCOL_VERSION_V2_HEIGHT_TESTNET,
+        Network::Signet => PROTOCOL_VERSION_V2_HEIGHT_SIGNET,
+        Network::Regtest => PROTOCOL_VERSION_V2_HEIGHT_REGTEST,
+        _ => u32::MAX,
+    };
+
+    if height >= v2_height {
+        PROTOCOL_VERSION_V2
+    } else {
+        PROTOCOL_VERSION_V1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_version_at() {
+        assert_eq!(protocol_version_at(Network::Regtest, 0), PROTOCOL_VERSION_V2);
+        assert_eq!(protocol_version_at(Network::Bitcoin, 0), PROTOCOL_VERSION_V1);
+        assert_eq!(
+            protocol_version_at(Network::Bitcoin, PROTOCOL_VERSION_V2_HEIGHT_BITCOIN - 1),
+            PROTOCOL_VERSION_V1
+        );
+    }
+}
@@ -0,0 +1,389 @@
+//! The long name type
+
+use alloc::string::String;
+use anyhow::{bail, Result};
+use parity_scale_codec::{Decode, Encode, EncodeLike, Error as CodecError, Input, Output};
+
+use crate::names::*;
+
+pub const LONG_NAME_LEN_MAX: usize = 32;
+
+/// The len nibble in the head for a long name, a name 's len is just for 0 - 10.
+const LONG_NAME_FLAG: u8 = 0x0f;
+
+/// The Long Name, which can hold all the names with len <= 32.
+///
+/// It use the same 6 bits chars as the name, but with a variable length encoding:
+///
+/// - if len <= 10, it is encoded as the same as the `Name`, so the long name is
+///   compatible with the name for the storage.
+/// - else the head is 8 bytes, which the first 10 chars encoded as the `Name`, but the len
+///   is `0x0f`. then 1 byte for the len, and the other chars by 6 bits:
+///
+/// |   8 bytes   |  u8  |      (len - 10) * 6 bits, padding to bytes       |
+/// |  0 .. 9 | f | len  | 10 | 11 | 12 | ...                                |
+#[derive(Default, Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
+pub struct LongName {
+    // Note the fields order is for the `Ord`, which will be the same as the `Name`.
+    values: [u8; LONG_NAME_LEN_MAX],
+    len: u8,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for LongName {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for LongName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct NameVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for NameVisitor {
+            type Value = LongName;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                formatter.write_str("vital long name resource")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                LongName::try_from(v)
+                    .map_err(|err| E::custom(format!("long name format error {}", err)))
+            }
+        }
+
+        deserializer.deserialize_str(NameVisitor)
+    }
+}
+
+impl core::fmt::Display for LongName {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for i in 0..self.len() {
+            write!(f, "{}", u8_to_char(self.index_value(i)).expect("the value should be valid"))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl LongName {
+    pub fn must_from(name_str: &str) -> Self {
+        Self::try_from(name_str)
+            .unwrap_or_else(|_| panic!("the {} must be long name format!", name_str))
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let length = self.len();
+        if length > LONG_NAME_LEN_MAX {
+            return false;
+        }
+
+        for (i, v) in self.values.iter().enumerate() {
+            let valid = if i < length { *v != 0 && *v <= VALUE_MAX } else { *v == 0 };
+            if !valid {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// If the name cannot be a `Name`.
+    pub fn is_long(&self) -> bool {
+        self.len() > NAME_LEN_MAX
+    }
+
+    pub fn push(&mut self, c: char) -> Result<()> {
+        let len = self.len();
+
+        if len >= LONG_NAME_LEN_MAX {
+            bail!("index invalid");
+        }
+
+        self.values[len] = char2u8(c)?;
+        self.len += 1;
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn index_value(&self, index: usize) -> u8 {
+        if index >= LONG_NAME_LEN_MAX {
+            return INVALID_VALUE;
+        }
+
+        self.values[index]
+    }
+
+    /// The bytes of head, which is the same as the `Name` if the len <= 10.
+    fn head(&self) -> [u8; NAME_BYTES_LEN] {
+        let mut head = [0_u8; NAME_BYTES_LEN];
+        pack_values(&self.values[..NAME_LEN_MAX], &mut head);
+
+        head[NAME_BYTES_LEN - 1] |= if self.is_long() { LONG_NAME_FLAG } else { self.len };
+
+        head
+    }
+
+    fn tail_bytes_len(&self) -> usize {
+        ((self.len() - NAME_LEN_MAX) * 6 + 7) / 8
+    }
+}
+
+/// Pack the 6 bits values into bytes, from the high bits to low bits.
+fn pack_values(values: &[u8], bytes: &mut [u8]) {
+    for (i, v) in values.iter().enumerate() {
+        let (start, shift) = ((i * 6) / 8, (i * 6) % 8);
+        let window = ((*v as u16) & 0x3f) << (10 - shift);
+
+        bytes[start] |= (window >> 8) as u8;
+        if let Some(b) = bytes.get_mut(start + 1) {
+            *b |= window as u8;
+        }
+    }
+}
+
+fn unpack_value(bytes: &[u8], i: usize) -> u8 {
+    let (start, shift) = ((i * 6) / 8, (i * 6) % 8);
+    let window = ((bytes[start] as u16) << 8) | bytes.get(start + 1).copied().unwrap_or(0) as u16;
+
+    ((window >> (10 - shift)) & 0x3f) as u8
+}
+
+impl Encode for LongName {
+    fn size_hint(&self) -> usize {
+        if self.is_long() {
+            NAME_BYTES_LEN + 1 + self.tail_bytes_len()
+        } else {
+            NAME_BYTES_LEN
+        }
+    }
+
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(&self.head());
+
+        if self.is_long() {
+            let mut tail = [0_u8; LONG_NAME_LEN_MAX];
+            let tail_len = self.tail_bytes_len();
+            pack_values(&self.values[NAME_LEN_MAX..self.len()], &mut tail[..tail_len]);
+
+            dest.push_byte(self.len);
+            dest.write(&tail[..tail_len]);
+        }
+    }
+}
+
+impl EncodeLike for LongName {}
+
+impl Decode for LongName {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let mut head = [0_u8; NAME_BYTES_LEN];
+        input.read(&mut head)?;
+
+        let mut res = LongName::default();
+        for i in 0..NAME_LEN_MAX {
+            res.values[i] = unpack_value(&head, i);
+        }
+
+        let flag = head[NAME_BYTES_LEN - 1] & 0x0f;
+        if flag as usize <= NAME_LEN_MAX {
+            res.len = flag;
+            return Ok(res);
+        }
+
+        if flag != LONG_NAME_FLAG {
+            return Err("invalid name len".into());
+        }
+
+        res.len = input.read_byte()?;
+        if !res.is_long() || res.len() > LONG_NAME_LEN_MAX {
+            return Err("invalid long name len".into());
+        }
+
+        let mut tail = [0_u8; LONG_NAME_LEN_MAX];
+        let tail_len = res.tail_bytes_len();
+        input.read(&mut tail[..tail_len])?;
+
+        for i in NAME_LEN_MAX..res.len() {
+            res.values[i] = unpack_value(&tail, i - NAME_LEN_MAX);
+        }
+
+        Ok(res)
+    }
+}
+
+impl TryFrom<&str> for LongName {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> core::result::Result<Self, Self::Error> {
+        if value.len() > LONG_NAME_LEN_MAX {
+            bail!("the string len too large");
+        }
+
+        let mut res = LongName::default();
+        for c in value.chars() {
+            res.push(c)?;
+        }
+
+        if !res.is_valid() {
+            bail!("the string not valid");
+        }
+
+        Ok(res)
+    }
+}
+
+impl TryFrom<String> for LongName {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
+    }
+}
+
+impl From<Name> for LongName {
+    fn from(value: Name) -> Self {
+        let mut res = LongName { len: value.len() as u8, ..Default::default() };
+        for i in 0..NAME_LEN_MAX {
+            res.values[i] = value.index_value(i);
+        }
+
+        res
+    }
+}
+
+impl From<ShortName> for LongName {
+    fn from(value: ShortName) -> Self {
+        Name::from(value).into()
+    }
+}
+
+impl TryFrom<LongName> for Name {
+    type Error = anyhow::Error;
+
+    fn try_from(value: LongName) -> Result<Self> {
+        if value.is_long() {
+            bail!("the name is too long")
+        }
+
+        Ok(Name::new(value.head()))
+    }
+}
+
+impl TryFrom<LongName> for ShortName {
+    type Error = anyhow::Error;
+
+    fn try_from(value: LongName) -> Result<Self> {
+        ShortName::try_from(Name::try_from(value)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    fn test_long_name_by(name: &str) {
+        let n = LongName::try_from(name).unwrap_or_else(|_| panic!("try from {}", name));
+
+        assert_eq!(n.to_string(), name);
+        assert_eq!(n.len(), name.len());
+        assert!(n.is_valid());
+
+        let encoded = n.encode();
+        assert_eq!(encoded.len(), n.size_hint());
+        assert_eq!(LongName::decode(&mut encoded.as_slice()).expect("decode"), n);
+
+        if name.len() <= NAME_LEN_MAX {
+            let name = Name::try_from(name).expect("name");
+
+            assert_eq!(encoded, name.0.to_vec(), "should be the same as the name");
+            assert_eq!(Name::try_from(n).expect("name"), name);
+            assert_eq!(LongName::from(name), n);
+        } else {
+            assert!(Name::try_from(n).is_err());
+        }
+    }
+
+    #[test]
+    fn test_long_name_new() {
+        test_long_name_by("");
+        test_long_name_by("a");
+        test_long_name_by("abcde");
+        test_long_name_by("abcdefghij");
+        test_long_name_by("**********");
+        test_long_name_by("abcdefghijk");
+        test_long_name_by("abcdefghijkl");
+        test_long_name_by("abcdefghijklmn");
+        test_long_name_by("klmnopqrstuvwxyz0123");
+        test_long_name_by("4567890@-_.!*abcdefghijklmnopqrs");
+        test_long_name_by("********************************");
+    }
+
+    #[test]
+    fn test_long_name_new_failed() {
+        assert!(LongName::try_from("a a").is_err());
+        assert!(LongName::try_from("abcdefghijklmn(").is_err());
+        assert!(LongName::try_from("a".repeat(LONG_NAME_LEN_MAX + 1)).is_err());
+    }
+
+    #[test]
+    fn test_long_name_encode() {
+        let name = LongName::must_from("abcdefghijkl");
+        let encoded = name.encode();
+
+        // the head with 0x0f, the len and 2 chars by 6 bits.
+        assert_eq!(encoded.len(), 8 + 1 + 2);
+        assert_eq!(encoded[7] & 0x0f, 0x0f);
+        assert_eq!(encoded[8], 12);
+        assert_eq!(encoded[9..], [11 << 2, 12 << 4]);
+
+        // the len nibble 11 - 14 is invalid
+        let mut invalid = encoded.clone();
+        invalid[7] = (invalid[7] & 0xf0) | 0x0b;
+        assert!(LongName::decode(&mut invalid.as_slice()).is_err());
+
+        // the len byte should > 10
+        let mut invalid = encoded.clone();
+        invalid[8] = 10;
+        assert!(LongName::decode(&mut invalid.as_slice()).is_err());
+
+        // not enough bytes
+        assert!(LongName::decode(&mut &encoded[..10]).is_err());
+    }
+
+    #[test]
+    fn test_long_name_ord_same_as_name() {
+        let names = ["", "a", "aa", "ab", "b", "abcdefghij", "abcdefghi", "z", "0", "*"];
+
+        let mut by_name = names.iter().map(|n| Name::must_from(n)).collect::<Vec<_>>();
+        let mut by_long_name = names.iter().map(|n| LongName::must_from(n)).collect::<Vec<_>>();
+
+        by_name.sort();
+        by_long_name.sort();
+
+        assert_eq!(by_name.into_iter().map(LongName::from).collect::<Vec<_>>(), by_long_name);
+    }
+}
@@ -5,9 +5,11 @@
 //! a-z : 1 ~ 26
 //! 0-9 : 27 ~ 36
 
+mod long_name;
 mod name;
 mod short_name;
 
+pub use long_name::*;
 pub use name::*;
 pub use short_name::*;
 
@@ -81,6 +83,7 @@ fn u8_to_char(v: u8) -> Result<char> {
 pub enum NameOrShort {
     Name(Name),
     ShortName(ShortName),
+    LongName(LongName),
 }
 
 #[cfg(feature = "std")]
@@ -95,8 +98,11 @@ impl NameOrShort {
         } else if name.len() <= NAME_LEN_MAX {
             let name = Name::try_from(name).context("the name format not valid")?;
             NameOrShort::Name(name)
+        } else if name.len() <= LONG_NAME_LEN_MAX {
+            let name = LongName::try_from(name).context("the name format not valid")?;
+            NameOrShort::LongName(name)
         } else {
-            bail!("not support long name with length {}, need <= {}", name.len(), NAME_LEN_MAX);
+            bail!("not support name with length {}, need <= {}", name.len(), LONG_NAME_LEN_MAX);
         };
 
         Ok(res)
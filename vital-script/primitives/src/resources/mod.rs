@@ -9,7 +9,7 @@ use primitive_types::U256;
 
 use parity_scale_codec::{Decode, Encode};
 
-pub use crate::names::Name;
+pub use crate::names::{LongName, Name};
 use crate::{names::ShortName, H256};
 
 pub mod vrc20;
@@ -18,7 +18,8 @@ pub mod vrc721;
 pub use vrc20::*;
 pub use vrc721::*;
 
-pub type Tag = Name;
+/// The tag for the name and vrc20, a name with len <= 10 will be encoded the same as `Name`.
+pub type Tag = LongName;
 
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 #[repr(u16)]
@@ -47,7 +48,7 @@ impl fmt::Display for ResourceType {
                 write!(f, "vrc20({})", name)
             }
             ResourceType::VRC721 { hash } => {
-                write!(f, "vrc721({})", hash)
+                write!(f, "vrc721({:?})", hash)
             }
         }
     }
@@ -83,7 +84,7 @@ impl ResourceType {
 #[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Resource {
-    Name(Name),
+    Name(Tag),
     VRC20(VRC20),
     VRC721(VRC721),
 }
@@ -106,13 +107,13 @@ impl fmt::Display for Resource {
 
 impl Default for Resource {
     fn default() -> Self {
-        Self::Name(Name::default())
+        Self::Name(Tag::default())
     }
 }
 
 impl Resource {
     pub fn vrc20(name: impl Into<String>, amount: U256) -> Result<Self> {
-        let name = Name::try_from(name.into())?;
+        let name = Tag::try_from(name.into())?;
 
         Ok(Self::VRC20(VRC20::new(name, amount)))
     }
@@ -174,6 +175,12 @@ impl From<ShortName> for Resource {
 
 impl From<Name> for Resource {
     fn from(value: Name) -> Self {
+        Self::Name(value.into())
+    }
+}
+
+impl From<LongName> for Resource {
+    fn from(value: LongName) -> Self {
         Self::Name(value)
     }
 }
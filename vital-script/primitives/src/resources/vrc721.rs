@@ -1,6 +1,10 @@
 //! The VRC721 Token
+//!
+//! The hash of a VRC721 is the sha256 of its content, so a VRC721 can be
+//! verified by the bytes of the content.
 
 use crate::H256;
+use bitcoin::hashes::{sha256, Hash};
 use parity_scale_codec::{Decode, Encode};
 
 #[derive(Default, Clone, Encode, Decode, Debug, PartialOrd, Ord, PartialEq, Eq)]
@@ -12,7 +16,7 @@ pub struct VRC721 {
 
 impl core::fmt::Display for VRC721 {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "VRC721[{}]", self.hash)
+        write!(f, "VRC721[{:?}]", self.hash)
     }
 }
 
@@ -20,4 +24,45 @@ impl VRC721 {
     pub fn new(hash: H256) -> Self {
         Self { hash }
     }
+
+    /// Create the VRC721 by the content, its hash is the sha256 of the content.
+    pub fn from_content(content: &[u8]) -> Self {
+        Self::new(content_hash(content))
+    }
+
+    /// If the content is the one the VRC721 committed to.
+    pub fn is_content(&self, content: &[u8]) -> bool {
+        self.hash == content_hash(content)
+    }
+}
+
+/// The hash for the content of VRC721, just the sha256.
+pub fn content_hash(content: &[u8]) -> H256 {
+    H256::from(sha256::Hash::hash(content).to_byte_array())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vrc721_from_content() {
+        let vrc721 = VRC721::from_content(b"abc");
+
+        // the sha256 of "abc"
+        assert_eq!(
+            format!("{:?}", vrc721.hash),
+            "0xba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        assert!(vrc721.is_content(b"abc"));
+        assert!(!vrc721.is_content(b"abd"));
+        assert!(!VRC721::default().is_content(b""));
+
+        // should show the full hash, so it can be used to move the vrc721.
+        assert_eq!(
+            vrc721.to_string(),
+            "VRC721[0xba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad]"
+        );
+    }
 }
@@ -1,13 +1,17 @@
 use alloc::vec::Vec;
 
-use anyhow::{bail, Context as AnyhowContext, Result};
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
 use bitcoin::{OutPoint, Txid};
 use parity_scale_codec::{Decode, Encode};
-use primitive_types::H256;
+use primitive_types::{H256, U256};
 
 use crate::{
+    consts::{PROTOCOL_VERSION_V1, PROTOCOL_VERSION_V2},
     resources::{Resource, Tag},
-    types::vrc20::{VRC20MetaData, VRC20StatusData},
+    types::{
+        vrc20::{VRC20MetaData, VRC20StatusData},
+        vrc721::{VRC721MetaData, VRC721StatusData},
+    },
 };
 
 #[repr(u8)]
@@ -16,6 +20,9 @@ pub enum MetaDataType {
     Name = 1_u8,
     VRC20,
     VRC721,
+    /// The burned amount of the vrc20, it is not in `VRC20StatusData` to keep the status
+    /// records stored before compatible.
+    VRC20Burned,
 }
 
 pub trait EnvContext {
@@ -25,6 +32,10 @@ pub trait EnvContext {
     /// get current tx id.
     fn get_reveal_tx_id(&self) -> &Txid;
 
+    /// If the commit tx of the script in the input had been confirmed in a block before
+    /// current block, each script is spent from its own commit output.
+    fn is_commit_in_prev_blocks(&self, input_index: u8) -> bool;
+
     /// Get the output 's point by the index for current tx.
     fn get_output(&self, output_index: u8) -> OutPoint {
         OutPoint { txid: *self.get_reveal_tx_id(), vout: output_index as u32 }
@@ -32,6 +43,9 @@ pub trait EnvContext {
 
     fn get_ops(&self) -> &[(u8, Vec<u8>)];
 
+    /// Get the outpoint spent by the input for current tx.
+    fn get_input(&self, input_index: u8) -> Result<OutPoint>;
+
     fn get_input_resource(&self, index: u8) -> Result<Resource>;
     fn get_output_resource(&self, index: u8) -> Option<&Resource>;
 
@@ -43,9 +57,19 @@ pub trait EnvContext {
     /// Apply changes to indexer, will do:
     ///   - del all inputs 's resources bind
     ///   - set all outputs 's resources bind
-    ///   - storage all uncosted inputs 's resources to space.
     fn apply_output_resources(&mut self) -> Result<()>;
 
+    /// Storage the resources into the space of current tx,
+    /// the space can be claimed by the tx which spend the first output of current tx.
+    fn set_space_resources(&mut self, resources: Vec<Resource>) -> Result<()>;
+
+    /// Get the resources in the space of the tx, empty if no space or had been claimed.
+    fn get_space_resources(&self, txid: &Txid) -> Result<Vec<Resource>>;
+
+    /// Claim all the resources in the space by the input, the input should spend the
+    /// first output of the tx which own the space, after claimed the space will be empty.
+    fn claim_space_resources(&mut self, input_index: u8) -> Result<Vec<Resource>>;
+
     fn new_name(&mut self, name: Tag) -> Result<()> {
         // log::debug()
 
@@ -80,9 +104,7 @@ pub trait EnvContext {
             bail!("the vrc20 had created");
         }
 
-        // set to false, means it not costed
-        self.set_vrc20_metadata(name, VRC20StatusData { mint_count: 0, meta })
-            .context("set")
+        self.set_vrc20_metadata(name, VRC20StatusData::new(meta)).context("set")
     }
 
     fn increase_vrc20_mint_count(&mut self, name: Tag) -> Result<()> {
@@ -101,6 +123,25 @@ pub trait EnvContext {
         Ok(())
     }
 
+    fn increase_vrc20_burned(&mut self, name: Tag, amount: U256) -> Result<()> {
+        if self.get_vrc20_metadata(name).context("get")?.is_none() {
+            bail!("the vrc20 had not created");
+        }
+
+        let burned = self
+            .get_vrc20_burned(name)
+            .context("get burned")?
+            .checked_add(amount)
+            .ok_or_else(|| anyhow!("burned amount overflow"))?;
+
+        self.set_metadata(name, MetaDataType::VRC20Burned, burned).context("set")
+    }
+
+    /// The total amount burned of the vrc20, zero if nothing burned.
+    fn get_vrc20_burned(&self, name: Tag) -> Result<U256> {
+        Ok(self.get_metadata(name, MetaDataType::VRC20Burned)?.unwrap_or_default())
+    }
+
     fn get_vrc20_metadata(&self, name: Tag) -> Result<Option<VRC20StatusData>> {
         self.get_metadata(name, MetaDataType::VRC20)
     }
@@ -109,27 +150,99 @@ pub trait EnvContext {
         self.set_metadata(name, MetaDataType::VRC20, meta)
     }
 
-    fn mint_vrc721(&mut self, hash: H256) -> Result<()>;
+    fn deploy_vrc721(&mut self, name: Tag, meta: VRC721MetaData) -> Result<()> {
+        let curr = self.get_vrc721_metadata(name).context("get")?;
+        if curr.is_some() {
+            bail!("the vrc721 collection had created");
+        }
+
+        self.set_vrc721_metadata(name, VRC721StatusData { mint_count: 0, meta })
+            .context("set")
+    }
+
+    fn increase_vrc721_mint_count(&mut self, name: Tag) -> Result<()> {
+        let status_data = self.get_vrc721_metadata(name).context("get")?;
+        if let Some(mut status_data) = status_data {
+            if status_data.mint_count >= status_data.meta.max_supply {
+                bail!("mint count had reached max supply");
+            }
+
+            status_data.mint_count += 1;
+            self.set_vrc721_metadata(name, status_data).context("set")?;
+        } else {
+            bail!("the vrc721 collection had not created");
+        }
+
+        Ok(())
+    }
+
+    fn get_vrc721_metadata(&self, name: Tag) -> Result<Option<VRC721StatusData>> {
+        self.get_metadata(name, MetaDataType::VRC721)
+    }
+
+    fn set_vrc721_metadata(&mut self, name: Tag, meta: VRC721StatusData) -> Result<()> {
+        self.set_metadata(name, MetaDataType::VRC721, meta)
+    }
+
+    /// Mint the vrc721 by the hash, if it in a collection, record the collection for it.
+    fn mint_vrc721(&mut self, hash: H256, collection: Option<Tag>) -> Result<()>;
     fn vrc721_had_mint(&self, hash: H256) -> Result<bool>;
+    /// Get the collection of the vrc721, None if it not in a collection.
+    fn get_vrc721_collection(&self, hash: H256) -> Result<Option<Tag>>;
 
     fn set_metadata<T: Encode>(&mut self, name: Tag, typ: MetaDataType, meta: T) -> Result<()>;
     fn get_metadata<T: Decode>(&self, name: Tag, typ: MetaDataType) -> Result<Option<T>>;
 }
 
+/// The state for running the scripts in a tx, the scripts run in the order of their inputs.
+///
+/// An input or an output can only be asserted once in the tx, but the asserted outputs and
+/// the mint are isolated by the script: a script can only send resources to the outputs
+/// asserted by itself, and each script can mint once. The input resources are shared by all
+/// the scripts, the uncosted ones will be handled after all the scripts run.
 pub trait RunnerContext {
+    /// Enter the script in the input, the outputs asserted by the scripts before will not be
+    /// available for it.
+    fn enter_script(&mut self, input_index: u8);
+    /// Exit the current script, after all the scripts exited, all the asserted outputs are
+    /// available, such as return the uncosted resources.
+    fn exit_script(&mut self);
+    /// The input index of the script running, None if not in any script.
+    fn current_script(&self) -> Option<u8>;
+
     fn try_assert_input(&mut self, index: u8) -> Result<()>;
     fn try_assert_output(&mut self, index: u8) -> Result<()>;
     fn is_output_available(&self, index: u8) -> bool;
+    /// The min index of the asserted outputs.
+    fn first_asserted_output(&self) -> Option<u8>;
     fn try_mint(&mut self) -> Result<()>;
 }
 
 pub trait InputResourcesContext {
     fn push(&mut self, input_index: u8, resource: Resource) -> Result<()>;
+    /// Push the resource claimed from a space by the input, it is not bind to the input,
+    /// so the input will not be unbind for it.
+    fn push_claimed(&mut self, input_index: u8, resource: Resource) -> Result<()>;
     fn cost(&mut self, resource: &Resource) -> Result<()>;
+    /// Take the input indexs costed since the last take, by the order of costed.
+    fn take_costed(&mut self) -> Vec<u8>;
 
     fn all(&self) -> &[u8];
     fn uncosted(&self) -> Vec<(u8, Resource)>;
 
+    /// The uncosted resources, the vrc20s with the same name will be merged into one.
+    fn uncosted_merged(&self) -> Vec<Resource> {
+        let mut res: Vec<Resource> = Vec::new();
+
+        for (_, resource) in self.uncosted() {
+            if !res.iter_mut().any(|r| r.merge(&resource).is_ok()) {
+                res.push(resource);
+            }
+        }
+
+        res
+    }
+
     fn get_uncosted_vrc20(&self, name: Tag) -> Option<Resource>;
 }
 
@@ -156,6 +269,26 @@ impl RunMode {
     }
 }
 
+/// The policy for the input resources which not costed by the script.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UncostedPolicy {
+    /// Storage them into the space of the reveal tx, which can be claimed later.
+    #[default]
+    Space,
+    /// Send them to the first asserted output, if cannot, the tx will failed.
+    ReturnToFirstOutput,
+}
+
+impl UncostedPolicy {
+    pub fn by_protocol_version(version: u32) -> Result<Self> {
+        match version {
+            PROTOCOL_VERSION_V1 => Ok(Self::Space),
+            PROTOCOL_VERSION_V2 => Ok(Self::ReturnToFirstOutput),
+            _ => bail!("unknown protocol version {}", version),
+        }
+    }
+}
+
 pub trait Context {
     type Env: EnvContext;
     type Runner: RunnerContext;
@@ -171,17 +304,49 @@ pub trait Context {
     fn input_resource_mut(&mut self) -> &mut Self::InputResource;
 
     fn get_ops(&self) -> &[(u8, Vec<u8>)];
-    fn get_instructions(&self) -> Result<Vec<Self::Instruction>>;
+    /// Get the instructions of each script by its input index, in the order of the inputs.
+    fn get_instructions(&self) -> Result<Vec<(u8, Vec<Self::Instruction>)>>;
     fn pre_check(&self) -> Result<()>;
     fn post_check(&self) -> Result<()>;
 
     fn run_mod(&self) -> RunMode;
 
+    fn uncosted_policy(&self) -> UncostedPolicy {
+        UncostedPolicy::default()
+    }
+
+    /// The protocol version to run the scripts, the rules added later are enabled by it.
+    fn protocol_version(&self) -> u32 {
+        PROTOCOL_VERSION_V1
+    }
+
     /// Apply changes to indexer, will do:
+    ///   - handle all uncosted inputs 's resources by the `UncostedPolicy`
     ///   - del all inputs 's resources bind
     ///   - set all outputs 's resources bind
-    ///   - storage all uncosted inputs 's resources to space.
     fn apply_resources(&mut self) -> Result<()> {
+        let uncosted = self.input_resource().uncosted_merged();
+
+        if !uncosted.is_empty() {
+            match self.uncosted_policy() {
+                UncostedPolicy::ReturnToFirstOutput => {
+                    let index = self
+                        .runner()
+                        .first_asserted_output()
+                        .ok_or_else(|| anyhow!("no output to return the uncosted resources"))?;
+
+                    for resource in uncosted {
+                        self.send_resource_to_output(index, resource).context("return")?;
+                    }
+                }
+                UncostedPolicy::Space => {
+                    if !self.run_mod().is_skip_check() {
+                        self.env_mut().set_space_resources(uncosted).context("space")?;
+                    }
+                }
+            }
+        }
+
         if !self.run_mod().is_skip_check() {
             // del all inputs 's resources bind
             let all = self.input_resource().all().to_vec();
@@ -191,9 +356,6 @@ pub trait Context {
             self.env_mut().apply_output_resources().context("apply")?;
         }
 
-        // storage all uncosted inputs 's resources to space.
-        // TODO: impl
-
         Ok(())
     }
 
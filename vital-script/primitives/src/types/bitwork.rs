@@ -0,0 +1,151 @@
+//! The bitwork for vrc20 mint.
+//!
+//! The `bworkc` in vrc20 metadata is a u64 like:
+//!
+//! | 8 bits              | 56 bits         |
+//! | prefix len (nibble) | prefix value    |
+//!
+//! A mint for the vrc20 need the reveal txid start with the prefix in hex, the same as
+//! it show in explorer. If the prefix len is 0, the mint not need any work.
+//!
+//! For example, `0x04000000_0000abcd` need the txid like `abcd....`.
+
+use anyhow::{bail, Result};
+use bitcoin::{hashes::Hash, Txid};
+
+/// The max count of hex chars in the prefix, the nonce to mine the reveal tx is its input 's
+/// sequence which has about 2^32 values, so the prefix should be at most 28 bits to make sure
+/// the nonce can be found.
+pub const BITWORK_PREFIX_LEN_MAX: u8 = 7;
+
+const PREFIX_LEN_SHIFT: u32 = 56;
+const PREFIX_VALUE_MASK: u64 = (1 << PREFIX_LEN_SHIFT) - 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bitwork {
+    len: u8,
+    prefix: u64,
+}
+
+impl Bitwork {
+    pub fn new(len: u8, prefix: u64) -> Result<Self> {
+        if len == 0 {
+            bail!("the bitwork prefix should not be empty");
+        }
+
+        if len > BITWORK_PREFIX_LEN_MAX {
+            bail!("the bitwork prefix too long");
+        }
+
+        if prefix >> (4 * len as u32) != 0 {
+            bail!("the bitwork prefix value overflow");
+        }
+
+        Ok(Self { len, prefix })
+    }
+
+    /// Parse from the `bworkc` in vrc20 metadata, return None if not need work.
+    pub fn from_bworkc(bworkc: u64) -> Result<Option<Self>> {
+        let len = (bworkc >> PREFIX_LEN_SHIFT) as u8;
+        if len == 0 {
+            return Ok(None);
+        }
+
+        Self::new(len, bworkc & PREFIX_VALUE_MASK).map(Some)
+    }
+
+    /// Parse from the hex chars, such as `0000` or `abc`.
+    pub fn from_hex_prefix(prefix: &str) -> Result<Self> {
+        if prefix.len() > BITWORK_PREFIX_LEN_MAX as usize {
+            bail!("the bitwork prefix too long");
+        }
+
+        let mut value = 0_u64;
+        for c in prefix.chars() {
+            let Some(v) = c.to_digit(16) else {
+                bail!("the bitwork prefix should be hex chars");
+            };
+
+            value = (value << 4) | v as u64;
+        }
+
+        Self::new(prefix.len() as u8, value)
+    }
+
+    pub fn to_bworkc(&self) -> u64 {
+        ((self.len as u64) << PREFIX_LEN_SHIFT) | self.prefix
+    }
+
+    /// The count of hex chars in the prefix.
+    pub fn prefix_len(&self) -> u8 {
+        self.len
+    }
+
+    /// Check the txid, the bytes is in the order as `Txid::to_byte_array`,
+    /// which is reversed from the hex string of txid.
+    pub fn is_match(&self, txid_bytes: &[u8; 32]) -> bool {
+        (0..self.len as usize).all(|i| {
+            let byte = txid_bytes[31 - i / 2];
+            let nibble = if i % 2 == 0 { byte >> 4 } else { byte & 0x0f };
+            let expected = (self.prefix >> (4 * (self.len as usize - 1 - i))) & 0x0f;
+
+            nibble as u64 == expected
+        })
+    }
+
+    pub fn is_match_txid(&self, txid: &Txid) -> bool {
+        self.is_match(&txid.to_byte_array())
+    }
+}
+
+impl core::fmt::Display for Bitwork {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:0width$x}", self.prefix, width = self.len as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn txid_bytes(hex_str: &str) -> [u8; 32] {
+        let mut res = [0_u8; 32];
+        for i in 0..32 {
+            res[31 - i] = u8::from_str_radix(&hex_str[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        res
+    }
+
+    #[test]
+    fn test_bworkc_encode_and_decode() {
+        assert_eq!(Bitwork::from_bworkc(0).unwrap(), None);
+        assert_eq!(Bitwork::from_bworkc(1000000).unwrap(), None);
+
+        let bitwork = Bitwork::from_hex_prefix("00ab").unwrap();
+        assert_eq!(bitwork.to_bworkc(), 0x04000000_000000ab);
+        assert_eq!(Bitwork::from_bworkc(bitwork.to_bworkc()).unwrap(), Some(bitwork));
+        assert_eq!(bitwork.to_string(), "00ab");
+
+        assert!(Bitwork::from_bworkc(0x01000000_00000010).is_err());
+        assert!(Bitwork::from_bworkc(0x0f000000_00000000).is_err());
+        assert!(Bitwork::from_bworkc(0x08000000_00000000).is_err());
+        assert_eq!(Bitwork::from_hex_prefix("0000000").unwrap().to_bworkc(), 0x07000000_00000000);
+        assert!(Bitwork::from_hex_prefix("").is_err());
+        assert!(Bitwork::from_hex_prefix("xyz").is_err());
+        assert!(Bitwork::from_hex_prefix("00000000").is_err());
+    }
+
+    #[test]
+    fn test_bitwork_match() {
+        let txid = txid_bytes("00abc3e1f0cfc6c4a2d2b4b7d2a6e6b10e2d4af7d6a3f7f57b6f1e4fa2a1a3b9");
+
+        assert!(Bitwork::from_hex_prefix("0").unwrap().is_match(&txid));
+        assert!(Bitwork::from_hex_prefix("00").unwrap().is_match(&txid));
+        assert!(Bitwork::from_hex_prefix("00abc").unwrap().is_match(&txid));
+        assert!(Bitwork::from_hex_prefix("00ABC3").unwrap().is_match(&txid));
+
+        assert!(!Bitwork::from_hex_prefix("000").unwrap().is_match(&txid));
+        assert!(!Bitwork::from_hex_prefix("00abd").unwrap().is_match(&txid));
+        assert!(!Bitwork::from_hex_prefix("1").unwrap().is_match(&txid));
+    }
+}
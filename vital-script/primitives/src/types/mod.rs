@@ -4,7 +4,9 @@ use alloc::vec::Vec;
 
 use parity_scale_codec::{Decode, Encode};
 
+pub mod bitwork;
 pub mod vrc20;
+pub mod vrc721;
 
 #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
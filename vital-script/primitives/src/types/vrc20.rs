@@ -1,4 +1,5 @@
 use parity_scale_codec::{Decode, Encode};
+use primitive_types::U256;
 
 use super::MetaData;
 
@@ -29,3 +30,20 @@ pub struct VRC20StatusData {
     pub mint_count: u64,
     pub meta: VRC20MetaData,
 }
+
+impl VRC20StatusData {
+    pub fn new(meta: VRC20MetaData) -> Self {
+        Self { mint_count: 0, meta }
+    }
+
+    /// The total amount minted, by the mint count and the amount for each mint
+    pub fn minted(&self) -> U256 {
+        U256::from(self.mint_count) * U256::from(self.meta.mint.mint_amount)
+    }
+
+    /// The amount minted but not burned, the burned amount is stored apart from the status,
+    /// see `EnvContext::get_vrc20_burned`.
+    pub fn circulating(&self, burned: U256) -> U256 {
+        self.minted().saturating_sub(burned)
+    }
+}
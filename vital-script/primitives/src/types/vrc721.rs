@@ -0,0 +1,25 @@
+use parity_scale_codec::{Decode, Encode};
+
+use super::MetaData;
+
+/// The base of the royalty, the royalty is in parts per ten thousand.
+pub const ROYALTY_BASE: u16 = 10000;
+
+/// The meta data for vrc721 collection
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VRC721MetaData {
+    /// The max count of the vrc721s can be minted in the collection.
+    pub max_supply: u64,
+    /// The royalty for the creator, in parts per `ROYALTY_BASE`.
+    pub royalty: u16,
+    pub meta: Option<MetaData>,
+}
+
+/// The status data for vrc721 collection
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VRC721StatusData {
+    pub mint_count: u64,
+    pub meta: VRC721MetaData,
+}
@@ -1,4 +1,7 @@
-use alloc::{collections::BTreeMap, vec::Vec};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
 use anyhow::{anyhow, bail, Context, Result};
 use bitcoin::{hashes::Hash, OutPoint, Transaction, Txid};
 use parity_scale_codec::{Decode, Encode};
@@ -15,6 +18,8 @@ use super::script::parse_vital_scripts;
 
 const STORAGE_KEY_METADATA: &[u8; 8] = b"metadata";
 const STORAGE_KEY_VRC721: &[u8; 6] = b"vrc721";
+const STORAGE_KEY_COLLECTION: &[u8; 10] = b"collection";
+const STORAGE_KEY_SPACE: &[u8; 5] = b"space";
 
 #[derive(Clone)]
 pub struct EnvContext<Functions: EnvFunctions> {
@@ -27,6 +32,9 @@ pub struct EnvContext<Functions: EnvFunctions> {
     reveal_tx_id: Txid,
     inputs: Vec<OutPoint>,
 
+    /// The input indexes of the scripts whose commit tx is in current block.
+    commits_in_curr_block: BTreeSet<u8>,
+
     ops: Vec<(u8, Vec<u8>)>,
 
     /// The outputs need to bind to outputs.
@@ -48,6 +56,7 @@ impl<Functions: EnvFunctions> EnvContext<Functions> {
             env: env_interface,
             inputs,
             reveal_tx_id,
+            commits_in_curr_block: BTreeSet::new(),
             block_height,
             ops,
             cached_output_resources: BTreeMap::new(),
@@ -67,22 +76,23 @@ impl<Functions: EnvFunctions> EnvContext<Functions> {
             env: env_interface,
             inputs: Default::default(),
             reveal_tx_id: Txid::all_zeros(),
+            commits_in_curr_block: BTreeSet::new(),
             ops: Default::default(),
             cached_output_resources: BTreeMap::new(),
             block_height,
         }
     }
 
-    fn get_input(&self, input_index: u8) -> Result<OutPoint> {
-        let res = self
-            .inputs
-            .get(input_index as usize)
-            .ok_or_else(|| anyhow!("not found input"))?;
-
-        Ok(*res)
+    pub fn set_commits_in_curr_block(&mut self, input_indexes: BTreeSet<u8>) {
+        self.commits_in_curr_block = input_indexes;
     }
 }
 
+/// The storage key for the space of the tx.
+pub(crate) fn space_key(txid: &Txid) -> Vec<u8> {
+    [STORAGE_KEY_SPACE.to_vec(), txid.to_byte_array().to_vec()].concat()
+}
+
 impl<Functions: EnvFunctions> EnvContextT for EnvContext<Functions> {
     fn get_block_height(&self) -> u32 {
         self.block_height
@@ -93,10 +103,23 @@ impl<Functions: EnvFunctions> EnvContextT for EnvContext<Functions> {
         &self.reveal_tx_id
     }
 
+    fn is_commit_in_prev_blocks(&self, input_index: u8) -> bool {
+        !self.commits_in_curr_block.contains(&input_index)
+    }
+
     fn get_ops(&self) -> &[(u8, Vec<u8>)] {
         &self.ops
     }
 
+    fn get_input(&self, input_index: u8) -> Result<OutPoint> {
+        let res = self
+            .inputs
+            .get(input_index as usize)
+            .ok_or_else(|| anyhow!("not found input"))?;
+
+        Ok(*res)
+    }
+
     fn get_input_resource(&self, index: u8) -> Result<Resource> {
         log::debug!(target: TARGET, "get_input_resource {}", index);
 
@@ -160,14 +183,59 @@ impl<Functions: EnvFunctions> EnvContextT for EnvContext<Functions> {
         Ok(())
     }
 
-    fn mint_vrc721(&mut self, hash: H256) -> Result<()> {
-        log::debug!(target: TARGET, "mint_vrc721 {:?}", hash);
+    fn set_space_resources(&mut self, resources: Vec<Resource>) -> Result<()> {
+        log::debug!(target: TARGET, "set_space_resources {} {:?}", self.reveal_tx_id, resources);
+
+        self.env
+            .storage_set(space_key(&self.reveal_tx_id), resources.encode())
+            .context("set space failed")
+    }
+
+    fn get_space_resources(&self, txid: &Txid) -> Result<Vec<Resource>> {
+        let value = self.env.storage_get(&space_key(txid)).context("get space failed")?;
+
+        value
+            .map(|datas| Vec::<Resource>::decode(&mut datas.as_slice()))
+            .transpose()
+            .map_err(|err| anyhow!("decode failed by {:?}", err))
+            .map(|res| res.unwrap_or_default())
+    }
+
+    fn claim_space_resources(&mut self, input_index: u8) -> Result<Vec<Resource>> {
+        let out_point = self.get_input(input_index).context("get input")?;
+        log::debug!(target: TARGET, "claim_space_resources by {}", out_point);
+
+        if out_point.vout != 0 {
+            bail!("the space can only be claimed by the first output");
+        }
+
+        let res = self.get_space_resources(&out_point.txid).context("get space")?;
+        if res.is_empty() {
+            bail!("the space is empty");
+        }
+
+        self.env
+            .storage_remove(&space_key(&out_point.txid))
+            .context("clear space failed")?;
+
+        Ok(res)
+    }
+
+    fn mint_vrc721(&mut self, hash: H256, collection: Option<Tag>) -> Result<()> {
+        log::debug!(target: TARGET, "mint_vrc721 {:?} in {:?}", hash, collection);
 
         let key = [STORAGE_KEY_VRC721.to_vec(), hash.0.to_vec()].concat();
         let value = true.encode();
 
         self.env.storage_set(key, value).context("set failed")?;
 
+        if let Some(collection) = collection {
+            let key = [STORAGE_KEY_COLLECTION.to_vec(), hash.0.to_vec()].concat();
+            self.env
+                .storage_set(key, collection.encode())
+                .context("set collection failed")?;
+        }
+
         Ok(())
     }
 
@@ -180,10 +248,21 @@ impl<Functions: EnvFunctions> EnvContextT for EnvContext<Functions> {
         Ok(self.env.storage_get(&key).context("get metadata failed")?.is_some())
     }
 
+    fn get_vrc721_collection(&self, hash: H256) -> Result<Option<Tag>> {
+        let key = [STORAGE_KEY_COLLECTION.to_vec(), hash.0.to_vec()].concat();
+
+        self.env
+            .storage_get(&key)
+            .context("get collection failed")?
+            .map(|datas| Tag::decode(&mut datas.as_slice()))
+            .transpose()
+            .map_err(|err| anyhow!("decode collection failed by {:?}", err))
+    }
+
     fn set_metadata<T: Encode>(&mut self, name: Tag, typ: MetaDataType, meta: T) -> Result<()> {
         log::debug!(target: TARGET, "set metadata {} {:?}", name, typ);
 
-        let key = [STORAGE_KEY_METADATA.to_vec(), [typ as u8].to_vec(), name.0.to_vec()].concat();
+        let key = [STORAGE_KEY_METADATA.to_vec(), [typ as u8].to_vec(), name.encode()].concat();
         let value = (typ as u8, meta).encode();
 
         self.env.storage_set(key, value).context("set metadata failed")
@@ -192,7 +271,7 @@ impl<Functions: EnvFunctions> EnvContextT for EnvContext<Functions> {
     fn get_metadata<T: Decode>(&self, name: Tag, typ: MetaDataType) -> Result<Option<T>> {
         log::debug!(target: TARGET, "get metadata {} {:?}", name, typ);
 
-        let key = [STORAGE_KEY_METADATA.to_vec(), [typ as u8].to_vec(), name.0.to_vec()].concat();
+        let key = [STORAGE_KEY_METADATA.to_vec(), [typ as u8].to_vec(), name.encode()].concat();
 
         let value = self.env.storage_get(&key).context("get metadata failed")?;
         if let Some((typ_in_storage, res)) = value
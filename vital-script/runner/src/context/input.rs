@@ -1,7 +1,6 @@
 use alloc::vec::Vec;
 use anyhow::{bail, Result};
 use vital_script_primitives::{
-    names::Name,
     resources::{self, Resource, Tag, VRC20, VRC721},
     traits::context::InputResourcesContext as InputResourcesContextT,
     H256, U256,
@@ -15,29 +14,46 @@ const VEC_CAP_SIZE: usize = 8;
 pub struct InputResourcesContext {
     inputs: InputResources,
     inputs_indexs: Vec<u8>,
+    /// The input indexs costed since the last take.
+    costed: Vec<u8>,
 }
 
 impl InputResourcesContext {
     pub fn new(cap: usize) -> Self {
-        Self { inputs: InputResources::new(cap), inputs_indexs: Vec::with_capacity(cap) }
+        Self {
+            inputs: InputResources::new(cap),
+            inputs_indexs: Vec::with_capacity(cap),
+            costed: Vec::new(),
+        }
     }
 }
 
 impl InputResourcesContextT for InputResourcesContext {
     fn push(&mut self, input_index: u8, resource: Resource) -> Result<()> {
-        match resource {
-            Resource::Name(name) => self.inputs.push_name(input_index, name),
-            Resource::VRC20(v) => self.inputs.push_vrc20(input_index, v.name, v.amount),
-            Resource::VRC721(v) => self.inputs.push_vrc721(input_index, Tag::default(), v.hash),
-        }
-
+        self.inputs.push(input_index, resource);
         self.inputs_indexs.push(input_index);
 
         Ok(())
     }
 
+    fn push_claimed(&mut self, input_index: u8, resource: Resource) -> Result<()> {
+        self.inputs.push(input_index, resource);
+
+        Ok(())
+    }
+
     fn cost(&mut self, resource: &Resource) -> Result<()> {
-        self.inputs.cost(resource)
+        for index in self.inputs.cost(resource)? {
+            if !self.costed.contains(&index) {
+                self.costed.push(index);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn take_costed(&mut self) -> Vec<u8> {
+        core::mem::take(&mut self.costed)
     }
 
     fn get_uncosted_vrc20(&self, name: Tag) -> Option<Resource> {
@@ -71,7 +87,7 @@ impl InputResourcesContextT for InputResourcesContext {
 pub struct NameInput {
     index: u8,
     costed: bool,
-    name: Name,
+    name: Tag,
 }
 
 #[derive(Debug, Clone)]
@@ -89,14 +105,27 @@ pub struct VRC20Inputs {
 }
 
 impl VRC20Inputs {
-    pub fn cost(&mut self, amount: U256) -> Result<()> {
+    /// Cost the amount, return the indexs of the inputs costed, the cost is first-indexed
+    /// first-cost.
+    pub fn cost(&mut self, amount: U256) -> Result<Vec<u8>> {
         if self.amount < amount + self.costed {
             bail!("not enough inputs");
         }
 
-        self.costed += amount;
+        let (from, to) = (self.costed, self.costed + amount);
+        self.costed = to;
 
-        Ok(())
+        let mut res = Vec::new();
+        let mut start = U256::zero();
+        for input in self.inputs.iter() {
+            let end = start + input.amount;
+            if start < to && end > from && !res.contains(&input.index) {
+                res.push(input.index);
+            }
+            start = end;
+        }
+
+        Ok(res)
     }
 
     pub fn is_costed(&self) -> bool {
@@ -155,6 +184,14 @@ impl InputResources {
         }
     }
 
+    pub fn push(&mut self, index: u8, resource: Resource) {
+        match resource {
+            Resource::Name(name) => self.push_name(index, name),
+            Resource::VRC20(v) => self.push_vrc20(index, v.name, v.amount),
+            Resource::VRC721(v) => self.push_vrc721(index, v.hash),
+        }
+    }
+
     pub fn push_name(&mut self, index: u8, name: Tag) {
         log::debug!(target: TARGET, "push input name: {} {}", index, name);
 
@@ -178,8 +215,9 @@ impl InputResources {
         self.vrc20s.push(VRC20Inputs { name, amount, costed: U256::zero(), inputs });
     }
 
-    pub fn push_vrc721(&mut self, index: u8, name: Tag, hash: H256) {
-        log::debug!(target: TARGET, "push input vrc721: {} {} {}", index, name, hash);
+    /// The collection of the vrc721 is in the env, it not need for cost.
+    pub fn push_vrc721(&mut self, index: u8, hash: H256) {
+        log::debug!(target: TARGET, "push input vrc721: {} {:?}", index, hash);
 
         self.vrc721s.push(VRC721Input { index, costed: false, hash });
     }
@@ -192,8 +230,8 @@ impl InputResources {
             && self.names.iter().all(|v| v.costed)
     }
 
-    /// Cost the resources from input
-    pub fn cost(&mut self, resource: &Resource) -> Result<()> {
+    /// Cost the resources from input, return the indexs of the inputs costed.
+    pub fn cost(&mut self, resource: &Resource) -> Result<Vec<u8>> {
         match resource {
             Resource::Name(n) => self.cost_name(n),
             Resource::VRC20(v) => self.cost_vrc20(v),
@@ -201,7 +239,7 @@ impl InputResources {
         }
     }
 
-    pub fn cost_vrc20(&mut self, resource: &resources::VRC20) -> Result<()> {
+    pub fn cost_vrc20(&mut self, resource: &resources::VRC20) -> Result<Vec<u8>> {
         log::debug!(target: TARGET, "cost_vrc20: {}", resource);
 
         for v in self.vrc20s.iter_mut() {
@@ -213,14 +251,14 @@ impl InputResources {
         bail!("not found res in inputs")
     }
 
-    pub fn cost_vrc721(&mut self, resource: &resources::VRC721) -> Result<()> {
+    pub fn cost_vrc721(&mut self, resource: &resources::VRC721) -> Result<Vec<u8>> {
         log::debug!(target: TARGET, "cost_vrc721: {}", resource);
 
         for v in self.vrc721s.iter_mut() {
             if v.hash == resource.hash {
                 if !v.costed {
                     v.costed = true;
-                    return Ok(())
+                    return Ok(alloc::vec![v.index])
                 } else {
                     bail!("had already costed");
                 }
@@ -230,7 +268,7 @@ impl InputResources {
         bail!("not found res in inputs")
     }
 
-    pub fn cost_name(&mut self, resource: &resources::Name) -> Result<()> {
+    pub fn cost_name(&mut self, resource: &Tag) -> Result<Vec<u8>> {
         log::debug!(target: TARGET, "cost_name: {}", resource);
 
         for v in self.names.iter_mut() {
@@ -239,7 +277,7 @@ impl InputResources {
                     bail!("had already costed")
                 } else {
                     v.costed = true;
-                    return Ok(())
+                    return Ok(alloc::vec![v.index])
                 }
             }
         }
@@ -305,8 +343,19 @@ impl InputResources {
 
 #[cfg(test)]
 mod tests {
+    use bitcoin::OutPoint;
+    use vital_script_ops::instruction::{
+        assert_input::InstructionInputAssert, assert_output::InstructionOutputAssert,
+        claim_space::InstructionClaimSpace, Instruction,
+    };
+    use vital_script_primitives::{
+        consts::{PROTOCOL_VERSION_V1, PROTOCOL_VERSION_V2},
+        names::Name,
+        traits::{Context, EnvContext, UncostedPolicy},
+    };
+
     use super::*;
-    use crate::mock::*;
+    use crate::{mock::*, traits::EnvFunctions};
 
     #[test]
     fn test_input_resources_context_should_work() {
@@ -398,8 +447,9 @@ mod tests {
         // cost first one
         {
             assert!(ctx.cost(&Resource::vrc20("abc", 8000.into()).expect("vrc20")).is_ok());
-            let uncosted =
-                ctx.get_uncosted_vrc20(Name::must_from("abc")).expect("should not costed all");
+            let uncosted = ctx
+                .get_uncosted_vrc20(Name::must_from("abc").into())
+                .expect("should not costed all");
             assert_eq!(uncosted, Resource::vrc20("abc", 22000.into()).expect("vrc20"));
 
             assert_eq!(ctx.inputs.vrc20s[0].costed, U256::from(8000));
@@ -427,8 +477,9 @@ mod tests {
 
         {
             assert!(ctx.cost(&Resource::vrc20("abc", 2000.into()).expect("vrc20")).is_ok());
-            let uncosted =
-                ctx.get_uncosted_vrc20(Name::must_from("abc")).expect("should not costed all");
+            let uncosted = ctx
+                .get_uncosted_vrc20(Name::must_from("abc").into())
+                .expect("should not costed all");
             assert_eq!(uncosted, Resource::vrc20("abc", 20000.into()).expect("vrc20"));
 
             assert_eq!(ctx.inputs.vrc20s[0].costed, U256::from(10000));
@@ -452,8 +503,9 @@ mod tests {
 
         {
             assert!(ctx.cost(&Resource::vrc20("abc", 14000.into()).expect("vrc20")).is_ok());
-            let uncosted =
-                ctx.get_uncosted_vrc20(Name::must_from("abc")).expect("should not costed all");
+            let uncosted = ctx
+                .get_uncosted_vrc20(Name::must_from("abc").into())
+                .expect("should not costed all");
             assert_eq!(uncosted, Resource::vrc20("abc", 6000.into()).expect("vrc20"));
 
             assert_eq!(ctx.inputs.vrc20s[0].costed, U256::from(24000));
@@ -476,7 +528,7 @@ mod tests {
 
         {
             assert!(ctx.cost(&Resource::vrc20("abc", 6000.into()).expect("vrc20")).is_ok());
-            let uncosted = ctx.get_uncosted_vrc20(Name::must_from("abc"));
+            let uncosted = ctx.get_uncosted_vrc20(Name::must_from("abc").into());
             assert_eq!(uncosted, None);
 
             assert_eq!(ctx.inputs.vrc20s[0].costed, U256::from(30000));
@@ -498,7 +550,7 @@ mod tests {
 
         {
             assert!(ctx.cost(&Resource::vrc20("abcdefgh", 100000.into()).expect("vrc20")).is_ok());
-            let uncosted = ctx.get_uncosted_vrc20(Name::must_from("abcdefgh"));
+            let uncosted = ctx.get_uncosted_vrc20(Name::must_from("abcdefgh").into());
             assert_eq!(uncosted, None);
 
             assert_eq!(ctx.inputs.vrc20s[1].costed, U256::from(100000));
@@ -602,4 +654,339 @@ mod tests {
             assert_eq!(uncosted, vec![(1, resources[1].clone()), (3, resources[3].clone())]);
         }
     }
+
+    #[test]
+    fn test_input_resources_context_uncosted_merged_should_work() {
+        let mut ctx = InputResourcesContext::new(8);
+
+        let hash = H256::random();
+        let resources = vec![
+            Resource::vrc20("abc", 10000.into()).expect("vrc20"),
+            Name::must_from("abc").into(),
+            Resource::vrc20("abc", 20000.into()).expect("vrc20"),
+            Resource::vrc20("abcdefgh", 10000.into()).expect("vrc20"),
+            Resource::vrc721(hash),
+        ];
+
+        for (i, r) in resources.iter().enumerate() {
+            assert!(ctx.push(i as u8, r.clone()).is_ok(), "the push should ok");
+        }
+
+        assert!(ctx.cost(&Resource::vrc20("abc", 12000.into()).expect("vrc20")).is_ok());
+        assert!(ctx.cost(&resources[4]).is_ok());
+
+        let mut uncosted = ctx.uncosted_merged();
+        uncosted.sort();
+
+        assert_eq!(
+            uncosted,
+            vec![
+                Name::must_from("abc").into(),
+                Resource::vrc20("abc", 18000.into()).expect("vrc20"),
+                Resource::vrc20("abcdefgh", 10000.into()).expect("vrc20"),
+            ]
+        );
+
+        // the claimed resources will not be unbind from the input.
+        assert!(ctx.push_claimed(5, Resource::vrc721(H256::random())).is_ok());
+        assert_eq!(ctx.all(), vec![0, 1, 2, 3, 4], "the claimed not in all inputs");
+        assert_eq!(ctx.uncosted_merged().len(), 4, "the claimed is uncosted");
+    }
+
+    #[test]
+    fn test_input_resources_context_take_costed_should_work() {
+        let mut ctx = InputResourcesContext::new(8);
+
+        let resources = vec![
+            Resource::vrc20("abc", 10000.into()).expect("vrc20"),
+            Name::must_from("abc").into(),
+            Resource::vrc20("abc", 20000.into()).expect("vrc20"),
+            Resource::vrc20("abc", 5000.into()).expect("vrc20"),
+        ];
+
+        for (i, r) in resources.iter().enumerate() {
+            assert!(ctx.push(i as u8, r.clone()).is_ok(), "the push should ok");
+        }
+
+        assert!(ctx.take_costed().is_empty(), "no costed before");
+
+        // the vrc20 costed from the first input.
+        assert!(ctx.cost(&Resource::vrc20("abc", 6000.into()).expect("vrc20")).is_ok());
+        assert_eq!(ctx.take_costed(), vec![0]);
+        assert!(ctx.take_costed().is_empty(), "the costed had been taken");
+
+        // the rest of the first input and a part of the third.
+        assert!(ctx.cost(&Resource::vrc20("abc", 5000.into()).expect("vrc20")).is_ok());
+        assert!(ctx.cost(&resources[1]).is_ok());
+        assert_eq!(ctx.take_costed(), vec![0, 2, 1]);
+
+        // the failed cost will not record.
+        assert!(ctx.cost(&Resource::vrc20("abc", 30000.into()).expect("vrc20")).is_err());
+        assert!(ctx.take_costed().is_empty());
+
+        assert!(ctx.cost(&Resource::vrc20("abc", 24000.into()).expect("vrc20")).is_ok());
+        assert_eq!(ctx.take_costed(), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_uncosted_policy_by_protocol_version() {
+        assert_eq!(
+            UncostedPolicy::by_protocol_version(PROTOCOL_VERSION_V1).expect("v1"),
+            UncostedPolicy::Space
+        );
+        assert_eq!(
+            UncostedPolicy::by_protocol_version(PROTOCOL_VERSION_V2).expect("v2"),
+            UncostedPolicy::ReturnToFirstOutput
+        );
+        assert_err_str(
+            UncostedPolicy::by_protocol_version(0),
+            "unknown protocol version 0",
+            "unknown version",
+        );
+    }
+
+    /// Move a part of the vrc20 from the input to the output 1, and keep others uncosted.
+    fn move_part_of_vrc20(
+        env_interface: &EnvMock,
+        policy: UncostedPolicy,
+        input: OutPoint,
+        amount: u128,
+    ) -> Result<ContextMock> {
+        TestCtx::new(env_interface)
+            .with_instructions(vec![
+                Instruction::Input(InstructionInputAssert {
+                    index: 1,
+                    resource: Resource::vrc20("abcde", 1000.into())?,
+                }),
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0, 1] }),
+                Instruction::move_vrc20_to(1, Name::must_from("abcde"), amount),
+            ])
+            .with_ops()
+            .with_input(input)
+            .with_outputs(2, 1000)
+            .with_uncosted_policy(policy)
+            .run()
+    }
+
+    #[test]
+    fn test_uncosted_resources_should_store_into_space() {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.deploy_vrc20("abcde", 1000);
+        let input = ctx.mint_vrc20("abcde");
+
+        let ctx = move_part_of_vrc20(&env_interface, UncostedPolicy::Space, input, 400)
+            .expect("move should ok");
+
+        let txid = *ctx.env().get_reveal_tx_id();
+        assert_eq!(env_interface.get_resources(&input).expect("get"), None);
+        assert_eq!(env_interface.get_resources(&ctx.env().get_output(0)).expect("get"), None);
+        assert_eq!(
+            env_interface.get_resources(&ctx.env().get_output(1)).expect("get"),
+            Some(Resource::vrc20("abcde", 400.into()).expect("vrc20"))
+        );
+        assert_eq!(
+            ctx.env().get_space_resources(&txid).expect("get space"),
+            vec![Resource::vrc20("abcde", 600.into()).expect("vrc20")]
+        );
+    }
+
+    #[test]
+    fn test_uncosted_resources_should_return_to_first_output() {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.deploy_vrc20("abcde", 1000);
+        let input = ctx.mint_vrc20("abcde");
+
+        let ctx =
+            move_part_of_vrc20(&env_interface, UncostedPolicy::ReturnToFirstOutput, input, 400)
+                .expect("move should ok");
+
+        let txid = *ctx.env().get_reveal_tx_id();
+        assert_eq!(env_interface.get_resources(&input).expect("get"), None);
+        assert_eq!(
+            env_interface.get_resources(&ctx.env().get_output(0)).expect("get"),
+            Some(Resource::vrc20("abcde", 600.into()).expect("vrc20"))
+        );
+        assert_eq!(
+            env_interface.get_resources(&ctx.env().get_output(1)).expect("get"),
+            Some(Resource::vrc20("abcde", 400.into()).expect("vrc20"))
+        );
+        assert_eq!(ctx.env().get_space_resources(&txid).expect("get space"), vec![]);
+    }
+
+    #[test]
+    fn test_uncosted_resources_return_to_first_output_failed() {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.mint_name("abc");
+        ctx.deploy_vrc20("abcde", 1000);
+        let name_input = ctx.get_name_outpoint("abc").expect("should mint");
+        let vrc20_input = ctx.mint_vrc20("abcde");
+
+        // 1. no output asserted
+        let res = TestCtx::new(&env_interface)
+            .with_instructions(vec![Instruction::Input(InstructionInputAssert {
+                index: 1,
+                resource: Name::must_from("abc").into(),
+            })])
+            .with_ops()
+            .with_input(name_input)
+            .with_output(1000)
+            .with_uncosted_policy(UncostedPolicy::ReturnToFirstOutput)
+            .run();
+        assert_err_str(res, "no output to return the uncosted resources", "no output asserted");
+
+        // 2. the uncosted resources cannot merged into the first output
+        let res = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Input(InstructionInputAssert {
+                    index: 1,
+                    resource: Name::must_from("abc").into(),
+                }),
+                Instruction::Input(InstructionInputAssert {
+                    index: 2,
+                    resource: Resource::vrc20("abcde", 1000.into()).expect("vrc20"),
+                }),
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+            ])
+            .with_ops()
+            .with_input(name_input)
+            .with_input(vrc20_input)
+            .with_output(1000)
+            .with_uncosted_policy(UncostedPolicy::ReturnToFirstOutput)
+            .run();
+        assert_err_str(res, "the resource type not support merge", "cannot merge");
+
+        // the failed tx not change the resources
+        assert!(env_interface.get_resources(&name_input).expect("get").is_some());
+        assert!(env_interface.get_resources(&vrc20_input).expect("get").is_some());
+    }
+
+    #[test]
+    fn test_claim_space_should_work() {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.deploy_vrc20("abcde", 1000);
+        let input = ctx.mint_vrc20("abcde");
+
+        let space_ctx = move_part_of_vrc20(&env_interface, UncostedPolicy::Space, input, 400)
+            .expect("move should ok");
+        let space_txid = *space_ctx.env().get_reveal_tx_id();
+        let first_output = space_ctx.env().get_output(0);
+
+        let claim = |resources: Vec<Resource>, input: OutPoint| {
+            TestCtx::new(&env_interface)
+                .with_instructions(vec![
+                    Instruction::ClaimSpace(InstructionClaimSpace { index: 1, resources }),
+                    Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                    Instruction::move_vrc20_to(0, Name::must_from("abcde"), 600),
+                ])
+                .with_ops()
+                .with_input(input)
+                .with_output(1000)
+                .run()
+        };
+
+        // 1. only the first output can claim the space
+        let res = claim(
+            vec![Resource::vrc20("abcde", 600.into()).expect("vrc20")],
+            space_ctx.env().get_output(1),
+        );
+        assert_err_str(
+            res,
+            "the space can only be claimed by the first output",
+            "claim by the second output",
+        );
+
+        // 2. the resources should be the same as the space
+        let res = claim(vec![Resource::vrc20("abcde", 700.into()).expect("vrc20")], first_output);
+        assert_err_str(res, "the space resources not expected", "claim by diff resources");
+
+        // 3. claim the space
+        let ctx = claim(vec![Resource::vrc20("abcde", 600.into()).expect("vrc20")], first_output)
+            .expect("claim should ok");
+        assert_eq!(
+            env_interface.get_resources(&ctx.env().get_output(0)).expect("get"),
+            Some(Resource::vrc20("abcde", 600.into()).expect("vrc20"))
+        );
+        assert_eq!(ctx.env().get_space_resources(&space_txid).expect("get space"), vec![]);
+        assert_eq!(
+            env_interface.storage_get(&crate::context::space_key(&space_txid)).expect("get"),
+            None,
+            "the claimed space should be removed"
+        );
+
+        // 4. the space cannot be claimed two times
+        let res = claim(vec![Resource::vrc20("abcde", 600.into()).expect("vrc20")], first_output);
+        assert_err_str(res, "the space is empty", "claim two times");
+    }
+
+    #[test]
+    fn test_claim_space_with_input_resource_should_work() {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.mint_name("abc");
+        ctx.deploy_vrc20("abcde", 1000);
+        let name_input = ctx.get_name_outpoint("abc").expect("should mint");
+        let vrc20_input = ctx.mint_vrc20("abcde");
+
+        // the name is moved to the first output, and the vrc20 is stored into the space.
+        let space_ctx = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Input(InstructionInputAssert {
+                    index: 1,
+                    resource: Name::must_from("abc").into(),
+                }),
+                Instruction::Input(InstructionInputAssert {
+                    index: 2,
+                    resource: Resource::vrc20("abcde", 1000.into()).expect("vrc20"),
+                }),
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::move_to(0, Name::must_from("abc")),
+            ])
+            .with_ops()
+            .with_input(name_input)
+            .with_input(vrc20_input)
+            .with_output(1000)
+            .run()
+            .expect("move name should ok");
+        let first_output = space_ctx.env().get_output(0);
+
+        // the input with both the name and the space.
+        let ctx = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Input(InstructionInputAssert {
+                    index: 1,
+                    resource: Name::must_from("abc").into(),
+                }),
+                Instruction::ClaimSpace(InstructionClaimSpace {
+                    index: 1,
+                    resources: vec![Resource::vrc20("abcde", 1000.into()).expect("vrc20")],
+                }),
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0, 1] }),
+                Instruction::move_to(0, Name::must_from("abc")),
+                Instruction::move_vrc20_to(1, Name::must_from("abcde"), 1000),
+            ])
+            .with_ops()
+            .with_input(first_output)
+            .with_outputs(2, 1000)
+            .run()
+            .expect("claim should ok");
+
+        assert_eq!(env_interface.get_resources(&first_output).expect("get"), None);
+        assert_eq!(
+            env_interface.get_resources(&ctx.env().get_output(0)).expect("get"),
+            Some(Name::must_from("abc").into())
+        );
+        assert_eq!(
+            env_interface.get_resources(&ctx.env().get_output(1)).expect("get"),
+            Some(Resource::vrc20("abcde", 1000.into()).expect("vrc20"))
+        );
+    }
 }
@@ -8,7 +8,7 @@ use vital_script_primitives::{
     resources::Resource,
     traits::{
         context::EnvContext as EnvContextT, InputResourcesContext as InputResourcesContextT,
-        RunMode,
+        RunMode, UncostedPolicy,
     },
 };
 
@@ -17,6 +17,7 @@ mod input;
 mod runner;
 pub mod script;
 
+pub(crate) use env::space_key;
 pub use env::EnvContext;
 use input::InputResourcesContext;
 use runner::RunnerContext;
@@ -32,6 +33,8 @@ pub struct Context<Functions: EnvFunctions> {
     runner: RunnerContext,
     pub outputs: Vec<(u8, Resource)>,
     mode: RunMode,
+    uncosted_policy: UncostedPolicy,
+    protocol_version: u32,
 }
 
 impl<Functions> ContextT for Context<Functions>
@@ -48,6 +51,14 @@ where
         self.mode
     }
 
+    fn uncosted_policy(&self) -> UncostedPolicy {
+        self.uncosted_policy
+    }
+
+    fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+
     fn env(&self) -> &Self::Env {
         &self.env
     }
@@ -76,30 +87,12 @@ where
         self.env.get_ops()
     }
 
-    fn get_instructions(&self) -> Result<Vec<Instruction>> {
-        let ops_bytes = self.get_ops();
-        let ins = ops_bytes
-            .iter()
-            .map(|(index, ops)| {
-                Parser::new(ops).parse().with_context(|| alloc::format!("parse {}", index))
-            })
-            .collect::<Result<Vec<_>>>()?;
-
-        Ok(ins.concat())
+    fn get_instructions(&self) -> Result<Vec<(u8, Vec<Instruction>)>> {
+        parse_instructions(self.get_ops())
     }
 
     fn pre_check(&self) -> Result<()> {
-        use vital_script_primitives::traits::Instruction;
-
-        let instructions = self.get_instructions().context("get instructions")?;
-
-        for (index, instruction) in instructions.iter().enumerate() {
-            instruction
-                .pre_check()
-                .with_context(|| alloc::format!("instruction {}", index))?;
-        }
-
-        Ok(())
+        pre_check_instructions(&self.get_instructions().context("get instructions")?)
     }
 
     /// Do post check
@@ -107,7 +100,11 @@ where
         let uncosted = self.input_resource().uncosted();
 
         if !uncosted.is_empty() {
-            log::warn!(target: TARGET, "the input not all costed yet");
+            log::warn!(
+                target: TARGET,
+                "the input not all costed, will handle by {:?}",
+                self.uncosted_policy
+            );
         }
 
         Ok(())
@@ -120,11 +117,46 @@ where
     }
 }
 
+/// Parse the ops of each script, keep the input index of the script.
+pub(crate) fn parse_instructions(
+    ops_bytes: &[(u8, Vec<u8>)],
+) -> Result<Vec<(u8, Vec<Instruction>)>> {
+    ops_bytes
+        .iter()
+        .map(|(index, ops)| {
+            let ins =
+                Parser::new(ops).parse().with_context(|| alloc::format!("parse {}", index))?;
+            Ok((*index, ins))
+        })
+        .collect()
+}
+
+pub(crate) fn pre_check_instructions(scripts: &[(u8, Vec<Instruction>)]) -> Result<()> {
+    use vital_script_primitives::traits::Instruction;
+
+    for (input_index, instructions) in scripts.iter() {
+        for (index, instruction) in instructions.iter().enumerate() {
+            instruction.pre_check().with_context(|| {
+                alloc::format!("instruction {} in script {}", index, input_index)
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
 impl<Functions> Context<Functions>
 where
     Functions: EnvFunctions,
 {
-    pub fn new(env_interface: Functions, reveal_tx: &Transaction, block_height: u32) -> Self {
+    /// New context to run the reveal tx, the uncosted input resources will be handled by the
+    /// policy of the protocol version, see `UncostedPolicy::by_protocol_version`.
+    pub fn new(
+        env_interface: Functions,
+        reveal_tx: &Transaction,
+        block_height: u32,
+        protocol_version: u32,
+    ) -> Result<Self> {
         let runner = RunnerContext::new();
         let input_resources = InputResourcesContext::new(CAP_SIZE);
         let env = EnvContext::new(
@@ -134,14 +166,55 @@ where
             block_height,
         );
 
-        Self { env, input_resources, runner, mode: RunMode::Normal, outputs: Vec::new() }
+        Ok(Self {
+            env,
+            input_resources,
+            runner,
+            mode: RunMode::Normal,
+            outputs: Vec::new(),
+            uncosted_policy: UncostedPolicy::by_protocol_version(protocol_version)
+                .context("uncosted policy")?,
+            protocol_version,
+        })
     }
 
-    pub fn simulator(env_interface: Functions, reveal_tx: &Transaction, block_height: u32) -> Self {
+    pub fn simulator(
+        env_interface: Functions,
+        reveal_tx: &Transaction,
+        block_height: u32,
+        protocol_version: u32,
+    ) -> Result<Self> {
         let runner = RunnerContext::new();
         let input_resources = InputResourcesContext::new(CAP_SIZE);
         let env = EnvContext::new_for_sim(env_interface, reveal_tx, block_height);
 
-        Self { env, input_resources, runner, mode: RunMode::Simulator, outputs: Vec::new() }
+        Ok(Self {
+            env,
+            input_resources,
+            runner,
+            mode: RunMode::Simulator,
+            outputs: Vec::new(),
+            uncosted_policy: UncostedPolicy::by_protocol_version(protocol_version)
+                .context("uncosted policy")?,
+            protocol_version,
+        })
+    }
+
+    /// Set the inputs of the scripts whose commit tx is in the reveal tx 's block, default all
+    /// the commit txs are confirmed in the blocks before, the block runner need set it by the
+    /// txs in block.
+    pub fn with_commits_in_curr_block(
+        mut self,
+        input_indexes: impl IntoIterator<Item = u8>,
+    ) -> Self {
+        self.env.set_commits_in_curr_block(input_indexes.into_iter().collect());
+        self
+    }
+
+    /// Set the policy for the uncosted input resources instead of the one by the protocol
+    /// version, see `UncostedPolicy::by_protocol_version`.
+    pub fn with_uncosted_policy(mut self, policy: UncostedPolicy) -> Self {
+        self.uncosted_policy = policy;
+        self
     }
 }
@@ -2,10 +2,20 @@ use alloc::collections::BTreeSet;
 use anyhow::{bail, Result};
 use vital_script_primitives::traits::context::RunnerContext as RunnerContextT;
 
+use crate::TARGET;
+
 #[derive(Default, Clone)]
 pub struct RunnerContext {
     inputs: BTreeSet<u8>,
     outputs: BTreeSet<u8>,
+    /// The state of the script running, None if not in any script.
+    script: Option<ScriptState>,
+}
+
+#[derive(Default, Clone)]
+struct ScriptState {
+    input_index: u8,
+    outputs: BTreeSet<u8>,
     had_mint: bool,
 }
 
@@ -16,8 +26,29 @@ impl RunnerContext {
 }
 
 impl RunnerContextT for RunnerContext {
+    fn enter_script(&mut self, input_index: u8) {
+        log::debug!(target: TARGET, "enter script in input {}", input_index);
+
+        self.script = Some(ScriptState { input_index, ..Default::default() });
+    }
+
+    fn exit_script(&mut self) {
+        self.script = None;
+    }
+
+    fn current_script(&self) -> Option<u8> {
+        self.script.as_ref().map(|script| script.input_index)
+    }
+
     fn is_output_available(&self, index: u8) -> bool {
-        self.outputs.contains(&index)
+        match &self.script {
+            Some(script) => script.outputs.contains(&index),
+            None => self.outputs.contains(&index),
+        }
+    }
+
+    fn first_asserted_output(&self) -> Option<u8> {
+        self.outputs.first().copied()
     }
 
     fn try_assert_input(&mut self, index: u8) -> Result<()> {
@@ -36,16 +67,23 @@ impl RunnerContextT for RunnerContext {
         }
 
         self.outputs.insert(index);
+        if let Some(script) = self.script.as_mut() {
+            script.outputs.insert(index);
+        }
 
         Ok(())
     }
 
     fn try_mint(&mut self) -> Result<()> {
-        if self.had_mint {
-            bail!("each tx can only have one mint");
+        let Some(script) = self.script.as_mut() else {
+            bail!("the mint not in a script");
+        };
+
+        if script.had_mint {
+            bail!("each script can only have one mint");
         }
 
-        self.had_mint = true;
+        script.had_mint = true;
 
         Ok(())
     }
@@ -56,10 +94,14 @@ mod tests {
     use vital_script_ops::instruction::{
         assert_input::InstructionInputAssert, assert_output::InstructionOutputAssert, Instruction,
     };
-    use vital_script_primitives::{names::Name, resources::Resource};
+    use vital_script_primitives::{
+        names::Name,
+        resources::{Resource, ResourceType},
+        traits::{Context as ContextT, EnvContext},
+    };
 
     use super::*;
-    use crate::mock::*;
+    use crate::{mock::*, traits::EnvFunctions};
 
     #[test]
     fn test_assert_input_can_work() {
@@ -178,7 +220,7 @@ mod tests {
         ]);
         assert_eq!(
             res.err().expect("should failed by mint two times").root_cause().to_string(),
-            "each tx can only have one mint"
+            "each script can only have one mint"
         );
 
         let res = test_mints_in_one_tx_res(&[
@@ -187,7 +229,7 @@ mod tests {
         ]);
         assert_eq!(
             res.err().expect("should failed by mint two times").root_cause().to_string(),
-            "each tx can only have one mint"
+            "each script can only have one mint"
         );
 
         let res = test_mints_in_one_tx_res(&[
@@ -197,7 +239,101 @@ mod tests {
         ]);
         assert_eq!(
             res.err().expect("should failed by mint two times").root_cause().to_string(),
-            "each tx can only have one mint"
+            "each script can only have one mint"
         );
     }
+
+    #[test]
+    fn test_mint_in_multiple_scripts_should_work() {
+        let env_interface = EnvMock::new();
+
+        let names = ["test1", "test2", "test3"];
+        let mut ctx = TestCtx::new(&env_interface);
+        for (index, name) in names.iter().enumerate() {
+            ctx = ctx.with_instructions(vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![index as u8] }),
+                Instruction::mint(index as u8, ResourceType::name(Name::must_from(name))),
+            ]);
+        }
+
+        let context = ctx.with_all_ops().with_outputs(names.len(), 2000).run().expect("mint");
+
+        for (index, name) in names.iter().enumerate() {
+            let res = env_interface
+                .get_resources(&context.env().get_output(index as u8))
+                .expect("get resources");
+            assert_eq!(res, Some(Resource::name(Name::must_from(name))));
+        }
+    }
+
+    #[test]
+    fn test_multiple_scripts_isolated_outputs() {
+        let env_interface = EnvMock::new();
+
+        // the output asserted by the script before can not be used.
+        let res = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::mint(0, ResourceType::name(Name::must_from("test1"))),
+            ])
+            .with_instructions(vec![Instruction::mint(
+                0,
+                ResourceType::name(Name::must_from("test2")),
+            )])
+            .with_all_ops()
+            .with_output(2000)
+            .run();
+        assert_err_str(res, "the output is not asserted", "use the output by the script before");
+
+        // also can not be asserted again.
+        let res = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::mint(0, ResourceType::name(Name::must_from("test1"))),
+            ])
+            .with_instructions(vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::mint(0, ResourceType::name(Name::must_from("test2"))),
+            ])
+            .with_all_ops()
+            .with_output(2000)
+            .run();
+        assert_err_str(res, "the output is already asserted", "assert the output again");
+
+        // the tx failed as a whole, so the first script 's mint not applied.
+        assert_eq!(env_interface.get_outpoint(&Name::must_from("test1").into()), None);
+    }
+
+    #[test]
+    fn test_multiple_scripts_shared_inputs() {
+        let test = Name::must_from("test1");
+
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+        ctx.mint_name("test1");
+
+        let outpoint1 = ctx.get_name_outpoint("test1").expect("should mint");
+
+        // the input asserted by the first script, the second script move it to its output.
+        let context = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Input(InstructionInputAssert { index: 2, resource: test.into() }),
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::mint(0, ResourceType::name(Name::must_from("test2"))),
+            ])
+            .with_instructions(vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![1] }),
+                Instruction::move_to(1, test),
+            ])
+            .with_all_ops()
+            .with_input(outpoint1)
+            .with_outputs(2, 2000)
+            .run()
+            .expect("move in the second script");
+
+        let res = env_interface
+            .get_resources(&context.env().get_output(1))
+            .expect("get resources");
+        assert_eq!(res, Some(Resource::name(test)));
+    }
 }
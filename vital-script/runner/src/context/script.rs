@@ -1,12 +1,14 @@
 use anyhow::{bail, Context, Result};
 use bitcoin::{
-    opcodes::all::{OP_PUSHBYTES_75, OP_PUSHDATA1, OP_PUSHDATA2},
+    opcodes::all::{OP_ENDIF, OP_PUSHBYTES_75, OP_PUSHDATA1, OP_PUSHDATA2},
     Transaction,
 };
 use hex_literal::hex;
 
 use alloc::vec::Vec;
 
+use vital_script_primitives::consts::{MAX_PUSH_DATAS_LEN, MAX_SCRIPT_DATAS_LEN};
+
 use crate::traits::EnvFunctions;
 
 // TODO: move to primitive types
@@ -135,8 +137,8 @@ pub fn try_get_vital_script(script: &[u8]) -> Result<Vec<u8>> {
     // 0x: OP_PUSHBYTES_XX
     // datas
     // 68: OP_ENDIF
-
-    // TODO: support multiple push bytes
+    //
+    // the datas can be chunked into multiple pushes, each push at most 520 bytes.
 
     if script.len() <= VITAL_SCRIPT_MIN_LEN {
         bail!("script len min");
@@ -177,58 +179,57 @@ pub fn try_get_vital_script(script: &[u8]) -> Result<Vec<u8>> {
     Ok(script_bytes)
 }
 
+/// Get the datas from the pushes until the OP_ENDIF, the bytes will be:
+/// [push1, push2, ..., OP_ENDIF], each push can be:
+/// - OP_PUSHBYTES_XX, the op is eq the len: [OP_PUSHBYTES_XX, [datas..]]
+/// - OP_PUSHDATA1(0x4c): [OP_PUSHDATA1, Len(u8), [datas..]]
+/// - OP_PUSHDATA2(0x4d): [OP_PUSHDATA2, Len([u8[2], u8[1]]), [datas..]]
 fn try_get_script_bytes(bytes: &[u8]) -> Result<Vec<u8>> {
-    let push_op = bytes[0];
-    if push_op <= OP_PUSHBYTES_75.to_u8() {
-        // use OP_PUSHBYTES_XX, the op is eq the len
-        let script_len = push_op as usize;
-        // the bytes will be [OP_PUSHBYTES_XX, [script_bytes..], OP_ENDIF]
-        if bytes.len() != 1 + script_len + 1 {
-            bail!("data len not match {}, {}", bytes.len(), 1 + script_len);
-        }
-
-        if bytes[1 + script_len] != 0x68 {
-            bail!("OP_ENDIF")
-        }
-
-        return Ok(bytes[1..=script_len].to_vec())
-    }
+    let mut res = Vec::new();
+    let mut n = 0;
 
-    if push_op == OP_PUSHDATA1.to_u8() {
-        // use OP_PUSHDATA1(0x4c), the bytes will be:
-        // [OP_PUSHDATA1(0x4c), Len(u8), [script_bytes..], OP_ENDIF]
-        let script_len = bytes[1] as usize;
+    loop {
+        let Some(&push_op) = bytes.get(n) else {
+            bail!("OP_ENDIF");
+        };
 
-        let expect_len = 1 + 1 + script_len + 1;
-        if bytes.len() != expect_len {
-            bail!("data len not match {}, {}", bytes.len(), expect_len);
+        if push_op == OP_ENDIF.to_u8() {
+            break;
         }
 
-        if bytes[1 + 1 + script_len] != 0x68 {
-            bail!("OP_ENDIF")
+        let (len_size, datas_len) = if push_op <= OP_PUSHBYTES_75.to_u8() {
+            (0, push_op as usize)
+        } else if push_op == OP_PUSHDATA1.to_u8() {
+            (1, *bytes.get(n + 1).context("no len for OP_PUSHDATA1")? as usize)
+        } else if push_op == OP_PUSHDATA2.to_u8() {
+            let len = bytes.get(n + 1..n + 3).context("no len for OP_PUSHDATA2")?;
+            (2, u16::from_le_bytes([len[0], len[1]]) as usize)
+        } else {
+            bail!("currently not support {}", push_op);
+        };
+
+        if datas_len > MAX_PUSH_DATAS_LEN {
+            bail!("the push datas too large {}", datas_len);
         }
 
-        return Ok(bytes[2..=(1 + script_len)].to_vec())
-    }
-
-    if push_op == OP_PUSHDATA2.to_u8() {
-        // use OP_PUSHDATA2(0x4d), the bytes will be:
-        // [OP_PUSHDATA1(0x4d), Len([u8[2], u8[1]]), [script_bytes..], OP_ENDIF]
-        let script_len = u16::from_le_bytes([bytes[1], bytes[2]]) as usize;
+        let start = n + 1 + len_size;
+        let datas = bytes.get(start..start + datas_len).with_context(|| {
+            alloc::format!("data len not match {}, {}", bytes.len(), start + datas_len)
+        })?;
 
-        let expect_len = 1 + 2 + script_len + 1;
-        if bytes.len() != expect_len {
-            bail!("data len not match {}, {}", bytes.len(), expect_len);
+        res.extend_from_slice(datas);
+        if res.len() > MAX_SCRIPT_DATAS_LEN {
+            bail!("the datas too large");
         }
 
-        if bytes[1 + 2 + script_len] != 0x68 {
-            bail!("OP_ENDIF")
-        }
+        n = start + datas_len;
+    }
 
-        return Ok(bytes[(1 + 2)..=(2 + script_len)].to_vec())
+    if n + 1 != bytes.len() {
+        bail!("should end by OP_ENDIF");
     }
 
-    bail!("currently not support {}", push_op);
+    Ok(res)
 }
 
 #[cfg(test)]
@@ -356,4 +357,71 @@ mod tests {
         assert_eq!(script[0].0, 0);
         assert_eq!(script[0].1, hex::decode("0b030010c2010000154200000010e8030000154200000110e8030000154200000210e8030000154200000310e8030000154200000410e8030000154200000510e8030000154200000610e8030000154200000710e8030000154200000810e8030000154200000910e8030000154200000a10e8030000154200000b10e8030000154200000c10e8030000154200000d10e8030000154200000e10e8030000154200000f10e8030000154200001010e8030000154200001110e8030000154200001210e8030000154200001310e8030000154200001410e8030000154200001510e8030000154200001610e8030000154200001710e8030000154200001810e803000015420000191036290000154200001a1e154200009f8c0000001e154200000100000001").expect("hex"));
     }
+
+    fn vital_script_with_pushes(pushes: &[&[u8]]) -> Vec<u8> {
+        let mut script = hex::decode(
+            "20d2e7612f73d26067ae83e2a9d8bfa496193374677490dff0792242bbacba6922ac006305766974616c",
+        )
+        .expect("hex");
+
+        for push in pushes {
+            if push.len() <= 75 {
+                script.push(push.len() as u8);
+            } else if push.len() <= 0xff {
+                script.extend([OP_PUSHDATA1.to_u8(), push.len() as u8]);
+            } else {
+                script.push(OP_PUSHDATA2.to_u8());
+                script.extend((push.len() as u16).to_le_bytes());
+            }
+            script.extend_from_slice(push);
+        }
+
+        script.push(OP_ENDIF.to_u8());
+        script
+    }
+
+    #[test]
+    fn test_try_get_vital_script_for_multiple_push() {
+        let datas = (0..1200).map(|i| i as u8).collect::<Vec<_>>();
+
+        let script = vital_script_with_pushes(&[
+            &datas[..520],
+            &datas[520..1040],
+            &datas[1040..1100],
+            &datas[1100..],
+        ]);
+        assert_eq!(try_get_vital_script(&script).expect("multiple push"), datas);
+    }
+
+    #[test]
+    fn test_try_get_vital_script_for_invalid_push_should_failed() {
+        let datas = [1_u8; 600];
+
+        // push too large
+        let script = vital_script_with_pushes(&[&datas]);
+        assert!(try_get_vital_script(&script).is_err());
+
+        // datas too large
+        let mut pushes = vec![&datas[..520]; 7];
+        pushes.push(&datas[..MAX_SCRIPT_DATAS_LEN - 520 * 7]);
+        assert!(try_get_vital_script(&vital_script_with_pushes(&pushes)).is_ok());
+        pushes.push(&datas[..1]);
+        assert!(try_get_vital_script(&vital_script_with_pushes(&pushes)).is_err());
+
+        // no OP_ENDIF
+        let mut script = vital_script_with_pushes(&[&datas[..10]]);
+        script.pop();
+        assert!(try_get_vital_script(&script).is_err());
+
+        // bytes after OP_ENDIF
+        let mut script = vital_script_with_pushes(&[&datas[..10]]);
+        script.push(0x01);
+        assert!(try_get_vital_script(&script).is_err());
+
+        // push len out of the script
+        let mut script = vital_script_with_pushes(&[&datas[..10]]);
+        script.truncate(script.len() - 3);
+        script.push(OP_ENDIF.to_u8());
+        assert!(try_get_vital_script(&script).is_err());
+    }
 }
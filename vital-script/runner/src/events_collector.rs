@@ -0,0 +1,400 @@
+//! Collect the events by the instructions which the runner had executed.
+
+use alloc::vec::Vec;
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use bitcoin::OutPoint;
+
+use vital_script_ops::instruction::Instruction;
+use vital_script_primitives::{
+    resources::{Resource, ResourceType, VRC20},
+    traits::{
+        Context as ContextT, EnvContext, InputResourcesContext, RunnerContext, UncostedPolicy,
+    },
+};
+
+use crate::traits::events::Event;
+
+#[derive(Default)]
+pub(crate) struct EventsCollector {
+    events: Vec<Event>,
+}
+
+impl EventsCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The uncosted vrc20 will be moved by the `MoveAll`, need call before it exec.
+    pub fn moved_all(context: &impl ContextT, instruction: &Instruction) -> Option<Resource> {
+        match instruction {
+            Instruction::MoveAll(i) => i
+                .resource_type
+                .get_tag()
+                .and_then(|name| context.input_resource().get_uncosted_vrc20(*name)),
+            _ => None,
+        }
+    }
+
+    /// Collect the events by the instruction after it exec success, the `costed` is the input
+    /// indexs costed by the instruction.
+    pub fn on_instruction(
+        &mut self,
+        context: &impl ContextT,
+        instruction: &Instruction,
+        moved_all: Option<Resource>,
+        costed: &[u8],
+    ) -> Result<()> {
+        let env = context.env();
+
+        match instruction {
+            Instruction::Input(_) | Instruction::Output(_) => {}
+            Instruction::Mint(i) => {
+                let resource = Self::minted(context, &i.resource_type)?;
+                let output = env.get_output(i.output_index);
+                self.events.push(Event::Minted { resource, output });
+            }
+            Instruction::DMint(i) => {
+                let resource = Self::minted(context, &i.resource_type)?;
+                let output = env.get_output(i.output_index);
+                self.events.push(Event::Minted { resource, output });
+            }
+            Instruction::Deploy(i) => {
+                let input = env.get_input(i.name_input).context("get name input")?;
+                self.events.push(Event::Deployed { name: i.name, meta: i.meta.clone(), input });
+                self.events.push(Event::NameCosted { name: i.name, input });
+            }
+            Instruction::DeployVRC721(i) => {
+                let input = env.get_input(i.name_input).context("get name input")?;
+                // the name is not costed, it is kept as the owner of the collection.
+                self.events.push(Event::CollectionDeployed {
+                    name: i.name,
+                    meta: i.meta.clone(),
+                    input,
+                });
+            }
+            Instruction::CollectionMint(i) => {
+                let resource = Resource::vrc721(i.hash);
+                let output = env.get_output(i.output_index);
+                self.events.push(Event::Minted { resource, output });
+            }
+            Instruction::Move(i) => {
+                let inputs = Self::outpoints(context, costed.iter().copied())?;
+                let output = env.get_output(i.output_index);
+                self.events.push(Event::Moved { resource: i.resource.clone(), inputs, output });
+            }
+            Instruction::MoveAll(i) => {
+                let resource = moved_all.ok_or_else(|| anyhow!("not found the moved vrc20"))?;
+                let inputs = Self::outpoints(context, costed.iter().copied())?;
+                let output = env.get_output(i.output_index);
+                self.events.push(Event::Moved { resource, inputs, output });
+            }
+            Instruction::Burn(i) => {
+                let inputs = Self::outpoints(context, costed.iter().copied())?;
+                self.events.push(Event::Burned { resource: i.resource.clone(), inputs });
+            }
+            Instruction::ClaimSpace(i) => {
+                let input = env.get_input(i.index).context("get claim input")?;
+                self.events.push(Event::SpaceClaimed { resources: i.resources.clone(), input });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Collect the events for the uncosted resources, need call before apply the resources.
+    pub fn on_uncosted(&mut self, context: &impl ContextT) -> Result<()> {
+        let uncosted = context.input_resource().uncosted();
+        if uncosted.is_empty() {
+            return Ok(());
+        }
+
+        let outpoints_of = |resource: Option<&Resource>| {
+            let indexs = uncosted
+                .iter()
+                .filter(|(_, r)| {
+                    resource.map_or(true, |res| res.resource_type() == r.resource_type())
+                })
+                .map(|(index, _)| *index);
+
+            Self::outpoints(context, indexs)
+        };
+
+        let resources = context.input_resource().uncosted_merged();
+        match context.uncosted_policy() {
+            UncostedPolicy::ReturnToFirstOutput => {
+                // if no output, the apply will failed.
+                if let Some(index) = context.runner().first_asserted_output() {
+                    let output = context.env().get_output(index);
+                    for resource in resources {
+                        let inputs = outpoints_of(Some(&resource))?;
+                        self.events.push(Event::UncostedReturned { resource, inputs, output });
+                    }
+                }
+            }
+            UncostedPolicy::Space => {
+                let inputs = outpoints_of(None)?;
+                let space = *context.env().get_reveal_tx_id();
+                self.events.push(Event::UncostedStored { resources, inputs, space });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn into_events(self) -> Vec<Event> {
+        self.events
+    }
+
+    fn minted(context: &impl ContextT, resource_type: &ResourceType) -> Result<Resource> {
+        Ok(match resource_type {
+            ResourceType::Name { name } => Resource::name(*name),
+            ResourceType::VRC20 { name } => {
+                let status_data = context
+                    .env()
+                    .get_vrc20_metadata(*name)
+                    .context("get vrc20 metadata")?
+                    .ok_or_else(|| anyhow!("not found vrc20 metadata"))?;
+
+                Resource::VRC20(VRC20::new(*name, status_data.meta.mint.mint_amount.into()))
+            }
+            ResourceType::VRC721 { hash } => Resource::vrc721(*hash),
+        })
+    }
+
+    fn outpoints(
+        context: &impl ContextT,
+        indexs: impl Iterator<Item = u8>,
+    ) -> Result<Vec<OutPoint>> {
+        let mut res = Vec::new();
+
+        for index in indexs {
+            let outpoint = context
+                .env()
+                .get_input(index)
+                .with_context(|| alloc::format!("get input {}", index))?;
+            if !res.contains(&outpoint) {
+                res.push(outpoint);
+            }
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vital_script_ops::{
+        builder::instruction::ScriptBuilderFromInstructions,
+        instruction::{
+            assert_input::InstructionInputAssert, assert_output::InstructionOutputAssert,
+            resource_burn::InstructionResourceBurn, resource_deploy::InstructionVRC20Deploy,
+            resource_move::InstructionResourceMoveAll,
+        },
+    };
+    use vital_script_primitives::{
+        names::Name,
+        types::vrc20::{VRC20MetaData, VRC20MintMeta},
+        H256,
+    };
+
+    use super::*;
+    use crate::{mock::*, Runner};
+
+    fn vrc20(amount: u128) -> Resource {
+        Resource::vrc20("abcde", amount.into()).expect("vrc20")
+    }
+
+    #[test]
+    fn test_mint_and_deploy_events() {
+        let env_interface = EnvMock::new();
+        let name = Name::must_from("abcde");
+
+        let (ctx, events) = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::mint(0, ResourceType::name(name)),
+            ])
+            .with_ops()
+            .with_output(1000)
+            .run_with_events()
+            .expect("mint name should ok");
+
+        let name_output = ctx.env().get_output(0);
+        assert_eq!(
+            events,
+            vec![Event::Minted { resource: Resource::name(name), output: name_output }]
+        );
+
+        let meta = VRC20MetaData {
+            decimals: 5,
+            nonce: 1000000,
+            bworkc: 1000000,
+            mint: VRC20MintMeta { mint_amount: 1000, mint_height: 0, max_mints: 100 },
+            meta: None,
+        };
+        let (_, events) = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Input(InstructionInputAssert { index: 1, resource: name.into() }),
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::Deploy(InstructionVRC20Deploy {
+                    name_input: 1,
+                    name: name.into(),
+                    meta: meta.clone(),
+                }),
+            ])
+            .with_ops()
+            .with_input(name_output)
+            .with_output(1000)
+            .run_with_events()
+            .expect("deploy should ok");
+
+        assert_eq!(
+            events,
+            vec![
+                Event::Deployed { name: name.into(), meta, input: name_output },
+                Event::NameCosted { name: name.into(), input: name_output },
+            ]
+        );
+
+        let (ctx, events) = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::mint(0, ResourceType::vrc20(name)),
+            ])
+            .with_ops()
+            .with_output(1000)
+            .run_with_events()
+            .expect("mint vrc20 should ok");
+
+        assert_eq!(
+            events,
+            vec![Event::Minted { resource: vrc20(1000), output: ctx.env().get_output(0) }]
+        );
+    }
+
+    #[test]
+    fn test_move_and_burn_events() {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.deploy_vrc20("abcde", 1000);
+        let inputs = [ctx.mint_vrc20("abcde"), ctx.mint_vrc20("abcde")];
+        let hash = H256::random();
+        let vrc721_input = ctx.mint_vrc721(hash);
+
+        let (ctx, events) = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Input(InstructionInputAssert { index: 1, resource: vrc20(1000) }),
+                Instruction::Input(InstructionInputAssert { index: 2, resource: vrc20(1000) }),
+                Instruction::Input(InstructionInputAssert {
+                    index: 3,
+                    resource: Resource::vrc721(hash),
+                }),
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0, 1] }),
+                Instruction::move_to(0, vrc20(500)),
+                Instruction::Burn(InstructionResourceBurn { resource: vrc20(100) }),
+                Instruction::MoveAll(InstructionResourceMoveAll::new(
+                    1,
+                    ResourceType::vrc20(Name::must_from("abcde")),
+                )),
+                Instruction::Burn(InstructionResourceBurn { resource: Resource::vrc721(hash) }),
+            ])
+            .with_ops()
+            .with_input(inputs[0])
+            .with_input(inputs[1])
+            .with_input(vrc721_input)
+            .with_outputs(2, 1000)
+            .run_with_events()
+            .expect("move should ok");
+
+        assert_eq!(
+            events,
+            vec![
+                Event::Moved {
+                    resource: vrc20(500),
+                    inputs: vec![inputs[0]],
+                    output: ctx.env().get_output(0)
+                },
+                Event::Burned { resource: vrc20(100), inputs: vec![inputs[0]] },
+                Event::Moved {
+                    resource: vrc20(1400),
+                    inputs: inputs.to_vec(),
+                    output: ctx.env().get_output(1)
+                },
+                Event::Burned { resource: Resource::vrc721(hash), inputs: vec![vrc721_input] },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_uncosted_events() {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.deploy_vrc20("abcde", 1000);
+
+        let mut move_part = |policy| {
+            let input = ctx.mint_vrc20("abcde");
+            TestCtx::new(&env_interface)
+                .with_instructions(vec![
+                    Instruction::Input(InstructionInputAssert { index: 1, resource: vrc20(1000) }),
+                    Instruction::Output(InstructionOutputAssert { indexs: vec![0, 1] }),
+                    Instruction::move_to(1, vrc20(400)),
+                ])
+                .with_ops()
+                .with_input(input)
+                .with_outputs(2, 1000)
+                .with_uncosted_policy(policy)
+                .run_with_events()
+                .map(|(ctx, events)| (input, ctx, events))
+                .expect("move should ok")
+        };
+
+        let (input, ctx, events) = move_part(UncostedPolicy::Space);
+        assert_eq!(
+            events[1],
+            Event::UncostedStored {
+                resources: vec![vrc20(600)],
+                inputs: vec![input],
+                space: *ctx.env().get_reveal_tx_id()
+            }
+        );
+
+        let (input, ctx, events) = move_part(UncostedPolicy::ReturnToFirstOutput);
+        assert_eq!(
+            events[1],
+            Event::UncostedReturned {
+                resource: vrc20(600),
+                inputs: vec![input],
+                output: ctx.env().get_output(0)
+            }
+        );
+    }
+
+    #[test]
+    fn test_failed_tx_should_no_events() {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.mint_name("abcde");
+        let name_output = ctx.get_name_outpoint("abcde").expect("should mint");
+
+        let name = Name::must_from("abcde");
+        let ops_bytes = ScriptBuilderFromInstructions::build(vec![
+            Instruction::Input(InstructionInputAssert { index: 1, resource: name.into() }),
+            Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+            Instruction::move_to(0, name),
+            Instruction::move_to(0, name),
+        ])
+        .expect("build should ok");
+
+        let tx = TxMock::new().with_ops(ops_bytes).with_input(name_output).with_output(1000);
+        let mut context = ContextMock::new(tx, env_interface.clone());
+
+        let mut events = Vec::new();
+        let res = Runner::new().run_with_sink(&mut context, &mut events);
+
+        assert_err_str(res, "had already costed", "move name two times");
+        assert!(events.is_empty(), "the failed tx should no events");
+    }
+}
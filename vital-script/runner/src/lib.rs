@@ -15,7 +15,9 @@ use anyhow::{Context as AnyhowContext, Result};
 pub mod traits;
 
 mod context;
+mod events_collector;
 mod resource_cache;
+mod spent;
 
 #[cfg(feature = "std")]
 pub mod mock;
@@ -26,9 +28,16 @@ pub use context::{
     },
     Context, EnvContext,
 };
+pub use resource_cache::EnvJournal;
+pub use spent::park_spent_resources;
 
+use events_collector::EventsCollector;
+use traits::events::EventSink;
 use vital_script_ops::instruction::Instruction;
-use vital_script_primitives::traits::{Context as ContextT, Instruction as InstructionT};
+use vital_script_primitives::traits::{
+    Context as ContextT, InputResourcesContext as InputResourcesContextT,
+    Instruction as InstructionT, RunnerContext as RunnerContextT,
+};
 
 pub struct Runner<Context: ContextT<Instruction = Instruction>> {
     _marker: core::marker::PhantomData<Context>,
@@ -46,24 +55,67 @@ impl<Context: ContextT<Instruction = Instruction>> Runner<Context> {
     }
 
     pub fn run(&mut self, context: &mut Context) -> Result<()> {
+        self.run_with_sink(context, &mut ())
+    }
+
+    /// Run the instructions, the events will be emitted into the sink after all success.
+    /// Note the inputs is unknown in simulator mode, so no events will be emitted.
+    ///
+    /// If the tx has multiple scripts, they will run one by one in the order of their inputs,
+    /// see `RunnerContext` for the isolation between them, the tx failed if any script failed.
+    pub fn run_with_sink(
+        &mut self,
+        context: &mut Context,
+        sink: &mut impl EventSink,
+    ) -> Result<()> {
         log::debug!(target: TARGET, "run instructions");
 
-        let instructions = context.get_instructions().context("get instructions")?;
-        log::debug!(target: TARGET, "run instructions len {}", instructions.len());
+        let scripts = context.get_instructions().context("get instructions")?;
+        log::debug!(target: TARGET, "run scripts len {}", scripts.len());
+
+        let mut collector = (!context.run_mod().is_skip_check()).then(EventsCollector::new);
+
+        // 2. run opcodes by the scripts in the order of the inputs, cost input resources,
+        //    call env traits.
+        for (input_index, instructions) in scripts.iter() {
+            context.runner_mut().enter_script(*input_index);
+
+            for (index, instruction) in instructions.iter().enumerate() {
+                log::debug!(target: TARGET, "run instruction {} : {}", index, instruction);
+
+                let moved_all = EventsCollector::moved_all(context, instruction);
 
-        // 2. run opcodes, cost input resources, call env traits.
-        for (index, instruction) in instructions.iter().enumerate() {
-            log::debug!(target: TARGET, "run instruction {} : {}", index, instruction);
+                instruction.exec(context).with_context(|| {
+                    alloc::format!("execute {} in script {}", index, input_index)
+                })?;
 
-            instruction.exec(context).with_context(|| alloc::format!("execute {}", index))?;
+                // the inputs costed by the instruction, for the events of moved and burned.
+                let costed = context.input_resource_mut().take_costed();
+
+                if let Some(collector) = collector.as_mut() {
+                    collector
+                        .on_instruction(context, instruction, moved_all, &costed)
+                        .with_context(|| alloc::format!("events by {}", index))?;
+                }
+            }
+
+            context.runner_mut().exit_script();
         }
 
         // 3. post check
         context.post_check().context("post check")?;
 
+        if let Some(collector) = collector.as_mut() {
+            collector.on_uncosted(context).context("events by uncosted")?;
+        }
+
         // 4. apply the resources
         context.apply_resources().context("apply")?;
 
+        for event in collector.map(|c| c.into_events()).unwrap_or_default() {
+            sink.emit(event);
+        }
+
         Ok(())
     }
 }
@@ -78,8 +130,7 @@ mod tests {
         },
     };
     use vital_script_primitives::{
-        names::Name,
-        resources::{Resource, ResourceType},
+        resources::{Resource, ResourceType, Tag},
         traits::{Context as ContextT, EnvContext, MetaDataType},
         types::vrc20::{VRC20MetaData, VRC20MintMeta},
     };
@@ -101,7 +152,7 @@ mod tests {
     fn test_simple_runner() {
         init_logger();
 
-        let mint_name = Name::try_from("abcdefg".to_string()).unwrap();
+        let mint_name = Tag::try_from("abcdefg".to_string()).unwrap();
         let instructions = vec![
             Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
             Instruction::mint(0, ResourceType::name(mint_name)),
@@ -116,6 +167,8 @@ mod tests {
         let mut context = ContextMock::new(tx_mock, EnvMock::new());
 
         Runner::new().run(&mut context).expect("run failed");
+
+        context.commit().expect("commit failed");
     }
 
     #[test]
@@ -123,7 +176,7 @@ mod tests {
         init_logger();
 
         let mint_name_str = "abcdefg";
-        let mint_name = Name::try_from(mint_name_str.to_string()).unwrap();
+        let mint_name = Tag::try_from(mint_name_str.to_string()).unwrap();
         let mint_amount = 10000000_u128;
 
         let env_interface = EnvMock::new();
@@ -231,7 +284,7 @@ mod tests {
         init_logger();
 
         let mint_name_str = "abc";
-        let mint_name = Name::try_from(mint_name_str.to_string()).unwrap();
+        let mint_name = Tag::try_from(mint_name_str.to_string()).unwrap();
         let mint_amount = 10000000_u128;
 
         let env_interface = EnvMock::new();
@@ -250,6 +303,7 @@ mod tests {
 
             let mut context = ContextMock::new(tx_mock1, env_interface.clone());
             Runner::new().run(&mut context).expect("run failed");
+            context.commit().expect("commit failed");
 
             context
         };
@@ -298,6 +352,7 @@ mod tests {
 
             let mut context = ContextMock::new(tx_mock2, env_interface.clone());
             Runner::new().run(&mut context).expect("run failed");
+            context.commit().expect("commit failed");
 
             context
         };
@@ -337,6 +392,7 @@ mod tests {
 
             let mut context = ContextMock::new(tx_mock3, env_interface.clone());
             Runner::new().run(&mut context).expect("run failed");
+            context.commit().expect("commit failed");
 
             let outpoint = context.env().get_output(0);
             let res = env_interface.get_resources(&outpoint).expect("get resources failed");
@@ -369,6 +425,7 @@ mod tests {
 
             let mut context = ContextMock::new(tx_mock4, env_interface.clone());
             Runner::new().run(&mut context).expect("run failed");
+            context.commit().expect("commit failed");
 
             context
         };
@@ -1,6 +1,9 @@
 use std::sync::Mutex;
 
-use alloc::{collections::BTreeMap, sync::Arc};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 use anyhow::{Context as AnyhowContext, Result};
 
 use bitcoin::{
@@ -10,19 +13,28 @@ use bitcoin::{
 use vital_script_ops::{
     builder::instruction::ScriptBuilderFromInstructions,
     instruction::{
-        assert_input::InstructionInputAssert, assert_output::InstructionOutputAssert,
-        resource_deploy::InstructionVRC20Deploy, Instruction,
+        assert_input::InstructionInputAssert,
+        assert_output::InstructionOutputAssert,
+        resource_deploy::{InstructionVRC20Deploy, InstructionVRC721Deploy},
+        Instruction,
     },
-    parser::Parser,
 };
 use vital_script_primitives::{
-    resources::{Name, Resource, ResourceType},
-    traits::{Context as ContextT, EnvContext, RunMode},
-    types::vrc20::{VRC20MetaData, VRC20MintMeta},
+    consts::PROTOCOL_VERSION,
+    resources::{Resource, ResourceType, Tag},
+    traits::{Context as ContextT, EnvContext, RunMode, UncostedPolicy},
+    types::{
+        vrc20::{VRC20MetaData, VRC20MintMeta},
+        vrc721::VRC721MetaData,
+    },
     H256,
 };
 
-use crate::{traits::EnvFunctions, Context, Runner, TARGET};
+use crate::{
+    context::{parse_instructions, pre_check_instructions},
+    traits::{events::Event, EnvFunctions},
+    Context, EnvJournal, Runner, TARGET,
+};
 
 pub fn assert_err_str<T>(res: Result<T>, str: &str, reason: &str) {
     let res = res
@@ -40,6 +52,8 @@ pub struct TxMock {
     pub reveal: Transaction,
     pub reveal_txid: Txid,
     pub seq: u32,
+    /// The inputs of the scripts whose commit tx is in the same block.
+    pub commits_in_curr_block: BTreeSet<u8>,
     ops_bytes: Vec<(u8, Vec<u8>)>,
 }
 
@@ -59,7 +73,13 @@ impl TxMock {
         };
         let txid = tx.txid();
 
-        Self { reveal: tx, reveal_txid: txid, ops_bytes: Vec::new(), seq: 0 }
+        Self {
+            reveal: tx,
+            reveal_txid: txid,
+            ops_bytes: Vec::new(),
+            seq: 0,
+            commits_in_curr_block: BTreeSet::new(),
+        }
     }
 
     /// Add a ext count, just for make txid not eq.
@@ -184,20 +204,58 @@ impl EnvFunctions for EnvMock {
 
         Ok(())
     }
+
+    fn storage_remove(&self, key: &[u8]) -> Result<()> {
+        self.storage.lock().expect("lock").remove(key);
+
+        Ok(())
+    }
 }
 
-pub type ContextMockInner = Context<EnvMock>;
+/// The block height for all the txs run in mock context.
+pub const MOCK_BLOCK_HEIGHT: u32 = 10000;
+
+pub type ContextMockInner = Context<EnvJournal<EnvMock>>;
 
 pub struct ContextMock {
     inner: ContextMockInner,
+    journal: EnvJournal<EnvMock>,
     tx: TxMock,
 }
 
 impl ContextMock {
     pub fn new(tx: TxMock, env: EnvMock) -> Self {
-        log::info!("new context mock {}", tx.reveal_txid);
+        Self::new_by_version(tx, env, PROTOCOL_VERSION)
+    }
+
+    /// New the context mock to run the tx by the protocol version.
+    pub fn new_by_version(tx: TxMock, env: EnvMock, protocol_version: u32) -> Self {
+        log::info!("new context mock {} by version {}", tx.reveal_txid, protocol_version);
+
+        let journal = EnvJournal::new(env);
 
-        Self { inner: ContextMockInner::new(env, &tx.reveal, 10000), tx }
+        Self {
+            inner: ContextMockInner::new(
+                journal.clone(),
+                &tx.reveal,
+                MOCK_BLOCK_HEIGHT,
+                protocol_version,
+            )
+            .expect("the protocol version should be valid")
+            .with_commits_in_curr_block(tx.commits_in_curr_block.clone()),
+            journal,
+            tx,
+        }
+    }
+
+    pub fn with_uncosted_policy(mut self, policy: UncostedPolicy) -> Self {
+        self.inner = self.inner.with_uncosted_policy(policy);
+        self
+    }
+
+    /// Commit the changes to the env mock, just like the block runner when the tx run success.
+    pub fn commit(&self) -> Result<()> {
+        self.journal.commit()
     }
 }
 
@@ -212,6 +270,14 @@ impl ContextT for ContextMock {
         RunMode::Normal
     }
 
+    fn uncosted_policy(&self) -> UncostedPolicy {
+        self.inner.uncosted_policy()
+    }
+
+    fn protocol_version(&self) -> u32 {
+        self.inner.protocol_version()
+    }
+
     fn env(&self) -> &Self::Env {
         self.inner.env()
     }
@@ -240,31 +306,12 @@ impl ContextT for ContextMock {
         &self.tx.ops_bytes
     }
 
-    fn get_instructions(&self) -> Result<Vec<Self::Instruction>> {
-        let ops_bytes = self.get_ops();
-        let ins = ops_bytes
-            .iter()
-            .map(|(index, ops)| {
-                Parser::new(ops).parse().with_context(|| format!("parse {}", index))
-            })
-            .collect::<Result<Vec<_>>>()?;
-
-        Ok(ins.concat())
+    fn get_instructions(&self) -> Result<Vec<(u8, Vec<Self::Instruction>)>> {
+        parse_instructions(self.get_ops())
     }
 
     fn pre_check(&self) -> Result<()> {
-        // TODO: just use context 's impl, not copy
-        use vital_script_primitives::traits::Instruction;
-
-        let instructions = self.get_instructions().context("get instructions")?;
-
-        for (index, instruction) in instructions.iter().enumerate() {
-            instruction
-                .pre_check()
-                .with_context(|| alloc::format!("instruction {}", index))?;
-        }
-
-        Ok(())
+        pre_check_instructions(&self.get_instructions().context("get instructions")?)
     }
 
     fn post_check(&self) -> Result<()> {
@@ -276,6 +323,9 @@ pub struct TestCtx {
     pub ops_bytes: Vec<Vec<u8>>,
     tx: TxMock,
     env_interface: EnvMock,
+    /// The policy for the uncosted inputs, if None, by the protocol version.
+    uncosted_policy: Option<UncostedPolicy>,
+    protocol_version: u32,
 
     /// A count auto increment for make txid not eq.
     count: u32,
@@ -287,6 +337,8 @@ impl TestCtx {
             ops_bytes: Vec::new(),
             tx: TxMock::new(),
             env_interface: env_interface.clone(),
+            uncosted_policy: None,
+            protocol_version: PROTOCOL_VERSION,
             count: 1,
         }
     }
@@ -310,6 +362,15 @@ impl TestCtx {
         self
     }
 
+    /// Push all the ops by the `with_instructions`, each one as a script in a new input.
+    pub fn with_all_ops(mut self) -> Self {
+        for bytes in self.ops_bytes.iter() {
+            log::debug!(target: TARGET, "with ops bytes: {}", hex::encode(bytes));
+            self.tx.push_ops(bytes.clone());
+        }
+        self
+    }
+
     pub fn with_ops_bytes(mut self, ops_bytes: &[u8]) -> Self {
         let bytes = ops_bytes.to_vec();
         log::debug!(target: TARGET, "with ops bytes: {}", hex::encode(&bytes));
@@ -331,23 +392,58 @@ impl TestCtx {
         self
     }
 
+    /// Make the commit txs of all the scripts in the same block with the reveal tx.
+    pub fn with_commit_in_curr_block(mut self) -> Self {
+        self.tx.commits_in_curr_block = self.tx.ops_bytes.iter().map(|(index, _)| *index).collect();
+        self
+    }
+
+    pub fn with_uncosted_policy(mut self, policy: UncostedPolicy) -> Self {
+        self.uncosted_policy = Some(policy);
+        self
+    }
+
+    pub fn with_protocol_version(mut self, protocol_version: u32) -> Self {
+        self.protocol_version = protocol_version;
+        self
+    }
+
+    /// The reveal txid for the tx to run.
+    pub fn reveal_txid(&self) -> Txid {
+        self.tx.reveal_txid
+    }
+
     pub fn run(&mut self) -> Result<ContextMock> {
-        let mut context = ContextMock::new(self.tx.clone(), self.env_interface.clone());
+        self.run_with_events().map(|(context, _)| context)
+    }
+
+    /// Run the tx, return the events emitted by the runner.
+    pub fn run_with_events(&mut self) -> Result<(ContextMock, Vec<Event>)> {
+        let mut context = ContextMock::new_by_version(
+            self.tx.clone(),
+            self.env_interface.clone(),
+            self.protocol_version,
+        );
+        if let Some(policy) = self.uncosted_policy {
+            context = context.with_uncosted_policy(policy);
+        }
 
         context.pre_check().context("context pre check")?;
 
-        Runner::new().run(&mut context)?;
+        let mut events = Vec::new();
+        Runner::new().run_with_sink(&mut context, &mut events)?;
+        context.commit().context("commit")?;
 
-        Ok(context)
+        Ok((context, events))
     }
 
     pub fn get_name_outpoint(&self, name: impl Into<String>) -> Option<OutPoint> {
         self.env_interface
-            .get_outpoint(&Resource::Name(Name::try_from(name.into()).expect("name failed")))
+            .get_outpoint(&Resource::Name(Tag::try_from(name.into()).expect("name failed")))
     }
 
     pub fn mint_name(&mut self, name: impl Into<String>) {
-        let mint_name = Name::try_from(name.into()).expect("the name format not supported");
+        let mint_name = Tag::try_from(name.into()).expect("the name format not supported");
 
         let ops_bytes = ScriptBuilderFromInstructions::build(vec![
             Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
@@ -363,6 +459,7 @@ impl TestCtx {
 
         let mut context = ContextMock::new(tx_mock, self.env_interface.clone());
         Runner::new().run(&mut context).expect("run failed");
+        context.commit().expect("commit failed");
     }
 
     pub fn deploy_vrc20(&mut self, name: impl Into<String>, mint_amount: u128) {
@@ -375,6 +472,19 @@ impl TestCtx {
         mint_amount: u128,
         max_count: u64,
     ) {
+        self.deploy_vrc20_with_meta(
+            name,
+            VRC20MetaData {
+                decimals: 5,
+                nonce: 1000000,
+                bworkc: 1000000,
+                mint: VRC20MintMeta { mint_amount, mint_height: 0, max_mints: max_count },
+                meta: None,
+            },
+        )
+    }
+
+    pub fn deploy_vrc20_with_meta(&mut self, name: impl Into<String>, meta: VRC20MetaData) {
         let name = name.into();
 
         // if not mint name, just mint it.
@@ -382,7 +492,7 @@ impl TestCtx {
             self.mint_name(name.clone());
         }
 
-        let mint_name = Name::try_from(name.clone()).unwrap();
+        let mint_name = Tag::try_from(name.clone()).unwrap();
 
         // 2. deploy a vrc20 by the name
         let ops_bytes = ScriptBuilderFromInstructions::build(vec![
@@ -391,22 +501,51 @@ impl TestCtx {
                 resource: Resource::Name(mint_name),
             }),
             Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
-            Instruction::Deploy(InstructionVRC20Deploy {
+            Instruction::Deploy(InstructionVRC20Deploy { name_input: 0, name: mint_name, meta }),
+        ])
+        .expect("build should ok");
+
+        log::info!("ops_bytes: {:?}", hex::encode(&ops_bytes));
+
+        let mut tx_mock = TxMock::new().with_ext(self.count);
+        tx_mock.push_input(self.get_name_outpoint(name).expect("not found name outpoint"));
+        tx_mock.push_ops(ops_bytes);
+        tx_mock.push_output(2000);
+
+        self.count += 1;
+
+        let mut context = ContextMock::new(tx_mock, self.env_interface.clone());
+        Runner::new().run(&mut context).expect("run failed");
+        context.commit().expect("commit failed");
+    }
+
+    pub fn deploy_vrc721(&mut self, name: impl Into<String>, max_supply: u64) {
+        let name = name.into();
+
+        // if not mint name, just mint it.
+        if self.get_name_outpoint(name.clone()).is_none() {
+            self.mint_name(name.clone());
+        }
+
+        let mint_name = Tag::try_from(name.clone()).unwrap();
+        let meta = VRC721MetaData { max_supply, royalty: 0, meta: None };
+
+        let ops_bytes = ScriptBuilderFromInstructions::build(vec![
+            Instruction::Input(InstructionInputAssert {
+                index: 0, // Note this tx will push input first into the tx
+                resource: Resource::Name(mint_name),
+            }),
+            Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+            Instruction::DeployVRC721(InstructionVRC721Deploy {
                 name_input: 0,
                 name: mint_name,
-                meta: VRC20MetaData {
-                    decimals: 5,
-                    nonce: 1000000,
-                    bworkc: 1000000,
-                    mint: VRC20MintMeta { mint_amount, mint_height: 0, max_mints: max_count },
-                    meta: None,
-                },
+                meta,
             }),
+            // keep the name as the owner of the collection.
+            Instruction::move_to(0, Resource::Name(mint_name)),
         ])
         .expect("build should ok");
 
-        log::info!("ops_bytes: {:?}", hex::encode(&ops_bytes));
-
         let mut tx_mock = TxMock::new().with_ext(self.count);
         tx_mock.push_input(self.get_name_outpoint(name).expect("not found name outpoint"));
         tx_mock.push_ops(ops_bytes);
@@ -416,10 +555,11 @@ impl TestCtx {
 
         let mut context = ContextMock::new(tx_mock, self.env_interface.clone());
         Runner::new().run(&mut context).expect("run failed");
+        context.commit().expect("commit failed");
     }
 
     pub fn mint_vrc20(&mut self, name: impl Into<String>) -> OutPoint {
-        let mint_name = Name::try_from(name.into()).unwrap();
+        let mint_name = Tag::try_from(name.into()).unwrap();
 
         let ops_bytes = ScriptBuilderFromInstructions::build(vec![
             Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
@@ -437,6 +577,7 @@ impl TestCtx {
 
         let mut context = ContextMock::new(tx_mock3, self.env_interface.clone());
         Runner::new().run(&mut context).expect("run failed");
+        context.commit().expect("commit failed");
 
         let outpoint = context.env().get_output(0);
         let res = self
@@ -465,6 +606,7 @@ impl TestCtx {
 
         let mut context = ContextMock::new(tx_mock, self.env_interface.clone());
         Runner::new().run(&mut context).expect("run failed");
+        context.commit().expect("commit failed");
 
         let outpoint = context.env().get_output(0);
         let res = self
@@ -478,3 +620,104 @@ impl TestCtx {
         outpoint
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use vital_script_primitives::resources::Tag;
+
+    use super::*;
+
+    fn storage_snapshot(env_interface: &EnvMock) -> BTreeMap<Vec<u8>, Vec<u8>> {
+        env_interface.storage.lock().expect("lock").clone()
+    }
+
+    fn resources_snapshot(env_interface: &EnvMock) -> BTreeMap<OutPoint, Resource> {
+        env_interface.resource_storage.lock().expect("lock").clone()
+    }
+
+    #[test]
+    fn test_failed_mint_should_not_change_storage() {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.mint_name("abc");
+
+        let storage = storage_snapshot(&env_interface);
+        let resources = resources_snapshot(&env_interface);
+
+        // the name will be flagged before send to output, the output not asserted so will failed.
+        let res = TestCtx::new(&env_interface)
+            .with_instructions(vec![Instruction::mint(
+                0,
+                ResourceType::name(Tag::must_from("abcdef")),
+            )])
+            .with_ops()
+            .with_output(1000)
+            .run();
+
+        assert_err_str(res, "the output is not asserted", "mint to not asserted output");
+
+        assert_eq!(storage_snapshot(&env_interface), storage);
+        assert_eq!(resources_snapshot(&env_interface), resources);
+
+        // the name can be mint after the failed tx.
+        ctx.mint_name("abcdef");
+        assert!(ctx.get_name_outpoint("abcdef").is_some());
+    }
+
+    #[test]
+    fn test_failed_deploy_should_not_change_storage() {
+        let env_interface = EnvMock::new();
+        let mut ctx = TestCtx::new(&env_interface);
+
+        ctx.mint_name("abcde");
+
+        let name = Tag::must_from("abcde");
+        let name_res = Resource::name(name);
+        let name_outpoint = ctx.get_name_outpoint("abcde").expect("should exist");
+
+        let storage = storage_snapshot(&env_interface);
+        let resources = resources_snapshot(&env_interface);
+
+        // the vrc20 metadata will be set before the name moved, the name had costed so will failed.
+        let res = TestCtx::new(&env_interface)
+            .with_instructions(vec![
+                Instruction::Input(InstructionInputAssert { index: 1, resource: name_res.clone() }),
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::Deploy(InstructionVRC20Deploy {
+                    name_input: 1,
+                    name,
+                    meta: VRC20MetaData {
+                        decimals: 5,
+                        nonce: 1000000,
+                        bworkc: 1000000,
+                        mint: VRC20MintMeta { mint_amount: 1000, mint_height: 0, max_mints: 100 },
+                        meta: None,
+                    },
+                }),
+                Instruction::move_to(0, name_res),
+            ])
+            .with_ops()
+            .with_input(name_outpoint)
+            .with_output(1000)
+            .run();
+
+        assert_err_str(res, "had already costed", "move a costed name");
+
+        assert_eq!(storage_snapshot(&env_interface), storage);
+        assert_eq!(resources_snapshot(&env_interface), resources);
+
+        let metadata = crate::EnvContext::new_for_query(env_interface.clone(), MOCK_BLOCK_HEIGHT)
+            .get_vrc20_metadata(name)
+            .expect("get metadata");
+        assert!(metadata.is_none());
+
+        // the vrc20 can deploy after the failed tx.
+        ctx.deploy_vrc20("abcde", 1000);
+
+        let metadata = crate::EnvContext::new_for_query(env_interface.clone(), MOCK_BLOCK_HEIGHT)
+            .get_vrc20_metadata(name)
+            .expect("get metadata");
+        assert!(metadata.is_some());
+    }
+}
@@ -1 +1,140 @@
 //! A Resource cache for runner
+//!
+//! The `EnvJournal` wraps the env functions, all the changes by a tx will be
+//! cached in it, and only be written into the inner env by `commit`, so if the
+//! tx run failed, just drop the journal and the env will not be changed.
+
+use alloc::{collections::BTreeMap, rc::Rc, vec::Vec};
+use core::cell::RefCell;
+
+use anyhow::{bail, Context as AnyhowContext, Result};
+use bitcoin::OutPoint;
+
+use vital_script_primitives::resources::Resource;
+
+use crate::{traits::EnvFunctions, TARGET};
+
+/// The changes to env, will apply by the order.
+#[derive(Debug, Clone)]
+enum JournalOp {
+    Bind(OutPoint, Resource),
+    Unbind(OutPoint),
+    StorageSet(Vec<u8>, Vec<u8>),
+    StorageRemove(Vec<u8>),
+}
+
+#[derive(Debug, Default)]
+struct Journal {
+    /// The resources changed, `None` means it had been unbind.
+    resources: BTreeMap<OutPoint, Option<Resource>>,
+    /// The storage changed, `None` means it had been removed.
+    storage: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+    ops: Vec<JournalOp>,
+}
+
+/// A journaled overlay for the env functions.
+#[derive(Debug, Clone)]
+pub struct EnvJournal<Functions: EnvFunctions> {
+    inner: Functions,
+    journal: Rc<RefCell<Journal>>,
+}
+
+impl<Functions: EnvFunctions> EnvJournal<Functions> {
+    pub fn new(inner: Functions) -> Self {
+        Self { inner, journal: Rc::new(RefCell::new(Journal::default())) }
+    }
+
+    /// Write all the cached changes into the inner env.
+    pub fn commit(&self) -> Result<()> {
+        let journal = core::mem::take(&mut *self.journal.borrow_mut());
+
+        log::debug!(target: TARGET, "commit {} changes to env", journal.ops.len());
+
+        for op in journal.ops.into_iter() {
+            match op {
+                JournalOp::Bind(output, res) => self
+                    .inner
+                    .bind_resource(output, res)
+                    .with_context(|| alloc::format!("bind resource to {}", output))?,
+                JournalOp::Unbind(input) => self
+                    .inner
+                    .unbind_resource(&input)
+                    .with_context(|| alloc::format!("unbind resource from {}", input))?,
+                JournalOp::StorageSet(key, value) => {
+                    self.inner.storage_set(key, value).context("storage set")?
+                }
+                JournalOp::StorageRemove(key) => {
+                    self.inner.storage_remove(&key).context("storage remove")?
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<Functions: EnvFunctions> EnvFunctions for EnvJournal<Functions> {
+    fn get_resources(&self, input_id: &OutPoint) -> Result<Option<Resource>> {
+        if let Some(res) = self.journal.borrow().resources.get(input_id) {
+            return Ok(res.clone());
+        }
+
+        self.inner.get_resources(input_id)
+    }
+
+    fn maybe_bound(&self, outpoint: &OutPoint) -> bool {
+        if let Some(res) = self.journal.borrow().resources.get(outpoint) {
+            return res.is_some();
+        }
+
+        self.inner.maybe_bound(outpoint)
+    }
+
+    fn bind_resource(&self, output: OutPoint, res: Resource) -> Result<()> {
+        if self.get_resources(&output)?.is_some() {
+            bail!("the resource had bind to {}", output);
+        }
+
+        let mut journal = self.journal.borrow_mut();
+        journal.resources.insert(output, Some(res.clone()));
+        journal.ops.push(JournalOp::Bind(output, res));
+
+        Ok(())
+    }
+
+    fn unbind_resource(&self, input: &OutPoint) -> Result<()> {
+        if self.get_resources(input)?.is_none() {
+            bail!("not found resource to unbind by {}", input);
+        }
+
+        let mut journal = self.journal.borrow_mut();
+        journal.resources.insert(*input, None);
+        journal.ops.push(JournalOp::Unbind(*input));
+
+        Ok(())
+    }
+
+    fn storage_get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        if let Some(value) = self.journal.borrow().storage.get(key) {
+            return Ok(value.clone());
+        }
+
+        self.inner.storage_get(key)
+    }
+
+    fn storage_set(&self, key: Vec<u8>, value: Vec<u8>) -> Result<()> {
+        let mut journal = self.journal.borrow_mut();
+        journal.storage.insert(key.clone(), Some(value.clone()));
+        journal.ops.push(JournalOp::StorageSet(key, value));
+
+        Ok(())
+    }
+
+    fn storage_remove(&self, key: &[u8]) -> Result<()> {
+        let mut journal = self.journal.borrow_mut();
+        journal.storage.insert(key.to_vec(), None);
+        journal.ops.push(JournalOp::StorageRemove(key.to_vec()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+//! The resources spent outside of the vital scripts.
+//!
+//! A resource is bind to an outpoint, when the outpoint is spent but the resource not handled
+//! by a vital script, such as the tx is not a vital tx, the tx run failed, or its scripts not
+//! assert the input, the resource will be parked into the space of the tx which spent it.
+//!
+//! Just like the uncosted resources, the tx which spend the first output of it can claim them,
+//! so a commit tx can carry the resources to its reveal tx.
+
+use alloc::vec::Vec;
+use anyhow::{anyhow, Context, Result};
+use bitcoin::Transaction;
+use parity_scale_codec::{Decode, Encode};
+
+use vital_script_primitives::resources::Resource;
+
+use crate::{
+    context::space_key,
+    traits::{events::Event, EnvFunctions},
+    TARGET,
+};
+
+/// Park the resources still bind to the inputs of the tx into its space, should be called
+/// after the tx 's vital scripts had been run, return the `Event::Parked` if any.
+pub fn park_spent_resources<Functions: EnvFunctions>(
+    tx: &Transaction,
+    env: &Functions,
+) -> Result<Option<Event>> {
+    let mut resources = Vec::new();
+    let mut inputs = Vec::new();
+
+    // only lookup the inputs may be bound, most of the inputs in a block are not.
+    for outpoint in tx.input.iter().map(|input| input.previous_output) {
+        if !env.maybe_bound(&outpoint) {
+            continue;
+        }
+
+        if let Some(resource) = env
+            .get_resources(&outpoint)
+            .with_context(|| alloc::format!("get resources {}", outpoint))?
+        {
+            env.unbind_resource(&outpoint)
+                .with_context(|| alloc::format!("unbind {}", outpoint))?;
+
+            resources.push(resource);
+            inputs.push(outpoint);
+        }
+    }
+
+    if resources.is_empty() {
+        return Ok(None);
+    }
+
+    let txid = tx.txid();
+    log::info!(target: TARGET, "park {} spent resources into the space of {}", resources.len(), txid);
+
+    // the space may had the uncosted resources by the tx.
+    let key = space_key(&txid);
+    let mut space = env
+        .storage_get(&key)
+        .context("get space")?
+        .map(|datas| Vec::<Resource>::decode(&mut datas.as_slice()))
+        .transpose()
+        .map_err(|err| anyhow!("decode space failed by {:?}", err))?
+        .unwrap_or_default();
+    space.extend(resources.iter().cloned());
+
+    env.storage_set(key, space.encode()).context("set space")?;
+
+    Ok(Some(Event::Parked { resources, inputs, space: txid }))
+}
@@ -11,7 +11,14 @@ pub trait EnvFunctions: Clone {
     fn get_resources(&self, input_id: &OutPoint) -> Result<Option<Resource>>;
     fn bind_resource(&self, output: OutPoint, res: Resource) -> Result<()>;
     fn unbind_resource(&self, input: &OutPoint) -> Result<()>;
+    /// If the outpoint may have a resource bind, used to skip the lookups for the outpoints
+    /// never bound, such as most of the inputs of the txs in a block, default is true.
+    fn maybe_bound(&self, _outpoint: &OutPoint) -> bool {
+        true
+    }
 
     fn storage_get(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
     fn storage_set(&self, key: Vec<u8>, value: Vec<u8>) -> Result<()>;
+    /// Remove the value by the key, do nothing if not exist.
+    fn storage_remove(&self, key: &[u8]) -> Result<()>;
 }
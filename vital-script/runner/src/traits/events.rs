@@ -1 +1,55 @@
+//! The events emitted by the runner
+//!
+//! The indexer and the wallet can use the events to know what a tx did to the
+//! resources, no need to diff the resources before and after the tx.
 
+use alloc::vec::Vec;
+
+use bitcoin::{OutPoint, Txid};
+
+use vital_script_primitives::{
+    resources::{Resource, Tag},
+    types::{vrc20::VRC20MetaData, vrc721::VRC721MetaData},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A resource minted to the output.
+    Minted { resource: Resource, output: OutPoint },
+    /// A resource moved from the inputs to the output.
+    Moved { resource: Resource, inputs: Vec<OutPoint>, output: OutPoint },
+    /// A resource from the inputs burned.
+    Burned { resource: Resource, inputs: Vec<OutPoint> },
+    /// A vrc20 deployed by the name from the input.
+    Deployed { name: Tag, meta: VRC20MetaData, input: OutPoint },
+    /// A vrc721 collection deployed by the name from the input.
+    CollectionDeployed { name: Tag, meta: VRC721MetaData, input: OutPoint },
+    /// A name from the input costed, it will not be bind to any output.
+    NameCosted { name: Tag, input: OutPoint },
+    /// A uncosted resource from the inputs returned to the output.
+    UncostedReturned { resource: Resource, inputs: Vec<OutPoint>, output: OutPoint },
+    /// The uncosted resources from the inputs stored into the space of the tx.
+    UncostedStored { resources: Vec<Resource>, inputs: Vec<OutPoint>, space: Txid },
+    /// The resources in a space claimed by the input.
+    SpaceClaimed { resources: Vec<Resource>, input: OutPoint },
+    /// The resources from the inputs not handled by any vital script parked into the space
+    /// of the tx which spent them.
+    Parked { resources: Vec<Resource>, inputs: Vec<OutPoint>, space: Txid },
+}
+
+/// The sink to receive the events, the runner will only emit the events after the tx
+/// run success.
+pub trait EventSink {
+    fn emit(&mut self, event: Event);
+}
+
+impl EventSink for Vec<Event> {
+    fn emit(&mut self, event: Event) {
+        self.push(event);
+    }
+}
+
+/// Drop all the events.
+impl EventSink for () {
+    fn emit(&mut self, _event: Event) {}
+}
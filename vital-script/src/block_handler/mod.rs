@@ -1,20 +1,28 @@
-use alloc::vec::Vec;
+use alloc::{collections::BTreeSet, vec::Vec};
 use anyhow::{Context as AnyhowContext, Result};
 
 use bitcoin::{Block, Transaction, Txid};
 
 use vital_script_primitives::traits::Context as ContextT;
 use vital_script_runner::{
-    check_is_vital_script, maybe_vital_commit_tx_with_input_resource, traits::EnvFunctions,
-    Context, Runner,
+    check_is_vital_script, park_spent_resources,
+    traits::{events::Event, EnvFunctions},
+    Context, EnvJournal, Runner,
 };
 
 use crate::TARGET;
 
+mod undo;
+
+use undo::UndoRecorder;
+pub use undo::MAX_ROLLBACK_DEPTH;
+
 #[derive(Debug, Clone)]
 pub enum TxRunStatus {
     Success,
     Failed,
+    /// Not a vital tx, but it spent the outpoints with resources, see `Event::Parked`.
+    NotVital,
 }
 
 #[derive(Debug, Clone)]
@@ -22,7 +30,8 @@ pub struct TxRunResponse {
     pub status: TxRunStatus,
     pub tx_index: u32,
     pub tx_id: Txid,
-    pub resp: (),
+    /// The events by the tx, only the `Event::Parked` if the tx run failed or not a vital tx.
+    pub events: Vec<Event>,
 }
 
 #[derive(Debug, Clone)]
@@ -33,66 +42,141 @@ pub struct BlockRunResponse {
 pub struct BlockRunner<'a> {
     block: &'a Block,
     height: u32,
+    /// The protocol version to run the txs, decide how to handle the uncosted resources.
+    protocol_version: u32,
 }
 
 impl<'a> BlockRunner<'a> {
-    pub fn new(block: &'a Block, height: u32) -> Self {
-        Self { block, height }
+    pub fn new(block: &'a Block, height: u32, protocol_version: u32) -> Self {
+        Self { block, height, protocol_version }
     }
 
+    /// Run all the txs in the block, the undo record for the block will be saved into the env,
+    /// so it can be disconnected by `rollback`.
     pub fn run<Functions>(&self, env_interface: Functions) -> Result<BlockRunResponse>
     where
         Functions: EnvFunctions,
     {
+        let env_interface = UndoRecorder::new(env_interface);
+
         let mut res = Vec::with_capacity(self.block.txdata.len());
+        let mut txids_in_block = BTreeSet::new();
 
         for (index, tx) in self.block.txdata.iter().enumerate() {
             let index = index as u32;
             let tx_id = tx.txid();
             log::debug!(target: TARGET, "run tx index {}, {} on {}", index, tx_id, self.height);
 
-            if maybe_vital_commit_tx_with_input_resource(tx, &env_interface)
-                .context("maybe_vital_commit_tx_with_input_resource")?
-            {
-                log::info!(target: TARGET, "handle vital commit transaction {}", tx_id);
-            }
+            // used to check if the commit tx in current block, a tx can only spend the txs before it.
+            txids_in_block.insert(tx_id);
 
-            if !check_is_vital_script(tx) {
-                continue;
-            }
+            let resp = if check_is_vital_script(tx) {
+                self.run_vital_tx(env_interface.clone(), index, tx, &txids_in_block)?
+            } else {
+                None
+            };
 
-            if tx.input.is_empty() {
-                log::debug!(target: TARGET, "skip by input is zero");
-                continue;
+            // the resources not handled by the vital scripts will be parked.
+            let parked = park_spent_resources(tx, &env_interface).with_context(|| {
+                alloc::format!("park spent resources by tx {} {}", index, tx_id)
+            })?;
+
+            match (resp, parked) {
+                (Some(mut resp), parked) => {
+                    resp.events.extend(parked);
+                    res.push(resp);
+                }
+                (None, Some(parked)) => res.push(TxRunResponse {
+                    status: TxRunStatus::NotVital,
+                    tx_index: index,
+                    tx_id,
+                    events: alloc::vec![parked],
+                }),
+                (None, None) => {}
             }
+        }
+
+        env_interface.save(self.height).context("save undo record")?;
+
+        Ok(BlockRunResponse { txs: res })
+    }
+
+    /// Undo all the changes by the block at the height, used when the block is disconnected
+    /// by reorg. The blocks should be rollback from the tip one by one.
+    pub fn rollback<Functions>(height: u32, env_interface: Functions) -> Result<()>
+    where
+        Functions: EnvFunctions,
+    {
+        log::info!(target: TARGET, "rollback block {}", height);
+
+        undo::rollback(&env_interface, height)
+            .with_context(|| alloc::format!("rollback {}", height))
+    }
+
+    /// Run the vital tx, all the changes by the tx will be dropped if it run failed,
+    /// return None if the tx is not valid for vital.
+    fn run_vital_tx<Functions>(
+        &self,
+        env_interface: Functions,
+        index: u32,
+        tx: &Transaction,
+        txids_in_block: &BTreeSet<Txid>,
+    ) -> Result<Option<TxRunResponse>>
+    where
+        Functions: EnvFunctions,
+    {
+        let tx_id = tx.txid();
+
+        if tx.input.is_empty() {
+            log::debug!(target: TARGET, "skip by input is zero");
+            return Ok(None);
+        }
+
+        // each script is spent from its own commit output, so check the commit tx by its input.
+        let commits_in_curr_block = tx
+            .input
+            .iter()
+            .enumerate()
+            .filter(|(_, input)| txids_in_block.contains(&input.previous_output.txid))
+            .filter_map(|(input_index, _)| u8::try_from(input_index).ok())
+            .collect::<Vec<_>>();
+        log::debug!(
+            target: TARGET,
+            "process vital tx with the commit txs of inputs {:?} in current block",
+            commits_in_curr_block
+        );
+
+        // all the changes by the tx will be cached, and only commit if the tx run success.
+        let env_journal = EnvJournal::new(env_interface);
+        let context = Context::new(env_journal.clone(), tx, self.height, self.protocol_version)
+            .context("new context")?
+            .with_commits_in_curr_block(commits_in_curr_block);
+        if let Err(err) = context.pre_check() {
+            log::debug!(target: TARGET, "context is not valid by {}", err);
+            return Ok(None);
+        }
 
-            let commit_txid = tx.input[0].previous_output.txid;
-            log::debug!(target: TARGET, "process vital tx with commit txid {}", commit_txid);
+        let resp = match self.run_tx(context, index, tx) {
+            Ok(events) => {
+                env_journal
+                    .commit()
+                    .with_context(|| alloc::format!("commit changes by tx {} {}", index, tx_id))?;
 
-            let context = Context::new(env_interface.clone(), tx, self.height);
-            if let Err(err) = context.pre_check() {
-                log::debug!(target: TARGET, "context is not valid by {}", err);
-                continue;
+                TxRunResponse { status: TxRunStatus::Success, tx_index: index, tx_id, events }
             }
+            Err(err) => {
+                log::warn!(target: TARGET, "tx run failed by {}, drop all its changes", err);
 
-            let resp = match self.run_tx(context, index, tx) {
-                Ok(res) => TxRunResponse {
-                    status: TxRunStatus::Success,
+                TxRunResponse {
+                    status: TxRunStatus::Failed,
                     tx_index: index,
                     tx_id,
-                    resp: res,
-                },
-                Err(err) => {
-                    log::warn!(target: TARGET, "tx run failed by {}", err);
-
-                    TxRunResponse { status: TxRunStatus::Failed, tx_index: index, tx_id, resp: () }
+                    events: Vec::new(),
                 }
-            };
-
-            res.push(resp);
-        }
+            }
+        };
 
-        Ok(BlockRunResponse { txs: res })
+        Ok(Some(resp))
     }
 
     fn run_tx<Functions>(
@@ -100,14 +184,511 @@ impl<'a> BlockRunner<'a> {
         mut context: Context<Functions>,
         _index: u32,
         _tx: &Transaction,
-    ) -> Result<()>
+    ) -> Result<Vec<Event>>
     where
         Functions: EnvFunctions,
     {
         let mut runner = Runner::new();
+        let mut events = Vec::new();
+
+        runner.run_with_sink(&mut context, &mut events).context("run")?;
+
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{collections::BTreeMap, vec};
+
+    use bitcoin::{
+        absolute::LockTime,
+        block::{self, Header},
+        hashes::Hash,
+        transaction::Version,
+        Amount, BlockHash, CompactTarget, OutPoint, ScriptBuf, TxIn, TxMerkleNode, TxOut, Witness,
+    };
+    use vital_script_ops::{
+        builder::instruction::ScriptBuilderFromInstructions,
+        instruction::{
+            assert_input::InstructionInputAssert, assert_output::InstructionOutputAssert,
+            claim_space::InstructionClaimSpace, resource_deploy::InstructionVRC20Deploy,
+            Instruction,
+        },
+    };
+    use vital_script_primitives::{
+        consts::{PROTOCOL_VERSION, PROTOCOL_VERSION_V1, PROTOCOL_VERSION_V2},
+        resources::{Resource, ResourceType, Tag},
+        traits::EnvContext as _,
+        types::vrc20::{VRC20MetaData, VRC20MintMeta},
+        H256,
+    };
+    use vital_script_runner::mock::{assert_err_str, EnvMock};
+
+    use super::*;
+
+    type Snapshot = (BTreeMap<OutPoint, Resource>, BTreeMap<Vec<u8>, Vec<u8>>);
+
+    fn snapshot(env: &EnvMock) -> Snapshot {
+        (
+            env.resource_storage.lock().expect("lock").clone(),
+            env.storage.lock().expect("lock").clone(),
+        )
+    }
+
+    /// A reveal tx with the ops in the first input, the `inputs` will be pushed after it.
+    fn vital_tx(
+        seq: u32,
+        instructions: Vec<Instruction>,
+        inputs: &[OutPoint],
+        outputs: usize,
+    ) -> Transaction {
+        let commit = OutPoint { txid: Txid::hash(&seq.to_le_bytes()), vout: 0 };
+
+        vital_tx_with_scripts(vec![(commit, instructions)], inputs, outputs)
+    }
+
+    /// A reveal tx with each script in an input spent its commit output, by the order of the
+    /// scripts, the `inputs` will be pushed after them.
+    fn vital_tx_with_scripts(
+        scripts: Vec<(OutPoint, Vec<Instruction>)>,
+        inputs: &[OutPoint],
+        outputs: usize,
+    ) -> Transaction {
+        let mut input = scripts
+            .into_iter()
+            .map(|(commit, instructions)| {
+                let ops = ScriptBuilderFromInstructions::build(instructions).expect("build ops");
+
+                let mut script =
+                    [&[0x20_u8][..], &[1; 32], &[0xac, 0x00, 0x63, 0x05], b"vital"].concat();
+                if ops.len() > 75 {
+                    script.push(0x4c);
+                }
+                script.push(ops.len() as u8);
+                script.extend(ops);
+                script.push(0x68);
+
+                let witness = Witness::from_slice(&[vec![0; 64], script, vec![0xc0; 33]]);
+                TxIn { previous_output: commit, witness, ..Default::default() }
+            })
+            .collect::<Vec<_>>();
+        input.extend(inputs.iter().map(|i| TxIn { previous_output: *i, ..Default::default() }));
+
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input,
+            output: (0..outputs)
+                .map(|_| TxOut { value: Amount::from_sat(1000), script_pubkey: ScriptBuf::new() })
+                .collect(),
+        }
+    }
+
+    fn run_block_res(env: &EnvMock, height: u32, txdata: Vec<Transaction>) -> BlockRunResponse {
+        run_block_by_version(env, height, PROTOCOL_VERSION, txdata).expect("run block")
+    }
+
+    fn run_block_by_version(
+        env: &EnvMock,
+        height: u32,
+        protocol_version: u32,
+        txdata: Vec<Transaction>,
+    ) -> Result<BlockRunResponse> {
+        let block = Block {
+            header: Header {
+                version: block::Version::TWO,
+                prev_blockhash: BlockHash::all_zeros(),
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: height,
+                bits: CompactTarget::from_consensus(0),
+                nonce: 0,
+            },
+            txdata,
+        };
+
+        BlockRunner::new(&block, height, protocol_version).run(env.clone())
+    }
+
+    fn run_block(env: &EnvMock, height: u32, txdata: Vec<Transaction>) {
+        let res = run_block_res(env, height, txdata);
+        for tx in res.txs.iter() {
+            assert!(matches!(tx.status, TxRunStatus::Success), "tx {} failed", tx.tx_index);
+        }
+    }
+
+    /// A tx without vital script.
+    fn plain_tx(inputs: &[OutPoint], outputs: usize) -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: inputs
+                .iter()
+                .map(|i| TxIn { previous_output: *i, ..Default::default() })
+                .collect(),
+            output: (0..outputs)
+                .map(|_| TxOut { value: Amount::from_sat(1000), script_pubkey: ScriptBuf::new() })
+                .collect(),
+        }
+    }
+
+    fn output(tx: &Transaction) -> OutPoint {
+        OutPoint { txid: tx.txid(), vout: 0 }
+    }
+
+    fn mint_tx(seq: u32, resource_type: ResourceType) -> Transaction {
+        vital_tx(
+            seq,
+            vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::mint(0, resource_type),
+            ],
+            &[],
+            1,
+        )
+    }
+
+    #[test]
+    fn test_rollback_3_blocks_reorg_should_work() {
+        let env = EnvMock::new();
+        let name = Tag::must_from("abc");
+        let hash = H256::repeat_byte(1);
+
+        let s0 = snapshot(&env);
+
+        // the main chain
+        let mint_name = mint_tx(1, ResourceType::name(name));
+        let mint_vrc721 = mint_tx(2, ResourceType::vrc721(hash));
+        run_block(&env, 10001, vec![mint_name.clone(), mint_vrc721.clone()]);
+        let s1 = snapshot(&env);
+
+        let deploy = vital_tx(
+            3,
+            vec![
+                Instruction::Input(InstructionInputAssert {
+                    index: 1,
+                    resource: Resource::name(name),
+                }),
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::Deploy(InstructionVRC20Deploy {
+                    name_input: 1,
+                    name,
+                    meta: VRC20MetaData {
+                        decimals: 5,
+                        nonce: 1000000,
+                        bworkc: 1000000,
+                        mint: VRC20MintMeta { mint_amount: 1000, mint_height: 0, max_mints: 100 },
+                        meta: None,
+                    },
+                }),
+            ],
+            &[output(&mint_name)],
+            1,
+        );
+        run_block(&env, 10002, vec![deploy]);
+        let s2 = snapshot(&env);
+
+        // the metadata will be updated by the mint.
+        let mint_vrc20 = mint_tx(4, ResourceType::vrc20(name));
+        run_block(&env, 10003, vec![mint_vrc20.clone()]);
+        assert_eq!(
+            env.get_resources(&output(&mint_vrc20)).expect("get"),
+            Some(Resource::vrc20("abc", 1000.into()).expect("vrc20"))
+        );
+
+        // should rollback from the tip
+        assert_err_str(
+            BlockRunner::rollback(10002, env.clone()),
+            "the block 10003 should be rollback first",
+            "rollback not from tip",
+        );
+
+        BlockRunner::rollback(10003, env.clone()).expect("rollback 10003");
+        assert_eq!(snapshot(&env), s2);
+
+        BlockRunner::rollback(10002, env.clone()).expect("rollback 10002");
+        assert_eq!(snapshot(&env), s1);
+
+        BlockRunner::rollback(10001, env.clone()).expect("rollback 10001");
+        assert_eq!(snapshot(&env), s0);
+
+        assert_err_str(
+            BlockRunner::rollback(10001, env.clone()),
+            "not found the undo record for block 10001",
+            "had rollback",
+        );
+
+        // the fork chain, the name can be minted again.
+        let fork_mint_name = mint_tx(5, ResourceType::name(name));
+        run_block(&env, 10001, vec![fork_mint_name.clone()]);
+        run_block(&env, 10002, vec![]);
+        run_block(&env, 10003, vec![mint_tx(6, ResourceType::vrc721(H256::repeat_byte(2)))]);
+
+        assert_eq!(
+            env.get_resources(&output(&fork_mint_name)).expect("get"),
+            Some(Resource::name(name))
+        );
+        assert_eq!(env.get_resources(&output(&mint_name)).expect("get"), None);
+        assert_eq!(env.get_resources(&output(&mint_vrc721)).expect("get"), None);
+
+        // the vrc721 minted in main chain can be minted in the fork.
+        run_block(&env, 10004, vec![mint_tx(7, ResourceType::vrc721(hash))]);
+    }
+
+    #[test]
+    fn test_uncosted_resources_should_handled_by_protocol_version() {
+        let env = EnvMock::new();
+        let name1 = Tag::must_from("abc");
+        let name2 = Tag::must_from("abd");
+
+        let mint_name1 = mint_tx(1, ResourceType::name(name1));
+        let mint_name2 = mint_tx(2, ResourceType::name(name2));
+        run_block(&env, 10001, vec![mint_name1.clone(), mint_name2.clone()]);
+
+        // the name is asserted but not moved.
+        let uncosted_tx = |seq: u32, name: Tag, input: OutPoint| {
+            vital_tx(
+                seq,
+                vec![
+                    Instruction::Input(InstructionInputAssert {
+                        index: 1,
+                        resource: Resource::name(name),
+                    }),
+                    Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                ],
+                &[input],
+                1,
+            )
+        };
+
+        // 1. the v1 will store the uncosted resources into the space.
+        let tx1 = uncosted_tx(3, name1, output(&mint_name1));
+        let res = run_block_by_version(&env, 10002, PROTOCOL_VERSION_V1, vec![tx1.clone()])
+            .expect("run block");
+        assert!(matches!(res.txs[0].status, TxRunStatus::Success));
+        assert_eq!(env.get_resources(&output(&tx1)).expect("get"), None);
+
+        let query = vital_script_runner::EnvContext::new_for_query(env.clone(), 10002);
+        assert_eq!(
+            query.get_space_resources(&tx1.txid()).expect("space"),
+            vec![Resource::name(name1)]
+        );
+
+        // 2. the v2 will return the uncosted resources to the first asserted output.
+        let tx2 = uncosted_tx(4, name2, output(&mint_name2));
+        let res = run_block_by_version(&env, 10003, PROTOCOL_VERSION_V2, vec![tx2.clone()])
+            .expect("run block");
+        assert!(matches!(res.txs[0].status, TxRunStatus::Success));
+        assert_eq!(env.get_resources(&output(&tx2)).expect("get"), Some(Resource::name(name2)));
+        assert!(query.get_space_resources(&tx2.txid()).expect("space").is_empty());
+
+        // 3. the unknown protocol version can not run the vital txs.
+        let tx3 = uncosted_tx(5, name2, output(&tx2));
+        assert_err_str(
+            run_block_by_version(&env, 10004, 0, vec![tx3]),
+            "unknown protocol version 0",
+            "unknown protocol version",
+        );
+    }
+
+    #[test]
+    fn test_dmint_should_check_the_commit_tx_of_each_script() {
+        let env = EnvMock::new();
+
+        // the commit txs, spend different outpoints to make them not the same.
+        let commit_tx =
+            |seq: u32| plain_tx(&[OutPoint { txid: Txid::hash(&seq.to_le_bytes()), vout: 0 }], 1);
+        let dmint_name = |output: u8, name: &str| {
+            vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![output] }),
+                Instruction::dmint(output, ResourceType::name(Tag::must_from(name))),
+            ]
+        };
+        let mint_name = |output: u8, name: &str| {
+            vec![
+                Instruction::Output(InstructionOutputAssert { indexs: vec![output] }),
+                Instruction::mint(output, ResourceType::name(Tag::must_from(name))),
+            ]
+        };
+
+        let commit_prev1 = commit_tx(1);
+        let commit_prev2 = commit_tx(2);
+        run_block(&env, 10001, vec![commit_prev1.clone(), commit_prev2.clone()]);
+
+        // 1. the first script 's commit tx is confirmed before, but the second 's not,
+        //    so the dmint in the second script should failed.
+        let commit_curr1 = commit_tx(3);
+        let reveal1 = vital_tx_with_scripts(
+            vec![
+                (output(&commit_prev1), dmint_name(0, "abc")),
+                (output(&commit_curr1), dmint_name(1, "abd")),
+            ],
+            &[],
+            2,
+        );
+        let res = run_block_res(&env, 10002, vec![commit_curr1, reveal1.clone()]);
+        assert_eq!(res.txs.len(), 1);
+        assert!(matches!(res.txs[0].status, TxRunStatus::Failed));
+        assert_eq!(env.get_resources(&output(&reveal1)).expect("get"), None);
+
+        // 2. the dmint in the script whose commit tx is confirmed before should success, even
+        //    the first script 's commit tx is in the same block.
+        let commit_curr2 = commit_tx(4);
+        let reveal2 = vital_tx_with_scripts(
+            vec![
+                (output(&commit_curr2), mint_name(0, "abc")),
+                (output(&commit_prev2), dmint_name(1, "abd")),
+            ],
+            &[],
+            2,
+        );
+        let res = run_block_res(&env, 10003, vec![commit_curr2, reveal2.clone()]);
+        assert_eq!(res.txs.len(), 1);
+        assert!(matches!(res.txs[0].status, TxRunStatus::Success));
+        assert_eq!(
+            env.get_resources(&output(&reveal2)).expect("get"),
+            Some(Resource::name(Tag::must_from("abc")))
+        );
+        assert_eq!(
+            env.get_resources(&OutPoint { txid: reveal2.txid(), vout: 1 }).expect("get"),
+            Some(Resource::name(Tag::must_from("abd")))
+        );
+    }
+
+    #[test]
+    fn test_undo_records_too_deep_should_be_removed() {
+        let env = EnvMock::new();
+
+        for height in 0..=MAX_ROLLBACK_DEPTH {
+            run_block(&env, height, vec![]);
+        }
+
+        for height in (1..=MAX_ROLLBACK_DEPTH).rev() {
+            BlockRunner::rollback(height, env.clone()).expect("rollback");
+        }
+
+        assert_err_str(
+            BlockRunner::rollback(0, env.clone()),
+            "not found the undo record for block 0",
+            "too deep",
+        );
+    }
+
+    #[test]
+    fn test_resource_spent_by_plain_tx_should_be_parked() {
+        let env = EnvMock::new();
+        let name = Resource::name(Tag::must_from("abc"));
+
+        let mint_name = mint_tx(1, name.resource_type());
+        run_block(&env, 10001, vec![mint_name.clone()]);
+        let s1 = snapshot(&env);
+
+        // spent by a tx without vital script, such as a commit tx or a transfer by mistake.
+        let spent = plain_tx(&[output(&mint_name)], 2);
+        let res = run_block_res(&env, 10002, vec![spent.clone()]);
+
+        assert_eq!(res.txs.len(), 1);
+        assert!(matches!(res.txs[0].status, TxRunStatus::NotVital));
+        assert_eq!(
+            res.txs[0].events,
+            vec![Event::Parked {
+                resources: vec![name.clone()],
+                inputs: vec![output(&mint_name)],
+                space: spent.txid(),
+            }]
+        );
+        assert_eq!(env.get_resources(&output(&mint_name)).expect("get"), None);
+        assert_eq!(env.get_resources(&output(&spent)).expect("get"), None);
+
+        // the tx spent the first output can claim it.
+        let claim = vital_tx(
+            2,
+            vec![
+                Instruction::ClaimSpace(InstructionClaimSpace {
+                    index: 1,
+                    resources: vec![name.clone()],
+                }),
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::move_to(0, name.clone()),
+            ],
+            &[output(&spent)],
+            1,
+        );
+        run_block(&env, 10003, vec![claim.clone()]);
+        assert_eq!(env.get_resources(&output(&claim)).expect("get"), Some(name));
+
+        BlockRunner::rollback(10003, env.clone()).expect("rollback 10003");
+        BlockRunner::rollback(10002, env.clone()).expect("rollback 10002");
+        assert_eq!(snapshot(&env), s1);
+    }
+
+    #[test]
+    fn test_resource_not_handled_by_vital_tx_should_be_parked() {
+        let env = EnvMock::new();
+        let name1 = Resource::name(Tag::must_from("abc"));
+        let name2 = Resource::name(Tag::must_from("abd"));
+
+        let mint_name1 = mint_tx(1, name1.resource_type());
+        let mint_name2 = mint_tx(2, name2.resource_type());
+        run_block(&env, 10001, vec![mint_name1.clone(), mint_name2.clone()]);
+
+        // the tx failed, all the resources in inputs will be parked.
+        let failed = vital_tx(
+            3,
+            vec![
+                Instruction::Input(InstructionInputAssert { index: 1, resource: name2.clone() }),
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::move_to(0, name2.clone()),
+            ],
+            &[output(&mint_name1)],
+            1,
+        );
+
+        // also spent the output of the failed tx, which has no resource.
+        let move_name2 = vital_tx(
+            4,
+            vec![
+                Instruction::Input(InstructionInputAssert { index: 1, resource: name2.clone() }),
+                Instruction::Output(InstructionOutputAssert { indexs: vec![0] }),
+                Instruction::move_to(0, name2.clone()),
+            ],
+            &[output(&mint_name2), output(&failed)],
+            1,
+        );
+
+        let res = run_block_res(&env, 10002, vec![failed.clone(), move_name2.clone()]);
+        assert_eq!(res.txs.len(), 2);
+
+        assert!(matches!(res.txs[0].status, TxRunStatus::Failed));
+        assert_eq!(
+            res.txs[0].events,
+            vec![Event::Parked {
+                resources: vec![name1.clone()],
+                inputs: vec![output(&mint_name1)],
+                space: failed.txid(),
+            }]
+        );
 
-        runner.run(&mut context).context("run")?;
+        assert!(matches!(res.txs[1].status, TxRunStatus::Success));
+        assert!(!res.txs[1].events.iter().any(|e| matches!(e, Event::Parked { .. })));
+        assert_eq!(env.get_resources(&output(&move_name2)).expect("get"), Some(name2.clone()));
 
-        Ok(())
+        // the script not assert the input, so the resource will be parked.
+        let move_name2_again = vital_tx(
+            5,
+            vec![Instruction::Output(InstructionOutputAssert { indexs: vec![0] })],
+            &[output(&move_name2)],
+            1,
+        );
+        let res = run_block_res(&env, 10003, vec![move_name2_again.clone()]);
+        assert!(matches!(res.txs[0].status, TxRunStatus::Success));
+        assert_eq!(
+            res.txs[0].events.last(),
+            Some(&Event::Parked {
+                resources: vec![name2],
+                inputs: vec![output(&move_name2)],
+                space: move_name2_again.txid(),
+            })
+        );
     }
 }
@@ -0,0 +1,168 @@
+//! The undo records for the blocks
+//!
+//! All the changes to the env by a block will be recorded with the values before,
+//! and stored into the env storage by the block height, so the block can be
+//! disconnected by apply the undo records in the reverse order.
+
+use alloc::{rc::Rc, vec::Vec};
+use core::cell::RefCell;
+
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use parity_scale_codec::{Decode, Encode};
+
+use bitcoin::{hashes::Hash, OutPoint, Txid};
+
+use vital_script_primitives::resources::Resource;
+use vital_script_runner::traits::EnvFunctions;
+
+use crate::TARGET;
+
+const STORAGE_KEY_UNDO: &[u8; 4] = b"undo";
+
+/// The max depth of the blocks can be rollback, the undo records for the blocks
+/// deeper than it will be removed.
+pub const MAX_ROLLBACK_DEPTH: u32 = 100;
+
+fn undo_key(height: u32) -> Vec<u8> {
+    [STORAGE_KEY_UNDO.to_vec(), height.to_be_bytes().to_vec()].concat()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+enum UndoOp {
+    /// A resource bind to the outpoint, undo by unbind it.
+    Bind { txid: [u8; 32], vout: u32 },
+    /// A resource unbind from the outpoint, undo by bind it back.
+    Unbind { txid: [u8; 32], vout: u32, resource: Resource },
+    /// The storage value changed, undo by restore the value before.
+    Storage { key: Vec<u8>, before: Option<Vec<u8>> },
+}
+
+impl UndoOp {
+    fn outpoint(txid: &[u8; 32], vout: u32) -> OutPoint {
+        OutPoint { txid: Txid::from_byte_array(*txid), vout }
+    }
+
+    fn undo<Functions: EnvFunctions>(self, env: &Functions) -> Result<()> {
+        match self {
+            Self::Bind { txid, vout } => {
+                let outpoint = Self::outpoint(&txid, vout);
+                env.unbind_resource(&outpoint)
+                    .with_context(|| alloc::format!("unbind {}", outpoint))
+            }
+            Self::Unbind { txid, vout, resource } => {
+                let outpoint = Self::outpoint(&txid, vout);
+                env.bind_resource(outpoint, resource)
+                    .with_context(|| alloc::format!("bind back {}", outpoint))
+            }
+            Self::Storage { key, before: Some(value) } => env.storage_set(key, value),
+            Self::Storage { key, before: None } => env.storage_remove(&key),
+        }
+    }
+}
+
+/// The env functions wrapper which record the undo ops for all the changes.
+#[derive(Clone)]
+pub(crate) struct UndoRecorder<Functions: EnvFunctions> {
+    inner: Functions,
+    ops: Rc<RefCell<Vec<UndoOp>>>,
+}
+
+impl<Functions: EnvFunctions> UndoRecorder<Functions> {
+    pub(crate) fn new(inner: Functions) -> Self {
+        Self { inner, ops: Rc::new(RefCell::new(Vec::new())) }
+    }
+
+    /// Save the undo record for the block into the inner env, and remove the
+    /// one too deep to rollback.
+    pub(crate) fn save(self, height: u32) -> Result<()> {
+        let ops = core::mem::take(&mut *self.ops.borrow_mut());
+
+        log::debug!(target: TARGET, "save {} undo ops for block {}", ops.len(), height);
+
+        self.inner
+            .storage_set(undo_key(height), ops.encode())
+            .context("save undo record")?;
+
+        if let Some(prune) = height.checked_sub(MAX_ROLLBACK_DEPTH) {
+            self.inner.storage_remove(&undo_key(prune)).context("prune undo record")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<Functions: EnvFunctions> EnvFunctions for UndoRecorder<Functions> {
+    fn get_resources(&self, input_id: &OutPoint) -> Result<Option<Resource>> {
+        self.inner.get_resources(input_id)
+    }
+
+    fn maybe_bound(&self, outpoint: &OutPoint) -> bool {
+        self.inner.maybe_bound(outpoint)
+    }
+
+    fn bind_resource(&self, output: OutPoint, res: Resource) -> Result<()> {
+        self.inner.bind_resource(output, res)?;
+
+        let op = UndoOp::Bind { txid: output.txid.to_byte_array(), vout: output.vout };
+        self.ops.borrow_mut().push(op);
+
+        Ok(())
+    }
+
+    fn unbind_resource(&self, input: &OutPoint) -> Result<()> {
+        let resource = self
+            .inner
+            .get_resources(input)?
+            .ok_or_else(|| anyhow!("not found resource to unbind by {}", input))?;
+
+        self.inner.unbind_resource(input)?;
+
+        let op = UndoOp::Unbind { txid: input.txid.to_byte_array(), vout: input.vout, resource };
+        self.ops.borrow_mut().push(op);
+
+        Ok(())
+    }
+
+    fn storage_get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.inner.storage_get(key)
+    }
+
+    fn storage_set(&self, key: Vec<u8>, value: Vec<u8>) -> Result<()> {
+        let before = self.inner.storage_get(&key)?;
+
+        self.inner.storage_set(key.clone(), value)?;
+        self.ops.borrow_mut().push(UndoOp::Storage { key, before });
+
+        Ok(())
+    }
+
+    fn storage_remove(&self, key: &[u8]) -> Result<()> {
+        let before = self.inner.storage_get(key)?;
+
+        self.inner.storage_remove(key)?;
+        self.ops.borrow_mut().push(UndoOp::Storage { key: key.to_vec(), before });
+
+        Ok(())
+    }
+}
+
+/// Undo all the changes by the block, the blocks after it should be rollback first.
+pub(crate) fn rollback<Functions: EnvFunctions>(env: &Functions, height: u32) -> Result<()> {
+    if env.storage_get(&undo_key(height + 1))?.is_some() {
+        bail!("the block {} should be rollback first", height + 1);
+    }
+
+    let record = env
+        .storage_get(&undo_key(height))?
+        .ok_or_else(|| anyhow!("not found the undo record for block {}", height))?;
+    let ops = Vec::<UndoOp>::decode(&mut record.as_slice())
+        .map_err(|err| anyhow!("decode undo record failed by {}", err))?;
+
+    log::debug!(target: TARGET, "rollback {} undo ops for block {}", ops.len(), height);
+
+    for (index, op) in ops.into_iter().enumerate().rev() {
+        op.undo(env).with_context(|| alloc::format!("undo op {}", index))?;
+    }
+
+    env.storage_remove(&undo_key(height)).context("remove undo record")
+}
@@ -5,3 +5,9 @@ pub const DEFAULT_WALLET_NAME: &str = "default";
 
 /// The default wallet name for fee
 pub const FEE_WALLET_NAME: &str = "fee";
+
+/// The env var for the password to unlock the wallets, if not set, will prompt for it.
+pub const WALLET_PASSWORD_ENV: &str = "VITALICALS_WALLET_PASSWORD";
+
+/// The env var for the new password when change the password of wallet.
+pub const WALLET_NEW_PASSWORD_ENV: &str = "VITALICALS_WALLET_NEW_PASSWORD";
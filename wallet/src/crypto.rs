@@ -0,0 +1,171 @@
+//! The encryption for the key of wallet, the key is derived from the password by scrypt,
+//! then encrypt the datas by chacha20poly1305.
+
+use anyhow::{anyhow, Context, Result};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use serde::{Deserialize, Serialize};
+
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+/// The scrypt log_n for encrypt, the tests use a smaller one to run fast.
+#[cfg(not(test))]
+const LOG_N: u8 = scrypt::Params::RECOMMENDED_LOG_N;
+#[cfg(test)]
+const LOG_N: u8 = 10;
+
+/// The encrypted datas with the params to decrypt it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedData {
+    /// The scrypt params
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+    /// The salt for scrypt, in hex
+    pub salt: String,
+    /// The nonce for chacha20poly1305, in hex
+    pub nonce: String,
+    /// The encrypted datas, in hex
+    pub ciphertext: String,
+}
+
+impl EncryptedData {
+    /// Encrypt the datas by the password, with the recommended scrypt params.
+    pub fn encrypt(datas: &[u8], password: &str) -> Result<Self> {
+        Self::encrypt_with_params(
+            datas,
+            password,
+            LOG_N,
+            scrypt::Params::RECOMMENDED_R,
+            scrypt::Params::RECOMMENDED_P,
+        )
+    }
+
+    pub fn encrypt_with_params(
+        datas: &[u8],
+        password: &str,
+        log_n: u8,
+        r: u32,
+        p: u32,
+    ) -> Result<Self> {
+        let mut salt = [0_u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        let cipher = cipher(password, &salt, log_n, r, p)?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, datas)
+            .map_err(|err| anyhow!("encrypt failed by {}", err))?;
+
+        Ok(Self {
+            log_n,
+            r,
+            p,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    /// Decrypt the datas by the password, will failed if the password is invalid.
+    pub fn decrypt(&self, password: &str) -> Result<Vec<u8>> {
+        let salt = hex::decode(&self.salt).context("decode salt")?;
+        let nonce = hex::decode(&self.nonce).context("decode nonce")?;
+        let ciphertext = hex::decode(&self.ciphertext).context("decode ciphertext")?;
+
+        if nonce.len() != 12 {
+            return Err(anyhow!("invalid nonce len {}", nonce.len()));
+        }
+
+        let cipher = cipher(password, &salt, self.log_n, self.r, self.p)?;
+        cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| anyhow!("invalid password"))
+    }
+}
+
+fn cipher(password: &str, salt: &[u8], log_n: u8, r: u32, p: u32) -> Result<ChaCha20Poly1305> {
+    let params = scrypt::Params::new(log_n, r, p, KEY_LEN)
+        .map_err(|err| anyhow!("invalid scrypt params by {}", err))?;
+
+    let mut key = [0_u8; KEY_LEN];
+    scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+        .map_err(|err| anyhow!("derive key failed by {}", err))?;
+
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_and_decrypt_should_work() -> Result<()> {
+        let datas = b"tprv8ZgxMBicQKsPd";
+
+        let encrypted = EncryptedData::encrypt(datas, "password")?;
+        assert_ne!(encrypted.ciphertext, hex::encode(datas));
+        assert_eq!(encrypted.decrypt("password")?, datas.to_vec());
+
+        // the salt and nonce are random, so the same datas will be encrypted differently.
+        let other = EncryptedData::encrypt(datas, "password")?;
+        assert_ne!(other.salt, encrypted.salt);
+        assert_ne!(other.ciphertext, encrypted.ciphertext);
+        assert_eq!(other.decrypt("password")?, datas.to_vec());
+
+        // also can be decrypted after serialized.
+        let json = serde_json::to_string(&encrypted)?;
+        let decoded: EncryptedData = serde_json::from_str(&json)?;
+        assert_eq!(decoded.decrypt("password")?, datas.to_vec());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decrypt_with_wrong_password_should_failed() -> Result<()> {
+        let encrypted = EncryptedData::encrypt(b"tprv8ZgxMBicQKsPd", "password")?;
+
+        for password in ["Password", "password1", ""] {
+            let res = encrypted.decrypt(password);
+            assert_eq!(
+                res.err().expect("should failed by wrong password").to_string(),
+                "invalid password"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decrypt_tampered_datas_should_failed() -> Result<()> {
+        let encrypted = EncryptedData::encrypt(b"tprv8ZgxMBicQKsPd", "password")?;
+
+        let mut ciphertext = hex::decode(&encrypted.ciphertext)?;
+        ciphertext[0] ^= 1;
+        let tampered = EncryptedData { ciphertext: hex::encode(ciphertext), ..encrypted.clone() };
+        assert_eq!(
+            tampered
+                .decrypt("password")
+                .err()
+                .expect("should failed by tampered")
+                .to_string(),
+            "invalid password"
+        );
+
+        let mut salt = hex::decode(&encrypted.salt)?;
+        salt[0] ^= 1;
+        let tampered = EncryptedData { salt: hex::encode(salt), ..encrypted.clone() };
+        assert!(tampered.decrypt("password").is_err());
+
+        let tampered = EncryptedData { nonce: encrypted.nonce[2..].to_string(), ..encrypted };
+        assert_eq!(
+            tampered.decrypt("password").err().expect("should failed by nonce").to_string(),
+            "invalid nonce len 11"
+        );
+
+        Ok(())
+    }
+}
@@ -1,20 +1,80 @@
 //! The wallet stored in file
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::{fs, path::Path};
 
 use bdk::bitcoin::Network;
 
+use crate::{crypto::EncryptedData, password};
+
+const BACKUP_PREFIX: &str = "wallet-backup-";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalletFile {
     pub network: Network,
-    pub xpriv: String,
+    /// The xpriv in plaintext, only for the old wallet file which not encrypted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xpriv: Option<String>,
+    /// The xpriv encrypted by the password.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted: Option<EncryptedData>,
+    /// The public `tr(...)` descriptor for the watch-only wallet, which had no xpriv.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub descriptor: Option<String>,
+    /// The public descriptor for change of the watch-only wallet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_descriptor: Option<String>,
 }
 
 impl WalletFile {
-    pub fn from_wallet(wallet: &crate::wallet::Wallet) -> Self {
-        Self { network: wallet.wallet.network(), xpriv: wallet.xprv.clone() }
+    pub fn encrypt(network: Network, xpriv: &str, password: &str) -> Result<Self> {
+        let encrypted = EncryptedData::encrypt(xpriv.as_bytes(), password).context("encrypt")?;
+
+        Ok(Self {
+            network,
+            xpriv: None,
+            encrypted: Some(encrypted),
+            descriptor: None,
+            change_descriptor: None,
+        })
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted.is_some()
+    }
+
+    pub fn is_watch_only(&self) -> bool {
+        self.descriptor.is_some()
+    }
+
+    /// Got the xpriv, the password is not used if the file is not encrypted.
+    pub fn decrypt(&self, password: &str) -> Result<String> {
+        match (&self.encrypted, &self.xpriv) {
+            (Some(encrypted), _) => {
+                String::from_utf8(encrypted.decrypt(password)?).context("xpriv from utf8")
+            }
+            (None, Some(xpriv)) => Ok(xpriv.clone()),
+            (None, None) => bail!("not found xpriv in wallet file"),
+        }
+    }
+
+    /// Unlock the xpriv, the password is from the env var or prompt.
+    pub fn unlock(&self, name: &str) -> Result<String> {
+        if self.is_watch_only() {
+            bail!("the wallet {} is watch-only", name);
+        }
+
+        if !self.is_encrypted() {
+            println!(
+                "the wallet {} is not encrypted, can use `wallet change-password --wallet {}` to encrypt it",
+                name, name
+            );
+            return self.decrypt("");
+        }
+
+        let password = password::unlock_password(name)?;
+        self.decrypt(&password).with_context(|| format!("unlock wallet {}", name))
     }
 
     pub fn path_to_wallet(root: &Path, name: &str, network: Network) -> std::path::PathBuf {
@@ -35,12 +95,14 @@ impl WalletFile {
 
         root.join(network.to_core_arg())
             .join(name)
-            .join(format!("wallet-backup-{}.json", in_ms))
+            .join(format!("{}{}.json", BACKUP_PREFIX, in_ms))
     }
 
     pub fn load(root: &Path, name: &str, network: Network) -> Result<Self> {
-        let path = Self::path_to_wallet(root, name, network);
+        Self::load_from(&Self::path_to_wallet(root, name, network))
+    }
 
+    fn load_from(path: &Path) -> Result<Self> {
         let res: WalletFile =
             serde_json::from_str(fs::read_to_string(path).context("read file error")?.as_str())
                 .context("json from str")?;
@@ -48,26 +110,275 @@ impl WalletFile {
         Ok(res)
     }
 
-    pub fn save(&self, root: &Path, name: &str) -> Result<()> {
-        let path = Self::path_to_wallet(root, name, self.network);
-
+    fn write_to(&self, path: &Path) -> Result<()> {
         let datas = serde_json::to_string_pretty(self)?;
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+
+        // only the owner can read the wallet file
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        std::io::Write::write_all(&mut options.open(path).context("open")?, datas.as_bytes())
+            .context("write")?;
+
+        // the mode only for the new file, so also set for the old one
+        #[cfg(unix)]
+        fs::set_permissions(path, std::os::unix::fs::PermissionsExt::from_mode(0o600))
+            .context("set permissions")?;
+
+        Ok(())
+    }
+
+    /// Save the xpriv encrypted by the password, if had a old wallet with other key, it will
+    /// be moved to backup, the old key not encrypted will be encrypted by the password.
+    pub fn save(
+        root: &Path,
+        name: &str,
+        network: Network,
+        xpriv: &str,
+        password: &str,
+    ) -> Result<()> {
+        let path = Self::path_to_wallet(root, name, network);
+
         if path.exists() {
-            let old = Self::load(root, name, self.network).context("load for rename");
+            let backup_path = Self::path_to_tmp_wallet(root, name, network);
 
-            let need_backup = match old {
-                Ok(old) => old.xpriv != self.xpriv,
-                Err(_) => true,
-            };
+            match Self::load(root, name, network).context("load for backup") {
+                Ok(old) if !old.is_encrypted() && !old.is_watch_only() => {
+                    let old_xpriv = old.decrypt("")?;
+                    if old_xpriv != xpriv {
+                        log::warn!("the wallet will overwrite the old wallet, so mv to backup.");
+                        Self::encrypt(network, &old_xpriv, password)?
+                            .write_to(&backup_path)
+                            .context("write backup")?;
+                    }
+                }
+                Ok(old) if old.decrypt(password).ok().as_deref() == Some(xpriv) => {}
+                _ => {
+                    log::warn!("the wallet will overwrite the old wallet, so mv to backup.");
+                    fs::rename(path.clone(), backup_path).context("rename to backup")?;
+                }
+            }
+        }
 
-            if need_backup {
-                log::warn!("the wallet will overwrite the old wallet, so mv to backup.");
-                fs::rename(path.clone(), Self::path_to_tmp_wallet(root, name, self.network))
-                    .context("rename to backup")?;
+        Self::encrypt(network, xpriv, password)?.write_to(&path)
+    }
+
+    /// Save the public descriptors for the watch-only wallet, the old wallet will be moved to
+    /// backup if had.
+    pub fn save_watch_only(
+        root: &Path,
+        name: &str,
+        network: Network,
+        descriptor: &str,
+        change_descriptor: Option<&str>,
+    ) -> Result<()> {
+        let path = Self::path_to_wallet(root, name, network);
+
+        if path.exists() {
+            log::warn!("the wallet will overwrite the old wallet, so mv to backup.");
+            fs::rename(path.clone(), Self::path_to_tmp_wallet(root, name, network))
+                .context("rename to backup")?;
+        }
+
+        Self {
+            network,
+            xpriv: None,
+            encrypted: None,
+            descriptor: Some(descriptor.to_string()),
+            change_descriptor: change_descriptor.map(|d| d.to_string()),
+        }
+        .write_to(&path)
+    }
+
+    /// Change the password of the wallet, the wallet and its backups not encrypted will be
+    /// encrypted by the new password.
+    pub fn change_password(root: &Path, name: &str, network: Network) -> Result<()> {
+        let path = Self::path_to_wallet(root, name, network);
+        let old = Self::load_from(&path).context("load wallet file")?;
+        if old.is_watch_only() {
+            bail!("the wallet {} is watch-only, no key to encrypt", name);
+        }
+
+        let xpriv = if old.is_encrypted() {
+            let password = password::unlock_password(name)?;
+            old.decrypt(&password).with_context(|| format!("unlock wallet {}", name))?
+        } else {
+            old.decrypt("")?
+        };
+
+        let password = password::change_password(name)?;
+        Self::encrypt(network, &xpriv, &password)?.write_to(&path)?;
+
+        let dir = path.parent().expect("the wallet file should in dir");
+        for entry in fs::read_dir(dir).context("read wallet dir")? {
+            let backup_path = entry.context("read wallet dir")?.path();
+            let is_backup = backup_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(BACKUP_PREFIX) && name.ends_with(".json"));
+            if !is_backup {
+                continue;
+            }
+
+            let backup = Self::load_from(&backup_path).context("load backup")?;
+            if !backup.is_encrypted() && !backup.is_watch_only() {
+                Self::encrypt(network, &backup.decrypt("")?, &password)?
+                    .write_to(&backup_path)
+                    .context("write backup")?;
             }
         }
 
-        fs::write(path, datas).context("write")?;
+        println!("the password of wallet {} changed", name);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::{WALLET_NEW_PASSWORD_ENV, WALLET_PASSWORD_ENV};
+
+    use super::*;
+
+    const NETWORK: Network = Network::Regtest;
+    const NAME: &str = "test";
+
+    fn backups(root: &Path) -> Result<Vec<WalletFile>> {
+        let path = WalletFile::path_to_wallet(root, NAME, NETWORK);
+        let mut res = Vec::new();
+        for entry in fs::read_dir(path.parent().expect("dir"))? {
+            let path = entry?.path();
+            let name = path.file_name().and_then(|name| name.to_str()).expect("name");
+            if name.starts_with(BACKUP_PREFIX) {
+                res.push(WalletFile::load_from(&path)?);
+            }
+        }
+
+        Ok(res)
+    }
+
+    fn save_plaintext(root: &Path, xpriv: &str) -> Result<()> {
+        WalletFile {
+            network: NETWORK,
+            xpriv: Some(xpriv.to_string()),
+            encrypted: None,
+            descriptor: None,
+            change_descriptor: None,
+        }
+        .write_to(&WalletFile::path_to_wallet(root, NAME, NETWORK))
+    }
+
+    #[test]
+    fn test_save_should_encrypt_the_wallet() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        WalletFile::save(dir.path(), NAME, NETWORK, "xpriv1", "password")?;
+
+        let file = WalletFile::load(dir.path(), NAME, NETWORK)?;
+        assert!(file.is_encrypted());
+        assert_eq!(file.xpriv, None);
+        assert_eq!(file.decrypt("password")?, "xpriv1");
+        assert!(file.decrypt("other").is_err());
+
+        let datas = fs::read_to_string(WalletFile::path_to_wallet(dir.path(), NAME, NETWORK))?;
+        assert!(!datas.contains("xpriv1"));
+
+        // save the same key again will not make a backup.
+        WalletFile::save(dir.path(), NAME, NETWORK, "xpriv1", "password")?;
+        assert!(backups(dir.path())?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_should_migrate_the_plaintext_wallet() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        save_plaintext(dir.path(), "xpriv1")?;
+
+        let file = WalletFile::load(dir.path(), NAME, NETWORK)?;
+        assert!(!file.is_encrypted());
+        assert_eq!(file.decrypt("any")?, "xpriv1");
+
+        WalletFile::save(dir.path(), NAME, NETWORK, "xpriv1", "password")?;
+
+        let file = WalletFile::load(dir.path(), NAME, NETWORK)?;
+        assert!(file.is_encrypted());
+        assert_eq!(file.xpriv, None);
+        assert_eq!(file.decrypt("password")?, "xpriv1");
+        assert!(backups(dir.path())?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_other_key_should_backup_the_old_wallet() -> Result<()> {
+        // the old plaintext wallet will be backup encrypted by the password.
+        let dir = tempfile::tempdir()?;
+        save_plaintext(dir.path(), "xpriv1")?;
+
+        WalletFile::save(dir.path(), NAME, NETWORK, "xpriv2", "password")?;
+
+        let file = WalletFile::load(dir.path(), NAME, NETWORK)?;
+        assert_eq!(file.decrypt("password")?, "xpriv2");
+
+        let backups = backups(dir.path())?;
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].is_encrypted());
+        assert_eq!(backups[0].decrypt("password")?, "xpriv1");
+
+        // the old encrypted wallet will be moved to backup as it is.
+        let dir = tempfile::tempdir()?;
+        WalletFile::save(dir.path(), NAME, NETWORK, "xpriv1", "password1")?;
+
+        WalletFile::save(dir.path(), NAME, NETWORK, "xpriv2", "password2")?;
+
+        let file = WalletFile::load(dir.path(), NAME, NETWORK)?;
+        assert_eq!(file.decrypt("password2")?, "xpriv2");
+
+        let backups = backups(dir.path())?;
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].decrypt("password1")?, "xpriv1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_change_password_should_work() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        // make a plaintext backup by overwrite a plaintext wallet.
+        save_plaintext(dir.path(), "xpriv1")?;
+        fs::rename(
+            WalletFile::path_to_wallet(dir.path(), NAME, NETWORK),
+            WalletFile::path_to_tmp_wallet(dir.path(), NAME, NETWORK),
+        )?;
+        WalletFile::save(dir.path(), NAME, NETWORK, "xpriv2", "password1")?;
+
+        // only this test use the password env vars.
+        std::env::set_var(WALLET_PASSWORD_ENV, "password1");
+        std::env::set_var(WALLET_NEW_PASSWORD_ENV, "password2");
+
+        WalletFile::change_password(dir.path(), NAME, NETWORK)?;
+
+        let file = WalletFile::load(dir.path(), NAME, NETWORK)?;
+        assert_eq!(file.decrypt("password2")?, "xpriv2");
+        assert!(file.decrypt("password1").is_err());
+
+        let backups = backups(dir.path())?;
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].is_encrypted());
+        assert_eq!(backups[0].decrypt("password2")?, "xpriv1");
+
+        // the wrong password can not unlock the wallet to change.
+        std::env::set_var(WALLET_PASSWORD_ENV, "password1");
+        std::env::set_var(WALLET_NEW_PASSWORD_ENV, "password3");
+
+        assert!(WalletFile::change_password(dir.path(), NAME, NETWORK).is_err());
+        let file = WalletFile::load(dir.path(), NAME, NETWORK)?;
+        assert_eq!(file.decrypt("password2")?, "xpriv2");
 
         Ok(())
     }
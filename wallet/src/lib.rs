@@ -1,7 +1,9 @@
+mod crypto;
 mod database;
 mod file;
+mod password;
 mod wallet;
 
-pub use crate::{file::WalletFile, wallet::Wallet};
+pub use crate::{crypto::EncryptedData, file::WalletFile, wallet::Wallet};
 
 pub mod consts;
@@ -0,0 +1,79 @@
+//! Got the password for the wallet, from the env var or prompt.
+
+use anyhow::{bail, Context, Result};
+
+use crate::consts::{WALLET_NEW_PASSWORD_ENV, WALLET_PASSWORD_ENV};
+
+/// The password to unlock the wallet.
+pub fn unlock_password(name: &str) -> Result<String> {
+    if let Ok(password) = std::env::var(WALLET_PASSWORD_ENV) {
+        return Ok(password);
+    }
+
+    rpassword::prompt_password(format!("password for wallet {}: ", name)).context("read password")
+}
+
+/// The new password to encrypt the wallet, if prompt, need input twice.
+pub fn new_password(name: &str, env: &str) -> Result<String> {
+    let password = match std::env::var(env) {
+        Ok(password) => password,
+        Err(_) => {
+            let password =
+                rpassword::prompt_password(format!("new password for wallet {}: ", name))
+                    .context("read password")?;
+            let confirm =
+                rpassword::prompt_password(format!("confirm password for wallet {}: ", name))
+                    .context("read password")?;
+
+            if password != confirm {
+                bail!("the passwords not match");
+            }
+
+            password
+        }
+    };
+
+    if password.is_empty() {
+        bail!("the password should not be empty");
+    }
+
+    Ok(password)
+}
+
+/// The password for create a wallet.
+pub fn create_password(name: &str) -> Result<String> {
+    new_password(name, WALLET_PASSWORD_ENV)
+}
+
+/// The new password for change the password of the wallet.
+pub fn change_password(name: &str) -> Result<String> {
+    new_password(name, WALLET_NEW_PASSWORD_ENV)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // each test use its own env var, as the tests run in parallel.
+
+    #[test]
+    fn test_new_password_from_env_should_work() -> Result<()> {
+        let env = "VITALICALS_TEST_NEW_PASSWORD";
+        std::env::set_var(env, "password");
+
+        assert_eq!(new_password("test", env)?, "password");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_password_should_not_be_empty() {
+        let env = "VITALICALS_TEST_EMPTY_PASSWORD";
+        std::env::set_var(env, "");
+
+        assert_eq!(
+            new_password("test", env).err().expect("should failed by empty").to_string(),
+            "the password should not be empty"
+        );
+    }
+}
@@ -5,7 +5,7 @@ use std::{path::Path, str::FromStr, time::UNIX_EPOCH};
 
 use bdk::{
     bitcoin::{
-        bip32::{self, DerivationPath, ExtendedPrivKey},
+        bip32::{self, DerivationPath, ExtendedPrivKey, Fingerprint},
         secp256k1::{All, Secp256k1, XOnlyPublicKey},
         Network,
     },
@@ -16,18 +16,23 @@ use bdk::{
         bip39::{Language, Mnemonic, WordCount},
         DerivableKey, ExtendedKey, GeneratableKey, GeneratedKey,
     },
-    miniscript::{self, Descriptor},
+    miniscript::{
+        self,
+        descriptor::{DescriptorPublicKey, SinglePubKey},
+        Descriptor,
+    },
     template::Bip86,
     KeychainKind, SyncOptions, Wallet as BdkWallet,
 };
 
-use crate::{database::*, file::WalletFile};
+use crate::{database::*, file::WalletFile, password};
 
 /// Wallet
 pub struct Wallet {
     pub name: String,
-    pub xprv: String,
-    pub xpriv: ExtendedPrivKey,
+    /// The xpriv, None if the wallet is watch-only.
+    pub xprv: Option<String>,
+    pub xpriv: Option<ExtendedPrivKey>,
     pub wallet: BdkWallet<AnyDatabase>,
     pub blockchain: AnyBlockchain,
     pub synced: bool,
@@ -41,7 +46,14 @@ impl Wallet {
         blockchain: AnyBlockchain,
     ) -> Result<Self> {
         let xpriv = ExtendedPrivKey::from_str(xprv.as_str()).context("ExtendedPrivKey from str")?;
-        Ok(Self { name: name.to_string(), xprv, xpriv, wallet, blockchain, synced: false })
+        Ok(Self {
+            name: name.to_string(),
+            xprv: Some(xprv),
+            xpriv: Some(xpriv),
+            wallet,
+            blockchain,
+            synced: false,
+        })
     }
 
     pub fn create(
@@ -69,6 +81,9 @@ impl Wallet {
         mnemonic_words: String,
         forced_sync: bool,
     ) -> Result<Self> {
+        // the wallet file will be encrypted by the password
+        let password = password::create_password(name)?;
+
         // clean database datas.
         rm_database(network, root, name)?;
 
@@ -98,17 +113,59 @@ impl Wallet {
         );
 
         let mut res = Self::create_from_wallet(name, xprv.to_string(), wallet, blockchain)?;
-        res.save(root)?;
+        res.save(root, &password)?;
 
         res.synced = synced;
 
         Ok(res)
     }
 
-    pub fn save(&self, root: &Path) -> Result<()> {
-        let to_file = WalletFile::from_wallet(self);
+    pub fn save(&self, root: &Path, password: &str) -> Result<()> {
+        let xprv = self
+            .xprv
+            .as_ref()
+            .ok_or_else(|| anyhow!("the wallet {} is watch-only", self.name))?;
+
+        WalletFile::save(root, &self.name, self.network(), xprv, password)
+    }
+
+    /// Create a watch-only wallet by the public `tr(...)` descriptors, it can build the txs
+    /// but can not sign them.
+    pub fn import_watch_only(
+        network: Network,
+        endpoint: String,
+        root: &std::path::PathBuf,
+        name: &str,
+        descriptor: &str,
+        change_descriptor: Option<&str>,
+        forced_sync: bool,
+    ) -> Result<Self> {
+        // clean database datas.
+        rm_database(network, root, name)?;
+
+        let (wallet, blockchain, synced) = Self::load_wallet(
+            network,
+            endpoint,
+            root,
+            name,
+            descriptor,
+            change_descriptor,
+            forced_sync,
+        )
+        .context("load_wallet")?;
+
+        if !matches!(wallet.get_descriptor_for_keychain(KeychainKind::External), Descriptor::Tr(_))
+        {
+            bail!("the watch-only wallet only support tr descriptor");
+        }
+
+        if !wallet.get_signers(KeychainKind::External).signers().is_empty() {
+            bail!("the descriptor for watch-only wallet should not contain private key");
+        }
+
+        WalletFile::save_watch_only(root, name, network, descriptor, change_descriptor)?;
 
-        to_file.save(root, &self.name)
+        Ok(Self { name: name.to_string(), xprv: None, xpriv: None, wallet, blockchain, synced })
     }
 
     pub fn load(
@@ -119,7 +176,30 @@ impl Wallet {
         forced_sync: bool,
     ) -> Result<Self> {
         let from_file = WalletFile::load(root, name, network).context("load file failed")?;
-        let xpriv = bip32::ExtendedPrivKey::from_str(from_file.xpriv.as_str()).unwrap();
+        if let Some(descriptor) = from_file.descriptor.as_deref() {
+            let (wallet, blockchain, synced) = Self::load_wallet(
+                network,
+                endpoint,
+                root,
+                name,
+                descriptor,
+                from_file.change_descriptor.as_deref(),
+                forced_sync,
+            )
+            .context("load watch-only wallet")?;
+
+            return Ok(Self {
+                name: name.to_string(),
+                xprv: None,
+                xpriv: None,
+                wallet,
+                blockchain,
+                synced,
+            });
+        }
+
+        let xprv = from_file.unlock(name)?;
+        let xpriv = bip32::ExtendedPrivKey::from_str(xprv.as_str()).context("parse xpriv")?;
 
         let (wallet, blockchain, synced) = Self::load_wallet(
             network,
@@ -134,8 +214,8 @@ impl Wallet {
 
         Ok(Self {
             name: name.to_string(),
-            xpriv,
-            xprv: from_file.xpriv,
+            xpriv: Some(xpriv),
+            xprv: Some(xprv),
             wallet,
             blockchain,
             synced,
@@ -193,20 +273,36 @@ impl Wallet {
 }
 
 impl Wallet {
-    pub fn full_derivation_path(&self) -> Result<DerivationPath> {
+    fn internal_key(&self) -> Result<DescriptorPublicKey> {
         let descriptor = self.wallet.get_descriptor_for_keychain(KeychainKind::External);
 
         let tr = match descriptor {
             Descriptor::Tr(tr) => tr,
             _ => bail!("not tr descriptor"),
         };
-        let derivation_path = tr.internal_key().full_derivation_path().unwrap();
+
+        Ok(tr.internal_key().clone())
+    }
+
+    pub fn full_derivation_path(&self) -> Result<DerivationPath> {
+        let derivation_path = self.internal_key()?.full_derivation_path().unwrap();
 
         Ok(derivation_path)
     }
 
-    pub fn xpriv(&self) -> &ExtendedPrivKey {
-        &self.xpriv
+    /// The fingerprint of the master key, for the watch-only wallet it is from the descriptor.
+    pub fn master_fingerprint(&self) -> Result<Fingerprint> {
+        Ok(self.internal_key()?.master_fingerprint())
+    }
+
+    pub fn is_watch_only(&self) -> bool {
+        self.xpriv.is_none()
+    }
+
+    pub fn xpriv(&self) -> Result<&ExtendedPrivKey> {
+        self.xpriv
+            .as_ref()
+            .ok_or_else(|| anyhow!("the wallet {} is watch-only", self.name))
     }
 
     pub fn flush(&self) -> Result<()> {
@@ -221,12 +317,28 @@ impl Wallet {
     }
 
     pub fn derive_x_only_public_key(&self, secp: &Secp256k1<All>) -> Result<XOnlyPublicKey> {
+        let xpriv = match &self.xpriv {
+            Some(xpriv) => xpriv,
+            None => {
+                // the watch-only wallet derive it from the xpub in descriptor
+                let internal_key = match self.internal_key()? {
+                    DescriptorPublicKey::XPub(xpub) => {
+                        xpub.xkey.derive_pub(secp, &xpub.derivation_path)?.to_x_only_pub()
+                    }
+                    DescriptorPublicKey::Single(single) => match single.key {
+                        SinglePubKey::XOnly(key) => key,
+                        SinglePubKey::FullKey(key) => key.inner.x_only_public_key().0,
+                    },
+                    _ => bail!("not supported internal key"),
+                };
+
+                return Ok(internal_key);
+            }
+        };
+
         let derivation_path = self.full_derivation_path().context("get full derivation")?;
-        let (internal_key, _) = self
-            .xpriv
-            .derive_priv(secp, &derivation_path)?
-            .to_keypair(secp)
-            .x_only_public_key();
+        let (internal_key, _) =
+            xpriv.derive_priv(secp, &derivation_path)?.to_keypair(secp).x_only_public_key();
 
         Ok(internal_key)
     }